@@ -14,13 +14,22 @@ use markdown_preview::markdown_preview_view::{MarkdownPreviewMode, MarkdownPrevi
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_derive::Serialize;
-use smol::{fs, io::AsyncReadExt};
+use sha2::{Digest, Sha256};
+use smol::{
+    fs,
+    io::{AsyncReadExt, AsyncWriteExt},
+    stream::StreamExt as _,
+};
 
 use settings::{Settings, SettingsSources, SettingsStore};
 use smol::{fs::File, process::Command};
 
-use http_client::{AsyncBody, HttpClient, HttpClientWithUrl};
+use http_client::{
+    http::header, AsyncBody, HttpClient, HttpClientWithUrl, HttpRequestExt, RedirectPolicy,
+    Request, StatusCode, Uri,
+};
 use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use reqwest_client::ReqwestClient;
 use std::{
     env::{
         self,
@@ -29,7 +38,7 @@ use std::{
     ffi::OsString,
     path::{Path, PathBuf},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant, SystemTime},
 };
 use update_notification::UpdateNotification;
 use util::ResultExt;
@@ -38,6 +47,14 @@ use workspace::Workspace;
 
 const SHOULD_SHOW_UPDATE_NOTIFICATION_KEY: &str = "auto-updater-should-show-updated-notification";
 const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Minimum time between two progress reports while downloading.
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// How many times an interrupted remote server download is resumed before giving up.
+const MAX_DOWNLOAD_ATTEMPTS: usize = 3;
+/// How long a remote server release check is trusted before asking for the latest version again.
+const REMOTE_SERVER_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+/// Name of the file in each remote server platform directory recording the latest known version.
+const REMOTE_SERVER_LATEST_FILE: &str = "latest";
 
 actions!(
     auto_update,
@@ -58,6 +75,14 @@ struct UpdateRequestBody {
     destination: &'static str,
 }
 
+/// Bytes received so far by an in-flight download.
+#[derive(Clone, Copy, Debug)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    /// Size of the whole download, when the server reports it.
+    pub total: Option<u64>,
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub enum AutoUpdateStatus {
     Idle,
@@ -85,6 +110,9 @@ pub struct AutoUpdater {
 struct JsonRelease {
     version: String,
     url: String,
+    /// Hex-encoded SHA256 of the asset, when the release server publishes one.
+    #[serde(default)]
+    sha256: Option<String>,
 }
 
 struct MacOsUnmounter {
@@ -433,7 +461,10 @@ impl AutoUpdater {
     pub async fn get_latest_remote_server_release(
         os: &str,
         arch: &str,
-        mut release_channel: ReleaseChannel,
+        release_channel: ReleaseChannel,
+        proxy: Option<String>,
+        base_url: Option<String>,
+        on_progress: &mut dyn FnMut(DownloadProgress, &mut AsyncAppContext),
         cx: &mut AsyncAppContext,
     ) -> Result<PathBuf> {
         let this = cx.update(|cx| {
@@ -443,45 +474,133 @@ impl AutoUpdater {
                 .ok_or_else(|| anyhow!("auto-update not initialized"))
         })??;
 
-        if release_channel == ReleaseChannel::Dev {
-            release_channel = ReleaseChannel::Nightly;
+        let release_channel = remote_server_release_channel(release_channel);
+        let platform_dir = remote_server_platform_dir(os, arch, release_channel);
+
+        if let Some(version_path) = fresh_cached_remote_server(&platform_dir).await {
+            log::info!("using cached zed-remote-server {os} {arch}: {version_path:?}");
+            return Ok(version_path);
         }
 
+        let client = this.read_with(cx, |this, _| this.http_client.clone())?;
+        let client = match proxy {
+            Some(proxy) => Arc::new(http_client_with_proxy(&client, &proxy, cx)?),
+            None => client,
+        };
+        // Mirrors serve the same releases API as the public update endpoint.
+        let client = match base_url {
+            Some(base_url) => {
+                let proxy = client.proxy().cloned();
+                Arc::new(HttpClientWithUrl::new_uri(client, base_url, proxy))
+            }
+            None => client,
+        };
+
         let release = Self::get_latest_release(
-            &this,
+            &client,
             "zed-remote-server",
             os,
             arch,
             Some(release_channel),
-            cx,
         )
         .await?;
 
-        let servers_dir = paths::remote_servers_dir();
-        let channel_dir = servers_dir.join(release_channel.dev_name());
-        let platform_dir = channel_dir.join(format!("{}-{}", os, arch));
-        let version_path = platform_dir.join(format!("{}.gz", release.version));
+        let version = release.version.clone();
+        let version_path = platform_dir.join(format!("{}.gz", version));
         smol::fs::create_dir_all(&platform_dir).await.ok();
 
-        let client = this.read_with(cx, |this, _| this.http_client.clone())?;
-
-        if smol::fs::metadata(&version_path).await.is_err() {
+        if !verify_cached_remote_server(&version_path).await {
+            let expected_sha256 = release.sha256.clone();
             log::info!("downloading zed-remote-server {os} {arch}");
-            download_remote_server_binary(&version_path, release, client, cx).await?;
+            // Download next to the final path and rename, so an interrupted
+            // download never leaves a truncated binary in the cache.
+            let download_path = version_path.with_extension("gz.partial");
+            download_remote_server_binary(&download_path, release, client, on_progress, cx).await?;
+            // Releases that predate published checksums are used as before.
+            if let Some(expected_sha256) = &expected_sha256 {
+                if let Err(error) = verify_sha256(&download_path, expected_sha256).await {
+                    smol::fs::remove_file(&download_path).await.log_err();
+                    return Err(error);
+                }
+                smol::fs::write(remote_server_checksum_path(&version_path), expected_sha256)
+                    .await?;
+            } else {
+                log::warn!("no checksum published for zed-remote-server {version}");
+                smol::fs::remove_file(remote_server_checksum_path(&version_path))
+                    .await
+                    .ok();
+            }
+            smol::fs::rename(&download_path, &version_path).await?;
         }
 
+        smol::fs::write(platform_dir.join(REMOTE_SERVER_LATEST_FILE), version)
+            .await
+            .log_err();
+
         Ok(version_path)
     }
 
+    /// Returns a previously downloaded remote server binary for the given
+    /// platform, for use when the latest release can't be checked (e.g. while
+    /// offline), along with its version. Prefers `version` and otherwise picks
+    /// the newest download that still matches its checksum.
+    pub async fn cached_remote_server_release(
+        os: &str,
+        arch: &str,
+        release_channel: ReleaseChannel,
+        version: SemanticVersion,
+    ) -> Option<(PathBuf, SemanticVersion)> {
+        let release_channel = remote_server_release_channel(release_channel);
+        let platform_dir = remote_server_platform_dir(os, arch, release_channel);
+
+        let version_path = platform_dir.join(format!("{}.gz", version));
+        if verify_cached_remote_server(&version_path).await {
+            return Some((version_path, version));
+        }
+
+        let mut newest: Option<(SystemTime, PathBuf, SemanticVersion)> = None;
+        let mut entries = smol::fs::read_dir(&platform_dir).await.ok()?;
+        while let Some(entry) = entries.next().await {
+            let Ok(entry) = entry else { continue };
+            let path = entry.path();
+            if path.extension().map_or(true, |extension| extension != "gz") {
+                continue;
+            }
+            let Some(version) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<SemanticVersion>().ok())
+            else {
+                continue;
+            };
+            let Some(modified) = entry
+                .metadata()
+                .await
+                .ok()
+                .and_then(|metadata| metadata.modified().ok())
+            else {
+                continue;
+            };
+            if newest
+                .as_ref()
+                .map_or(true, |(newest, _, _)| modified > *newest)
+            {
+                newest = Some((modified, path, version));
+            }
+        }
+        let (_, path, version) = newest?;
+        verify_cached_remote_server(&path)
+            .await
+            .then_some((path, version))
+    }
+
     async fn get_latest_release(
-        this: &Model<Self>,
+        client: &HttpClientWithUrl,
         asset: &str,
         os: &str,
         arch: &str,
         release_channel: Option<ReleaseChannel>,
-        cx: &mut AsyncAppContext,
     ) -> Result<JsonRelease> {
-        let client = this.read_with(cx, |this, _| this.http_client.clone())?;
         let mut url_string = client.build_url(&format!(
             "/api/releases/latest?asset={}&os={}&arch={}",
             asset, os, arch
@@ -526,8 +645,7 @@ impl AutoUpdater {
             )
         })?;
 
-        let release =
-            Self::get_latest_release(&this, "zed", OS, ARCH, release_channel, &mut cx).await?;
+        let release = Self::get_latest_release(&client, "zed", OS, ARCH, release_channel).await?;
 
         let should_download = match *RELEASE_CHANNEL {
             ReleaseChannel::Nightly => cx
@@ -615,13 +733,107 @@ impl AutoUpdater {
     }
 }
 
+/// Remote server builds aren't published for dev, so dev builds use nightly servers.
+fn remote_server_release_channel(release_channel: ReleaseChannel) -> ReleaseChannel {
+    if release_channel == ReleaseChannel::Dev {
+        ReleaseChannel::Nightly
+    } else {
+        release_channel
+    }
+}
+
+fn remote_server_platform_dir(os: &str, arch: &str, release_channel: ReleaseChannel) -> PathBuf {
+    paths::remote_servers_dir()
+        .join(release_channel.dev_name())
+        .join(format!("{}-{}", os, arch))
+}
+
+/// Builds a client that talks to the same server as `client`, but through `proxy`.
+fn http_client_with_proxy(
+    client: &HttpClientWithUrl,
+    proxy: &str,
+    cx: &AsyncAppContext,
+) -> Result<HttpClientWithUrl> {
+    let proxy_uri = proxy
+        .parse::<Uri>()
+        .with_context(|| format!("invalid proxy url {:?}", proxy))?;
+    let version = cx.update(|cx| AppVersion::global(cx))?;
+    let user_agent = format!("Zed/{} ({}; {})", version, OS, ARCH);
+    let http = ReqwestClient::proxy_and_user_agent(Some(proxy_uri.clone()), &user_agent)?;
+    Ok(HttpClientWithUrl::new_uri(
+        Arc::new(http),
+        client.base_url(),
+        Some(proxy_uri),
+    ))
+}
+
+/// Returns the cached remote server binary for a platform directory if the
+/// latest version was checked within [`REMOTE_SERVER_CACHE_TTL`].
+async fn fresh_cached_remote_server(platform_dir: &Path) -> Option<PathBuf> {
+    let latest_path = platform_dir.join(REMOTE_SERVER_LATEST_FILE);
+    let checked_at = smol::fs::metadata(&latest_path)
+        .await
+        .ok()?
+        .modified()
+        .ok()?;
+    if checked_at.elapsed().ok()? > REMOTE_SERVER_CACHE_TTL {
+        return None;
+    }
+    let version = smol::fs::read_to_string(&latest_path).await.ok()?;
+    let version_path = platform_dir.join(format!("{}.gz", version.trim()));
+    verify_cached_remote_server(&version_path)
+        .await
+        .then_some(version_path)
+}
+
+/// The checksum of a cached remote server binary, stored when it was downloaded.
+fn remote_server_checksum_path(version_path: &Path) -> PathBuf {
+    version_path.with_extension("gz.sha256")
+}
+
+/// Checks a cached remote server binary against the checksum stored next to
+/// it, removing it from the cache when it doesn't match. Binaries downloaded
+/// without a published checksum have none stored, and are kept as they are.
+async fn verify_cached_remote_server(version_path: &Path) -> bool {
+    if smol::fs::metadata(version_path).await.is_err() {
+        return false;
+    }
+    let checksum_path = remote_server_checksum_path(version_path);
+    let Ok(expected) = smol::fs::read_to_string(&checksum_path).await else {
+        return true;
+    };
+    match verify_sha256(version_path, &expected).await {
+        Ok(()) => true,
+        Err(error) => {
+            log::warn!("discarding cached remote server {version_path:?}: {error}");
+            smol::fs::remove_file(version_path).await.log_err();
+            smol::fs::remove_file(&checksum_path).await.ok();
+            false
+        }
+    }
+}
+
+async fn verify_sha256(path: &Path, expected: &str) -> Result<()> {
+    let contents = smol::fs::read(path).await?;
+    let actual = hex::encode(Sha256::digest(&contents));
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        Err(anyhow!(
+            "checksum mismatch for remote server binary {:?} (expected {}, got {})",
+            path,
+            expected.trim(),
+            actual
+        ))?;
+    }
+    Ok(())
+}
+
 async fn download_remote_server_binary(
     target_path: &PathBuf,
     release: JsonRelease,
     client: Arc<HttpClientWithUrl>,
-    cx: &AsyncAppContext,
+    on_progress: &mut dyn FnMut(DownloadProgress, &mut AsyncAppContext),
+    cx: &mut AsyncAppContext,
 ) -> Result<()> {
-    let mut target_file = File::create(&target_path).await?;
     let (installation_id, release_channel, telemetry_enabled, is_staff) = cx.update(|cx| {
         let telemetry = Client::global(cx).telemetry().clone();
         let is_staff = telemetry.is_staff();
@@ -637,16 +849,101 @@ async fn download_remote_server_binary(
             is_staff,
         )
     })?;
-    let request_body = AsyncBody::from(serde_json::to_string(&UpdateRequestBody {
+    let request_body = serde_json::to_string(&UpdateRequestBody {
         installation_id,
         release_channel,
         telemetry: telemetry_enabled,
         is_staff,
         destination: "remote",
-    })?);
+    })?;
 
-    let mut response = client.get(&release.url, request_body, true).await?;
-    smol::io::copy(response.body_mut(), &mut target_file).await?;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let result = resume_download(
+            target_path,
+            &release.url,
+            request_body.clone(),
+            &client,
+            on_progress,
+            cx,
+        )
+        .await;
+        match result {
+            Ok(()) => return Ok(()),
+            Err(error) if attempt < MAX_DOWNLOAD_ATTEMPTS => {
+                log::warn!("remote server download interrupted, resuming: {:?}", error);
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Downloads `url` into `target_path`, continuing after the bytes already
+/// there when the server supports range requests.
+async fn resume_download(
+    target_path: &Path,
+    url: &str,
+    body: String,
+    client: &HttpClientWithUrl,
+    on_progress: &mut dyn FnMut(DownloadProgress, &mut AsyncAppContext),
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let resume_from = smol::fs::metadata(target_path)
+        .await
+        .map_or(0, |metadata| metadata.len());
+    let mut request = Request::builder()
+        .uri(url)
+        .follow_redirects(RedirectPolicy::FollowAll);
+    if resume_from > 0 {
+        request = request.header(header::RANGE, format!("bytes={}-", resume_from));
+    }
+    let mut response = client.send(request.body(AsyncBody::from(body))?).await?;
+
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        // What we have doesn't line up with the server's file, so start over.
+        smol::fs::remove_file(target_path).await?;
+        Err(anyhow!("could not resume download of {}", url))?;
+    }
+    if !response.status().is_success() {
+        Err(anyhow!("failed to download {}: {}", url, response.status()))?;
+    }
+
+    let content_length: Option<u64> = response
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok()?.parse().ok());
+    let (mut target_file, downloaded) = if response.status() == StatusCode::PARTIAL_CONTENT {
+        log::info!("resuming download of {} at {} bytes", url, resume_from);
+        let file = smol::fs::OpenOptions::new()
+            .append(true)
+            .open(target_path)
+            .await?;
+        (file, resume_from)
+    } else {
+        (File::create(target_path).await?, 0)
+    };
+
+    let mut progress = DownloadProgress {
+        downloaded,
+        total: content_length.map(|length| downloaded + length),
+    };
+    let mut last_reported_at: Option<Instant> = None;
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let bytes_read = response.body_mut().read(&mut buffer).await?;
+        if bytes_read == 0 {
+            break;
+        }
+        target_file.write_all(&buffer[..bytes_read]).await?;
+        progress.downloaded += bytes_read as u64;
+        if last_reported_at.map_or(true, |at| at.elapsed() >= DOWNLOAD_PROGRESS_INTERVAL) {
+            on_progress(progress, cx);
+            last_reported_at = Some(Instant::now());
+        }
+    }
+    target_file.flush().await?;
+    on_progress(progress, cx);
     Ok(())
 }
 
@@ -18,6 +18,8 @@ pub enum CliRequest {
         dev_server_token: Option<String>,
         env: Option<HashMap<String, String>>,
     },
+    /// Print the recently opened local and ssh projects, most recent first.
+    ListRecent,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -56,6 +56,12 @@ struct Args {
     /// Run zed in dev-server mode
     #[arg(long)]
     dev_server_token: Option<String>,
+    /// Print the recently opened projects, most recent first, one per line.
+    ///
+    /// Local projects are printed as paths and remote ones as ssh:// URLs.
+    /// The folders of a project with several of them are separated by tabs.
+    #[arg(long)]
+    list_recent: bool,
 }
 
 fn parse_path_with_position(argument_str: &str) -> anyhow::Result<String> {
@@ -157,14 +163,18 @@ fn main() -> Result<()> {
             let (_, handshake) = server.accept().context("Handshake after Zed spawn")?;
             let (tx, rx) = (handshake.requests, handshake.responses);
 
-            tx.send(CliRequest::Open {
-                paths,
-                urls,
-                wait: args.wait,
-                open_new_workspace,
-                dev_server_token: args.dev_server_token,
-                env,
-            })?;
+            if args.list_recent {
+                tx.send(CliRequest::ListRecent)?;
+            } else {
+                tx.send(CliRequest::Open {
+                    paths,
+                    urls,
+                    wait: args.wait,
+                    open_new_workspace,
+                    dev_server_token: args.dev_server_token,
+                    env,
+                })?;
+            }
 
             while let Ok(response) = rx.recv() {
                 match response {
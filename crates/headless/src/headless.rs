@@ -201,13 +201,19 @@ impl DevServer {
         let fs = cx.read_model(&this, |this, _| this.app_state.fs.clone())?;
 
         let mut entries = Vec::new();
+        let mut entry_is_dir = Vec::new();
         let mut response = fs.read_dir(Path::new(&expanded)).await?;
         while let Some(path) = response.next().await {
-            if let Some(file_name) = path?.file_name() {
+            let path = path?;
+            if let Some(file_name) = path.file_name() {
                 entries.push(file_name.to_string_lossy().to_string());
+                entry_is_dir.push(fs.is_dir(&path).await);
             }
         }
-        Ok(proto::ListRemoteDirectoryResponse { entries })
+        Ok(proto::ListRemoteDirectoryResponse {
+            entries,
+            entry_is_dir,
+        })
     }
 
     async fn handle_shutdown(
@@ -263,6 +263,20 @@ pub fn remote_servers_dir() -> &'static PathBuf {
     REMOTE_SERVERS_DIR.get_or_init(|| support_dir().join("remote_servers"))
 }
 
+/// Returns the path to the directory holding the control sockets of ssh
+/// connections shared between windows.
+pub fn ssh_control_sockets_dir() -> &'static PathBuf {
+    static SSH_CONTROL_SOCKETS_DIR: OnceLock<PathBuf> = OnceLock::new();
+    SSH_CONTROL_SOCKETS_DIR.get_or_init(|| temp_dir().join("ssh"))
+}
+
+/// Returns the path to the directory holding a log file per remote host,
+/// recording the output of each connection attempt.
+pub fn remote_connection_logs_dir() -> &'static PathBuf {
+    static REMOTE_CONNECTION_LOGS_DIR: OnceLock<PathBuf> = OnceLock::new();
+    REMOTE_CONNECTION_LOGS_DIR.get_or_init(|| support_dir().join("remote_connection_logs"))
+}
+
 /// Returns the relative path to a `.zed` folder within a project.
 pub fn local_settings_folder_relative_path() -> &'static Path {
     Path::new(".zed")
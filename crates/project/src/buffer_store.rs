@@ -1448,6 +1448,110 @@ impl BufferStore {
         })?
     }
 
+    /// Recreates a buffer that the ssh client had open before the server was
+    /// restarted, from the state and operations it sent, so that the client can
+    /// keep editing it. The file is loaded again to attach the buffer to this
+    /// server's worktree.
+    pub async fn handle_restore_buffer(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::RestoreBuffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let mut state = envelope
+            .payload
+            .state
+            .ok_or_else(|| anyhow!("missing buffer state"))?;
+        let buffer_id = BufferId::new(state.id)?;
+        let operations = envelope
+            .payload
+            .operations
+            .into_iter()
+            .map(language::proto::deserialize_operation)
+            .collect::<Result<Vec<_>>>()?;
+        let file = match state.file.take() {
+            Some(file) => Some(Self::load_restored_file(&this, file, &mut cx).await?),
+            None => None,
+        };
+
+        this.update(&mut cx, |this, cx| {
+            let buffer = Buffer::from_proto(
+                0,
+                Capability::ReadWrite,
+                state,
+                file.clone().map(|file| file as Arc<dyn language::File>),
+            )?;
+            let buffer = cx.new_model(|_| buffer);
+            buffer.update(cx, |buffer, cx| buffer.apply_ops(operations, cx));
+            this.add_buffer(buffer.clone(), cx)?;
+            this.shared_buffers
+                .entry(proto::SSH_PEER_ID)
+                .or_default()
+                .insert(buffer);
+
+            let Some(file) = file else {
+                return Ok(proto::Ack {});
+            };
+            if let Some(local) = this.state.as_local() {
+                local.update(cx, |local, cx| {
+                    local.local_buffer_ids_by_path.insert(
+                        ProjectPath {
+                            worktree_id: file.worktree_id(cx),
+                            path: file.path.clone(),
+                        },
+                        buffer_id,
+                    );
+                    if let Some(entry_id) = file.entry_id {
+                        local
+                            .local_buffer_ids_by_entry_id
+                            .insert(entry_id, buffer_id);
+                    }
+                });
+            }
+            // The file's entry and modification time may have changed while the
+            // server was gone.
+            if let Some((client, project_id)) = &this.downstream_client {
+                client
+                    .send(proto::UpdateBufferFile {
+                        project_id: *project_id,
+                        buffer_id: buffer_id.into(),
+                        file: Some(file.to_proto(cx)),
+                    })
+                    .log_err();
+            }
+            Ok(proto::Ack {})
+        })?
+    }
+
+    async fn load_restored_file(
+        this: &Model<Self>,
+        file: proto::File,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Arc<File>> {
+        let path: Arc<Path> = Path::new(&file.path).into();
+        let worktree = this
+            .update(cx, |this, cx| {
+                this.worktree_store
+                    .read(cx)
+                    .worktree_for_id(WorktreeId::from_proto(file.worktree_id), cx)
+            })?
+            .ok_or_else(|| anyhow!("no such worktree"))?;
+
+        let load_file = worktree.update(cx, |worktree, cx| worktree.load_file(&path, cx))?;
+        match load_file.await {
+            Ok(loaded) => Ok(loaded.file),
+            Err(error) if is_not_found_error(&error) => Ok(Arc::new(File {
+                worktree,
+                path,
+                mtime: None,
+                entry_id: None,
+                is_local: true,
+                is_deleted: false,
+                is_private: false,
+            })),
+            Err(error) => Err(error),
+        }
+    }
+
     pub fn handle_synchronize_buffers(
         &mut self,
         envelope: TypedEnvelope<proto::SynchronizeBuffers>,
@@ -72,7 +72,7 @@ use std::{
     path::{Component, Path, PathBuf},
     str,
     sync::Arc,
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 use task_store::TaskStore;
 use terminals::Terminals;
@@ -139,6 +139,9 @@ pub struct Project {
     user_store: Model<UserStore>,
     fs: Arc<dyn Fs>,
     ssh_client: Option<Model<SshRemoteClient>>,
+    /// Operations on buffers made while the ssh connection was offline, sent
+    /// once it's restored.
+    offline_operations: HashMap<BufferId, OfflineOperations>,
     client_state: ProjectClientState,
     collaborators: HashMap<proto::PeerId, Collaborator>,
     client_subscriptions: Vec<client::Subscription>,
@@ -161,6 +164,13 @@ pub struct Project {
     settings_observer: Model<SettingsObserver>,
 }
 
+struct OfflineOperations {
+    /// The modification time of the buffer's file when it was first edited
+    /// offline, to detect whether the file changed on the host in the meantime.
+    mtime: Option<SystemTime>,
+    operations: Vec<proto::Operation>,
+}
+
 #[derive(Default)]
 struct RemotelyCreatedModels {
     worktrees: Vec<Model<Worktree>>,
@@ -659,6 +669,7 @@ impl Project {
                 settings_observer,
                 fs,
                 ssh_client: None,
+                offline_operations: Default::default(),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
@@ -783,6 +794,7 @@ impl Project {
                 settings_observer,
                 fs,
                 ssh_client: Some(ssh.clone()),
+                offline_operations: Default::default(),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
@@ -976,6 +988,7 @@ impl Project {
                 snippets,
                 fs,
                 ssh_client: None,
+                offline_operations: Default::default(),
                 settings_observer: settings_observer.clone(),
                 client_subscriptions: Default::default(),
                 _subscriptions: vec![cx.on_release(Self::release)],
@@ -1334,6 +1347,30 @@ impl Project {
             .map(|ssh| ssh.read(cx).connection_state())
     }
 
+    pub fn ssh_latency(&self, cx: &AppContext) -> Option<Duration> {
+        self.ssh_client
+            .as_ref()
+            .and_then(|ssh| ssh.read(cx).latency())
+    }
+
+    /// Shuts down the remote server this project is connected to, leaving the
+    /// project in the disconnected state.
+    pub fn disconnect_from_ssh_remote(&mut self, cx: &mut ModelContext<Self>) -> Task<()> {
+        match &self.ssh_client {
+            Some(ssh) => ssh.update(cx, |ssh, cx| ssh.disconnect(cx)),
+            None => Task::ready(()),
+        }
+    }
+
+    /// Tries to restore a lost connection to the remote server in place,
+    /// sending any edits made while offline.
+    pub fn reconnect_to_ssh_remote(&mut self, cx: &mut ModelContext<Self>) -> Result<()> {
+        match &self.ssh_client {
+            Some(ssh) => ssh.update(cx, |ssh, cx| ssh.reconnect(cx)),
+            None => Err(anyhow!("not connected to an ssh remote")),
+        }
+    }
+
     pub fn ssh_connection_options(&self, cx: &AppContext) -> Option<SshConnectionOptions> {
         self.ssh_client
             .as_ref()
@@ -1753,6 +1790,20 @@ impl Project {
             .unwrap_or(false)
     }
 
+    /// Whether the ssh connection is down but may be restored in place. Buffers
+    /// stay editable, but nothing can be loaded from or saved to the host.
+    pub fn is_offline(&self, cx: &AppContext) -> bool {
+        self.ssh_client
+            .as_ref()
+            .map(|ssh| ssh.read(cx).is_offline())
+            .unwrap_or(false)
+    }
+
+    /// Whether edits were made while offline that haven't reached the host yet.
+    pub fn has_offline_edits(&self) -> bool {
+        !self.offline_operations.is_empty()
+    }
+
     pub fn capability(&self) -> Capability {
         match &self.client_state {
             ProjectClientState::Remote { capability, .. } => *capability,
@@ -1842,7 +1893,9 @@ impl Project {
         path: impl Into<ProjectPath>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Model<Buffer>>> {
-        if (self.is_via_collab() || self.is_via_ssh()) && self.is_disconnected(cx) {
+        if (self.is_via_collab() || self.is_via_ssh())
+            && (self.is_disconnected(cx) || self.is_offline(cx))
+        {
             return Task::ready(Err(anyhow!(ErrorCode::Disconnected)));
         }
 
@@ -1897,6 +1950,9 @@ impl Project {
         buffer: Model<Buffer>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
+        if self.is_offline(cx) {
+            return Task::ready(Err(anyhow!("can't save while offline")));
+        }
         self.buffer_store
             .update(cx, |buffer_store, cx| buffer_store.save_buffer(buffer, cx))
     }
@@ -1907,6 +1963,9 @@ impl Project {
         path: ProjectPath,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
+        if self.is_offline(cx) {
+            return Task::ready(Err(anyhow!("can't save while offline")));
+        }
         self.buffer_store.update(cx, |buffer_store, cx| {
             buffer_store.save_buffer_as(buffer.clone(), path, cx)
         })
@@ -2156,6 +2215,10 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) {
         match event {
+            // Buffers stay editable while offline; their operations are queued
+            // and replayed once the connection is restored.
+            remote::SshRemoteEvent::Offline => cx.emit(Event::DisconnectedFromSshRemote),
+            remote::SshRemoteEvent::Reconnected => self.replay_offline_operations(cx),
             remote::SshRemoteEvent::Disconnected => {
                 // if self.is_via_ssh() {
                 // self.collaborators.clear();
@@ -2170,9 +2233,94 @@ impl Project {
                 });
                 cx.emit(Event::DisconnectedFromSshRemote);
             }
+            remote::SshRemoteEvent::ServerRestarted => {
+                // Restored buffers include the edits made while offline.
+                self.offline_operations.clear();
+                self.reattach_to_restarted_server(cx);
+            }
+        }
+    }
+
+    /// Sends the edits made while offline. The host's messages, including
+    /// changes to files on disk, have been received by now, so buffers whose
+    /// file changed in the meantime are reported before their edits are applied
+    /// on top of the host's version.
+    fn replay_offline_operations(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(ssh) = &self.ssh_client else {
+            return;
+        };
+        let client = ssh.read(cx).to_proto_client();
+
+        let mut conflicted_paths = Vec::new();
+        for (buffer_id, offline) in self.offline_operations.drain() {
+            if let Some(buffer) = self.buffer_store.read(cx).get(buffer_id) {
+                let buffer = buffer.read(cx);
+                if let Some(file) = buffer.file() {
+                    if file.mtime() != offline.mtime {
+                        conflicted_paths.push(file.full_path(cx));
+                    }
+                }
+            }
+
+            for operations in split_operations(offline.operations) {
+                client
+                    .send(proto::UpdateBuffer {
+                        project_id: 0,
+                        buffer_id: buffer_id.to_proto(),
+                        operations,
+                    })
+                    .log_err();
+            }
+        }
+
+        if !conflicted_paths.is_empty() {
+            conflicted_paths.sort();
+            let paths = conflicted_paths
+                .iter()
+                .map(|path| path.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(", ");
+            cx.emit(Event::Notification(format!(
+                "These files changed on the host while you were offline. Your edits were \
+                 applied on top of the host's version, so review them before saving: {paths}"
+            )));
         }
     }
 
+    /// Adds the worktrees and open buffers to a server that was started in place
+    /// of one that went away, keeping their ids, so that the project and its
+    /// buffers keep working as before.
+    fn reattach_to_restarted_server(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(ssh) = &self.ssh_client else {
+            return;
+        };
+        let client = ssh.read(cx).to_proto_client();
+        let reattach_worktrees = self.worktree_store.update(cx, |worktree_store, cx| {
+            worktree_store.reattach_ssh_worktrees(cx)
+        });
+
+        cx.spawn(|this, mut cx| async move {
+            reattach_worktrees.await?;
+
+            let buffers = this.update(&mut cx, |this, cx| this.opened_buffers(cx))?;
+            for buffer in buffers {
+                let (state, operations) = buffer.update(&mut cx, |buffer, cx| {
+                    (buffer.to_proto(cx), buffer.serialize_ops(None, cx))
+                })?;
+                client
+                    .request(proto::RestoreBuffer {
+                        project_id: SSH_PROJECT_ID,
+                        state: Some(state),
+                        operations: operations.await,
+                    })
+                    .await
+                    .log_err();
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn on_settings_observer_event(
         &mut self,
         _: Model<SettingsObserver>,
@@ -2293,7 +2441,16 @@ impl Project {
             } => {
                 let operation = language::proto::serialize_operation(operation);
 
-                if let Some(ssh) = &self.ssh_client {
+                if self.is_offline(cx) {
+                    self.offline_operations
+                        .entry(buffer_id)
+                        .or_insert_with(|| OfflineOperations {
+                            mtime: buffer.read(cx).file().and_then(|file| file.mtime()),
+                            operations: Vec::new(),
+                        })
+                        .operations
+                        .push(operation.clone());
+                } else if let Some(ssh) = &self.ssh_client {
                     ssh.read(cx)
                         .to_proto_client()
                         .send(proto::UpdateBuffer {
@@ -3209,17 +3366,139 @@ impl Project {
     ) -> Task<Result<Vec<PathBuf>>> {
         if self.is_local() {
             DirectoryLister::Local(self.fs.clone()).list_directory(query, cx)
+        } else {
+            let response = self.list_remote_directory(query, cx);
+            cx.background_executor().spawn(async move {
+                let response = response.await?;
+                Ok(response.entries.into_iter().map(PathBuf::from).collect())
+            })
+        }
+    }
+
+    /// Lists the names of the directories in `path`, leaving out files.
+    pub fn list_subdirectories(
+        &self,
+        path: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<PathBuf>>> {
+        let entries = self.list_directory_entries(path, cx);
+        cx.background_executor().spawn(async move {
+            Ok(entries
+                .await?
+                .into_iter()
+                .filter(|(_, kind)| *kind == PathKind::Directory)
+                .map(|(name, _)| name)
+                .collect())
+        })
+    }
+
+    /// Lists the names of the entries in `path`, along with whether each is a file or a
+    /// directory.
+    pub fn list_directory_entries(
+        &self,
+        path: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<(PathBuf, PathKind)>>> {
+        if self.is_local() {
+            let fs = self.fs.clone();
+            cx.background_executor().spawn(async move {
+                let expanded = shellexpand::tilde(&path);
+                let mut result = Vec::new();
+                let mut entries = fs.read_dir(Path::new(expanded.as_ref())).await?;
+                while let Some(entry) = entries.next().await {
+                    let entry = entry?;
+                    if let Some(file_name) = entry.file_name() {
+                        let kind = PathKind::from_is_dir(fs.is_dir(&entry).await);
+                        result.push((PathBuf::from(file_name), kind));
+                    }
+                }
+                Ok(result)
+            })
+        } else {
+            let response = self.list_remote_directory(path, cx);
+            cx.background_executor().spawn(async move {
+                let response = response.await?;
+                // Servers that don't say which entries are directories list all of them as
+                // directories.
+                let is_dir = response.entry_is_dir;
+                Ok(response
+                    .entries
+                    .into_iter()
+                    .enumerate()
+                    .map(|(ix, entry)| {
+                        let is_dir = is_dir.get(ix).copied().unwrap_or(true);
+                        (PathBuf::from(entry), PathKind::from_is_dir(is_dir))
+                    })
+                    .collect())
+            })
+        }
+    }
+
+    /// Creates the directory at `path`, along with any missing parents, on the machine the
+    /// project is on.
+    pub fn create_directory(&self, path: String, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        if self.is_local() {
+            let fs = self.fs.clone();
+            cx.background_executor().spawn(async move {
+                let expanded = shellexpand::tilde(&path);
+                fs.create_dir(Path::new(expanded.as_ref())).await
+            })
+        } else if let Some(session) = self.ssh_client.as_ref() {
+            let request = session
+                .read(cx)
+                .to_proto_client()
+                .request(proto::CreateRemoteDirectory { path });
+            cx.background_executor().spawn(async move {
+                request.await?;
+                Ok(())
+            })
+        } else {
+            Task::ready(Err(anyhow!("cannot create directory in remote project")))
+        }
+    }
+
+    /// Whether `path`, on the machine the project is on, is a file or a directory. `None`
+    /// if nothing exists at `path`.
+    pub fn path_kind(
+        &self,
+        path: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<PathKind>>> {
+        if self.is_local() {
+            let fs = self.fs.clone();
+            cx.background_executor().spawn(async move {
+                let expanded = shellexpand::tilde(&path);
+                let metadata = fs.metadata(Path::new(expanded.as_ref())).await?;
+                Ok(metadata.map(|metadata| PathKind::from_is_dir(metadata.is_dir)))
+            })
         } else if let Some(session) = self.ssh_client.as_ref() {
+            let request = session
+                .read(cx)
+                .to_proto_client()
+                .request(proto::GetPathMetadata { path });
+            cx.background_executor().spawn(async move {
+                let response = request.await?;
+                Ok(response
+                    .exists
+                    .then(|| PathKind::from_is_dir(response.is_dir)))
+            })
+        } else {
+            Task::ready(Err(anyhow!("cannot check paths in remote project")))
+        }
+    }
+
+    fn list_remote_directory(
+        &self,
+        path: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<proto::ListRemoteDirectoryResponse>> {
+        if let Some(session) = self.ssh_client.as_ref() {
             let request = proto::ListRemoteDirectory {
                 dev_server_id: SSH_PROJECT_ID,
-                path: query,
+                path,
             };
-
             let response = session.read(cx).to_proto_client().request(request);
-            cx.background_executor().spawn(async move {
-                let response = response.await?;
-                Ok(response.entries.into_iter().map(PathBuf::from).collect())
-            })
+            cx.background_executor().spawn(response)
         } else if let Some(dev_server) = self.dev_server_project_id().and_then(|id| {
             dev_server_projects::Store::global(cx)
                 .read(cx)
@@ -3227,13 +3506,10 @@ impl Project {
         }) {
             let request = proto::ListRemoteDirectory {
                 dev_server_id: dev_server.id.0,
-                path: query,
+                path,
             };
             let response = self.client.request(request);
-            cx.background_executor().spawn(async move {
-                let response = response.await?;
-                Ok(response.entries.into_iter().map(PathBuf::from).collect())
-            })
+            cx.background_executor().spawn(response)
         } else {
             Task::ready(Err(anyhow!("cannot list directory in remote project")))
         }
@@ -4085,6 +4361,22 @@ fn resolve_path(base: &Path, path: &Path) -> PathBuf {
     result
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathKind {
+    File,
+    Directory,
+}
+
+impl PathKind {
+    fn from_is_dir(is_dir: bool) -> Self {
+        if is_dir {
+            Self::Directory
+        } else {
+            Self::File
+        }
+    }
+}
+
 /// ResolvedPath is a path that has been resolved to either a ProjectPath
 /// or an AbsPath and that *exists*.
 #[derive(Debug, Clone)]
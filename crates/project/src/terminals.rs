@@ -39,8 +39,8 @@ pub enum TerminalKind {
 pub enum SshCommand {
     /// DevServers give a string from the user
     DevServer(String),
-    /// Direct ssh has a list of arguments to pass to ssh
-    Direct(Vec<String>),
+    /// Direct ssh has the ssh executable and a list of arguments to pass to it
+    Direct { program: String, args: Vec<String> },
 }
 
 impl Project {
@@ -67,12 +67,14 @@ impl Project {
     }
 
     fn ssh_command(&self, cx: &AppContext) -> Option<SshCommand> {
-        if let Some(args) = self
-            .ssh_client
-            .as_ref()
-            .and_then(|session| session.read(cx).ssh_args())
-        {
-            return Some(SshCommand::Direct(args));
+        if let Some(ssh_client) = self.ssh_client.as_ref() {
+            let ssh_client = ssh_client.read(cx);
+            if let Some((program, args)) = ssh_client.ssh_binary().zip(ssh_client.ssh_args()) {
+                return Some(SshCommand::Direct {
+                    program: program.to_string_lossy().to_string(),
+                    args,
+                });
+            }
         }
 
         let dev_server_project_id = self.dev_server_project_id()?;
@@ -102,6 +104,10 @@ impl Project {
             }
         };
         let ssh_command = self.ssh_command(cx);
+        let ssh_shell = self
+            .ssh_client
+            .as_ref()
+            .and_then(|ssh_client| ssh_client.read(cx).connection_options().shell);
 
         let mut settings_location = None;
         if let Some(path) = path.as_ref() {
@@ -122,6 +128,10 @@ impl Project {
             .read(cx)
             .get_cli_environment()
             .unwrap_or_default();
+        // Then with the variables configured for the remote host, if any.
+        if let Some(ssh_client) = &self.ssh_client {
+            env.extend(ssh_client.read(cx).connection_options().env);
+        }
         // Then extend it with the explicit env variables from the settings, so they take
         // precedence.
         env.extend(settings.env.clone());
@@ -154,8 +164,14 @@ impl Project {
                         env.entry("TERM".to_string())
                             .or_insert_with(|| "xterm-256color".to_string());
 
-                        let (program, args) =
-                            wrap_for_ssh(ssh_command, None, path.as_deref(), env, None);
+                        let (program, args) = wrap_for_ssh(
+                            ssh_command,
+                            None,
+                            path.as_deref(),
+                            env,
+                            None,
+                            ssh_shell.as_deref(),
+                        );
                         env = HashMap::default();
                         (None, Shell::WithArguments { program, args })
                     }
@@ -193,6 +209,7 @@ impl Project {
                             path.as_deref(),
                             env,
                             python_venv_directory,
+                            ssh_shell.as_deref(),
                         );
                         env = HashMap::default();
                         (task_state, Shell::WithArguments { program, args })
@@ -339,12 +356,15 @@ pub fn wrap_for_ssh(
     path: Option<&Path>,
     env: HashMap<String, String>,
     venv_directory: Option<PathBuf>,
+    shell: Option<&str>,
 ) -> (String, Vec<String>) {
     let to_run = if let Some((command, args)) = command {
         iter::once(command)
             .chain(args)
             .filter_map(|arg| shlex::try_quote(arg).ok())
             .join(" ")
+    } else if let Some(shell) = shell {
+        format!("exec {} -l", shell)
     } else {
         "exec ${SHELL:-sh} -l".to_string()
     };
@@ -375,7 +395,11 @@ pub fn wrap_for_ssh(
     } else {
         format!("cd; {env_changes} {to_run}")
     };
-    let shell_invocation = format!("sh -c {}", shlex::try_quote(&commands).unwrap());
+    let shell_invocation = format!(
+        "{} -c {}",
+        shell.unwrap_or("sh"),
+        shlex::try_quote(&commands).unwrap()
+    );
 
     let (program, mut args) = match ssh_command {
         SshCommand::DevServer(ssh_command) => {
@@ -383,7 +407,7 @@ pub fn wrap_for_ssh(
             let program = args.drain(0..1).next().unwrap_or("ssh".to_string());
             (program, args)
         }
-        SshCommand::Direct(ssh_args) => ("ssh".to_string(), ssh_args.clone()),
+        SshCommand::Direct { program, args } => (program.clone(), args.clone()),
     };
 
     if command.is_none() {
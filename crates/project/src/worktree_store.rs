@@ -215,6 +215,33 @@ impl WorktreeStore {
         })
     }
 
+    /// Adds the ssh worktrees to a server that was restarted, under the ids they
+    /// already have, so that paths and buffers in the project stay valid.
+    pub fn reattach_ssh_worktrees(&self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let WorktreeStoreState::Remote {
+            upstream_client, ..
+        } = &self.state
+        else {
+            return Task::ready(Err(anyhow!("not an ssh project")));
+        };
+        let requests = self
+            .worktrees()
+            .map(|worktree| {
+                let worktree = worktree.read(cx);
+                upstream_client.request(proto::AddWorktree {
+                    project_id: SSH_PROJECT_ID,
+                    path: worktree.abs_path().to_string_lossy().into_owned(),
+                    visible: worktree.is_visible(),
+                    worktree_id: Some(worktree.id().to_proto()),
+                })
+            })
+            .collect::<Vec<_>>();
+        cx.background_executor().spawn(async move {
+            futures::future::try_join_all(requests).await?;
+            Ok(())
+        })
+    }
+
     fn create_ssh_worktree(
         &mut self,
         client: AnyProtoClient,
@@ -247,6 +274,7 @@ impl WorktreeStore {
                     project_id: SSH_PROJECT_ID,
                     path: abs_path.clone(),
                     visible,
+                    worktree_id: None,
                 })
                 .await?;
 
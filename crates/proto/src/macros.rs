@@ -31,6 +31,7 @@ macro_rules! messages {
                         id,
                         responding_to,
                         original_sender_id,
+                        ack_id: None,
                         payload: Some(envelope::Payload::$name(self)),
                     }
                 }
@@ -367,6 +367,11 @@ messages!(
     (ShutdownRemoteServer, Foreground),
     (RemoveWorktree, Foreground),
     (LanguageServerLog, Foreground),
+    (FlushBufferedMessages, Foreground),
+    (CreateRemoteDirectory, Background),
+    (GetPathMetadata, Background),
+    (GetPathMetadataResponse, Background),
+    (RestoreBuffer, Foreground),
 );
 
 request_messages!(
@@ -490,7 +495,10 @@ request_messages!(
     (AddWorktree, AddWorktreeResponse),
     (CheckFileExists, CheckFileExistsResponse),
     (ShutdownRemoteServer, Ack),
-    (RemoveWorktree, Ack)
+    (RemoveWorktree, Ack),
+    (CreateRemoteDirectory, Ack),
+    (GetPathMetadata, GetPathMetadataResponse),
+    (RestoreBuffer, Ack),
 );
 
 entity_messages!(
@@ -564,6 +572,7 @@ entity_messages!(
     UpdateUserSettings,
     CheckFileExists,
     LanguageServerLog,
+    RestoreBuffer,
 );
 
 entity_messages!(
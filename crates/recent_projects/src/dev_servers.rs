@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -8,7 +9,6 @@ use anyhow::Context;
 use anyhow::Result;
 use dev_server_projects::{DevServer, DevServerId, DevServerProjectId};
 use editor::Editor;
-use file_finder::OpenPathDelegate;
 use futures::channel::oneshot;
 use futures::future::Shared;
 use futures::FutureExt;
@@ -26,7 +26,9 @@ use gpui::{
 use picker::Picker;
 use project::terminals::wrap_for_ssh;
 use project::terminals::SshCommand;
+use project::PathKind;
 use project::Project;
+use remote::SshPortForward;
 use rpc::proto::DevServerStatus;
 use settings::update_settings_file;
 use settings::Settings;
@@ -35,15 +37,20 @@ use task::RevealStrategy;
 use task::SpawnInTerminal;
 use terminal_view::terminal_panel::TerminalPanel;
 use ui::Section;
-use ui::{prelude::*, List, ListItem, ListSeparator, Modal, ModalHeader, Tooltip};
+use ui::{prelude::*, List, ListItem, ListSeparator, ListSubHeader, Modal, ModalHeader, Tooltip};
 use util::ResultExt;
 use workspace::notifications::NotificationId;
 use workspace::OpenOptions;
+use workspace::SerializedWorkspaceLocation;
 use workspace::Toast;
+use workspace::WORKSPACE_DB;
 use workspace::{notifications::DetachAndPromptErr, ModalView, Workspace};
 
 use crate::open_dev_server_project;
+use crate::remote_directory_picker::{RemoteDirectoryDelegate, ToggleHiddenFolders};
 use crate::ssh_connections::connect_over_ssh;
+use crate::ssh_connections::connection_log_path;
+use crate::ssh_connections::open_all_ssh_projects;
 use crate::ssh_connections::open_ssh_project;
 use crate::ssh_connections::RemoteSettingsContent;
 use crate::ssh_connections::SshConnection;
@@ -52,7 +59,7 @@ use crate::ssh_connections::SshConnectionModal;
 use crate::ssh_connections::SshProject;
 use crate::ssh_connections::SshPrompt;
 use crate::ssh_connections::SshSettings;
-use crate::OpenRemote;
+use crate::{MoveSelectedDown, MoveSelectedUp, OpenRemote};
 
 pub struct DevServerProjects {
     mode: Mode,
@@ -62,6 +69,8 @@ pub struct DevServerProjects {
     workspace: WeakView<Workspace>,
     _dev_server_subscription: Subscription,
     selectable_items: SelectableItemList,
+    /// When set, only servers with this tag are listed.
+    tag_filter: Option<String>,
 }
 
 struct CreateDevServer {
@@ -86,8 +95,10 @@ impl CreateDevServer {
 
 struct ProjectPicker {
     connection_string: SharedString,
-    picker: View<Picker<OpenPathDelegate>>,
+    project: Model<Project>,
+    picker: View<Picker<RemoteDirectoryDelegate>>,
     _path_task: Shared<Task<Option<()>>>,
+    _project_subscription: Subscription,
 }
 
 type SelectedItemCallback =
@@ -97,9 +108,26 @@ type SelectedItemCallback =
 #[derive(Default)]
 struct SelectableItemList {
     items: Vec<SelectedItemCallback>,
+    /// What moving each item up or down reorders, if anything.
+    reorder_targets: Vec<Option<ReorderTarget>>,
     active_item: Option<usize>,
 }
 
+/// A server or project in the settings, by its index there.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ReorderTarget {
+    Server(usize),
+    Project { server: usize, project: usize },
+}
+
+impl ReorderTarget {
+    fn server(&self) -> usize {
+        match self {
+            Self::Server(server) | Self::Project { server, .. } => *server,
+        }
+    }
+}
+
 struct EditNicknameState {
     index: usize,
     editor: View<Editor>,
@@ -127,9 +155,36 @@ impl EditNicknameState {
     }
 }
 
+struct EditConnectionState {
+    index: usize,
+    editor: View<Editor>,
+}
+
+impl EditConnectionState {
+    fn new(index: usize, cx: &mut WindowContext<'_>) -> Self {
+        let this = Self {
+            index,
+            editor: cx.new_view(Editor::single_line),
+        };
+        let starting_text = SshSettings::get_global(cx)
+            .ssh_connections()
+            .nth(index)
+            .map(|connection| remote::SshConnectionOptions::from(connection).connection_string());
+        this.editor.update(cx, |this, cx| {
+            this.set_placeholder_text("user@host:port", cx);
+            if let Some(starting_text) = starting_text {
+                this.set_text(starting_text, cx);
+            }
+        });
+        this.editor.focus_handle(cx).focus(cx);
+        this
+    }
+}
+
 impl SelectableItemList {
     fn reset(&mut self) {
         self.items.clear();
+        self.reorder_targets.clear();
     }
 
     fn reset_selection(&mut self) {
@@ -163,7 +218,30 @@ impl SelectableItemList {
     }
 
     fn add_item(&mut self, callback: SelectedItemCallback) {
-        self.items.push(callback)
+        self.items.push(callback);
+        self.reorder_targets.push(None);
+    }
+
+    fn add_reorderable_item(&mut self, callback: SelectedItemCallback, target: ReorderTarget) {
+        self.items.push(callback);
+        self.reorder_targets.push(Some(target));
+    }
+
+    fn selected_reorder_target(&self) -> Option<ReorderTarget> {
+        *self.reorder_targets.get(self.active_item?)?
+    }
+
+    /// The range of items belonging to the server, which are listed together.
+    fn server_items(&self, server: usize) -> Option<Range<usize>> {
+        let start = self
+            .reorder_targets
+            .iter()
+            .position(|target| target.map(|target| target.server()) == Some(server))?;
+        let len = self.reorder_targets[start..]
+            .iter()
+            .take_while(|target| target.map(|target| target.server()) == Some(server))
+            .count();
+        Some(start..start + len)
     }
 
     fn is_selected(&self) -> bool {
@@ -187,20 +265,32 @@ impl ProjectPicker {
     ) -> View<Self> {
         let (tx, rx) = oneshot::channel();
         let lister = project::DirectoryLister::Project(project.clone());
-        let query = lister.default_query(cx);
-        let delegate = file_finder::OpenPathDelegate::new(tx, lister);
+        let directory = SshSettings::get_global(cx)
+            .ssh_connections()
+            .nth(ix)
+            .and_then(|connection| connection.default_path)
+            .unwrap_or_else(|| lister.default_query(cx));
+        let directory = match directory.trim_end_matches('/') {
+            "" => "/".to_string(),
+            directory => directory.to_string(),
+        };
+        let delegate = RemoteDirectoryDelegate::new(project.clone(), tx, directory.clone());
 
         let picker = cx.new_view(|cx| {
-            let picker = Picker::uniform_list(delegate, cx)
+            let mut picker = Picker::uniform_list(delegate, cx)
                 .width(rems(34.))
                 .modal(false);
-            picker.set_query(query, cx);
+            picker.delegate.navigate(directory, cx);
             picker
         });
+        load_path_suggestions(ix, picker.downgrade(), cx);
         cx.new_view(|cx| {
+            // Keeps the latency shown in the header current.
+            let _project_subscription = cx.observe(&project, |_, _, cx| cx.notify());
             let _path_task = cx
                 .spawn({
                     let workspace = workspace.clone();
+                    let project = project.clone();
                     move |_, mut cx| async move {
                         let Ok(Some(paths)) = rx.await else {
                             workspace
@@ -240,26 +330,6 @@ impl ProjectPicker {
                                 }
                             });
 
-                            let tasks = paths
-                                .into_iter()
-                                .map(|path| {
-                                    project.update(cx, |project, cx| {
-                                        project.find_or_create_worktree(&path, true, cx)
-                                    })
-                                })
-                                .collect::<Vec<_>>();
-                            cx.spawn(|_| async move {
-                                for task in tasks {
-                                    task.await?;
-                                }
-                                Ok(())
-                            })
-                            .detach_and_prompt_err(
-                                "Failed to open path",
-                                cx,
-                                |_, _| None,
-                            );
-
                             cx.new_view(|cx| {
                                 let workspace =
                                     Workspace::new(None, project.clone(), app_state.clone(), cx);
@@ -269,6 +339,7 @@ impl ProjectPicker {
                                     .telemetry()
                                     .report_app_event("create ssh project".to_string());
 
+                                open_remote_paths(project.clone(), paths, cx);
                                 workspace
                             })
                         })
@@ -280,20 +351,99 @@ impl ProjectPicker {
 
             Self {
                 _path_task,
+                _project_subscription,
                 picker,
                 connection_string,
+                project,
             }
         })
     }
 }
 
+/// Adds a worktree for each of `paths`, opening the ones that are files in an editor.
+fn open_remote_paths(
+    project: Model<Project>,
+    paths: Vec<PathBuf>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    cx.spawn(|workspace, mut cx| async move {
+        for path in paths {
+            let path_kind = project
+                .update(&mut cx, |project, cx| {
+                    project.path_kind(path.to_string_lossy().into_owned(), cx)
+                })?
+                .await?;
+            if path_kind == Some(PathKind::File) {
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.open_abs_path(path, true, cx)
+                    })?
+                    .await?;
+            } else {
+                project
+                    .update(&mut cx, |project, cx| {
+                        project.find_or_create_worktree(&path, true, cx)
+                    })?
+                    .await?;
+            }
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to open path", cx, |_, _| None);
+}
+
+/// Suggests the paths previously opened on the server, from its configured projects and the
+/// workspace history, most recent first.
+fn load_path_suggestions(
+    ix: usize,
+    picker: WeakView<Picker<RemoteDirectoryDelegate>>,
+    cx: &mut ViewContext<DevServerProjects>,
+) {
+    let Some(connection) = SshSettings::get_global(cx).ssh_connections().nth(ix) else {
+        return;
+    };
+    cx.spawn(|_, mut cx| async move {
+        let mut suggestions = WORKSPACE_DB
+            .recent_workspaces_on_disk()
+            .await?
+            .into_iter()
+            .filter_map(|(_, location)| match location {
+                SerializedWorkspaceLocation::Ssh(ssh_project)
+                    if ssh_project.host == connection.host.as_ref()
+                        && ssh_project.port == connection.port
+                        && ssh_project.user == connection.username =>
+                {
+                    Some(ssh_project.paths)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for project in connection.projects {
+            if !suggestions.contains(&project.paths) {
+                suggestions.push(project.paths);
+            }
+        }
+        picker.update(&mut cx, |picker, cx| {
+            picker.delegate.set_suggestions(suggestions);
+            picker.refresh(cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
 impl gpui::Render for ProjectPicker {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         v_flex()
+            .key_context("RemoteDirectoryPicker")
+            .on_action(cx.listener(|this, _: &ToggleHiddenFolders, cx| {
+                this.picker
+                    .update(cx, |picker, cx| picker.delegate.toggle_hidden_folders(cx))
+            }))
             .child(
                 SshConnectionHeader {
                     connection_string: self.connection_string.clone(),
                     nickname: None,
+                    latency: self.project.read(cx).ssh_latency(cx),
                 }
                 .render(cx),
             )
@@ -304,6 +454,7 @@ enum Mode {
     Default,
     ViewServerOptions(usize, SshConnection),
     EditNickname(EditNicknameState),
+    EditConnection(EditConnectionState),
     ProjectPicker(View<ProjectPicker>),
     CreateDevServer(CreateDevServer),
 }
@@ -345,6 +496,7 @@ impl DevServerProjects {
             workspace,
             _dev_server_subscription: subscription,
             selectable_items: Default::default(),
+            tag_filter: None,
         }
     }
 
@@ -385,30 +537,7 @@ impl DevServerProjects {
             return;
         }
 
-        let mut host = host.trim_start_matches("ssh ");
-        let mut username: Option<String> = None;
-        let mut port: Option<u16> = None;
-
-        if let Some((u, rest)) = host.split_once('@') {
-            host = rest;
-            username = Some(u.to_string());
-        }
-        if let Some((rest, p)) = host.split_once(':') {
-            host = rest;
-            port = p.parse().ok()
-        }
-
-        if let Some((rest, p)) = host.split_once(" -p") {
-            host = rest;
-            port = p.trim().parse().ok()
-        }
-
-        let connection_options = remote::SshConnectionOptions {
-            host: host.to_string(),
-            username: username.clone(),
-            port,
-            password: None,
-        };
+        let connection_options = parse_ssh_address(&host);
         let ssh_prompt = cx.new_view(|cx| SshPrompt::new(&connection_options, cx));
 
         let connection = connect_over_ssh(
@@ -533,6 +662,110 @@ impl DevServerProjects {
         })
     }
 
+    fn move_selected_up(&mut self, _: &MoveSelectedUp, cx: &mut ViewContext<Self>) {
+        self.move_selected(true, cx);
+    }
+
+    fn move_selected_down(&mut self, _: &MoveSelectedDown, cx: &mut ViewContext<Self>) {
+        self.move_selected(false, cx);
+    }
+
+    /// Swaps the selected server or project with the one listed above or below it,
+    /// keeping it selected.
+    fn move_selected(&mut self, up: bool, cx: &mut ViewContext<Self>) {
+        if !matches!(self.mode, Mode::Default) {
+            return;
+        }
+        let (Some(target), Some(active_item)) = (
+            self.selectable_items.selected_reorder_target(),
+            self.selectable_items.active_item,
+        ) else {
+            return;
+        };
+
+        match target {
+            ReorderTarget::Server(server) => {
+                let Some(items) = self.selectable_items.server_items(server) else {
+                    return;
+                };
+                let neighbor_item = if up {
+                    items.start.checked_sub(1)
+                } else {
+                    Some(items.end)
+                };
+                let Some(neighbor) = neighbor_item
+                    .and_then(|ix| *self.selectable_items.reorder_targets.get(ix)?)
+                    .map(|target| target.server())
+                else {
+                    return;
+                };
+                // Servers are listed by their first tag, so swapping servers of different
+                // groups wouldn't move them in the list.
+                let connections = SshSettings::get_global(cx)
+                    .ssh_connections()
+                    .collect::<Vec<_>>();
+                let (Some(connection), Some(neighbor_connection)) =
+                    (connections.get(server), connections.get(neighbor))
+                else {
+                    return;
+                };
+                if self.tag_filter.is_none()
+                    && connection.tags.first() != neighbor_connection.tags.first()
+                {
+                    return;
+                }
+                let Some(neighbor_items) = self.selectable_items.server_items(neighbor) else {
+                    return;
+                };
+
+                self.selectable_items.active_item = Some(if up {
+                    neighbor_items.start + (active_item - items.start)
+                } else {
+                    active_item + neighbor_items.len()
+                });
+                self.update_settings_file(cx, move |setting, _| {
+                    if let Some(connections) = setting.ssh_connections.as_mut() {
+                        if server < connections.len() && neighbor < connections.len() {
+                            connections.swap(server, neighbor);
+                        }
+                    }
+                });
+            }
+            ReorderTarget::Project { server, project } => {
+                let neighbor = if up {
+                    let Some(neighbor) = project.checked_sub(1) else {
+                        return;
+                    };
+                    neighbor
+                } else {
+                    project + 1
+                };
+                let project_count = SshSettings::get_global(cx)
+                    .ssh_connections()
+                    .nth(server)
+                    .map_or(0, |connection| connection.projects.len());
+                if neighbor >= project_count {
+                    return;
+                }
+
+                self.selectable_items.active_item =
+                    Some(if up { active_item - 1 } else { active_item + 1 });
+                self.update_settings_file(cx, move |setting, _| {
+                    if let Some(server) = setting
+                        .ssh_connections
+                        .as_mut()
+                        .and_then(|connections| connections.get_mut(server))
+                    {
+                        if neighbor < server.projects.len() {
+                            server.projects.swap(project, neighbor);
+                        }
+                    }
+                });
+            }
+        }
+        cx.notify();
+    }
+
     fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
         match &self.mode {
             Mode::Default | Mode::ViewServerOptions(_, _) => {
@@ -570,6 +803,26 @@ impl DevServerProjects {
                 self.selectable_items.reset_selection();
                 self.focus_handle.focus(cx);
             }
+            Mode::EditConnection(state) => {
+                let address = get_text(&state.editor, cx);
+                if address.is_empty() {
+                    return;
+                }
+                let connection_options = parse_ssh_address(&address);
+                let index = state.index;
+                self.update_settings_file(cx, move |setting, _| {
+                    if let Some(connections) = setting.ssh_connections.as_mut() {
+                        if let Some(connection) = connections.get_mut(index) {
+                            connection.host = connection_options.host.into();
+                            connection.username = connection_options.username;
+                            connection.port = connection_options.port;
+                        }
+                    }
+                });
+                self.mode = Mode::Default;
+                self.selectable_items.reset_selection();
+                self.focus_handle.focus(cx);
+            }
         }
     }
 
@@ -627,6 +880,29 @@ impl DevServerProjects {
                         aux_label.map(|label| {
                             Label::new(label).size(LabelSize::Small).color(Color::Muted)
                         }),
+                    )
+                    .when(!ssh_connection.port_forwards.is_empty(), |this| {
+                        let count = ssh_connection.port_forwards.len();
+                        this.child(
+                            Label::new(format!(
+                                "· {} forwarded {}",
+                                count,
+                                if count == 1 { "port" } else { "ports" }
+                            ))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        )
+                    })
+                    .child(
+                        IconButton::new(("rename-ssh-server", ix), IconName::Pencil)
+                            .icon_size(IconSize::XSmall)
+                            .icon_color(Color::Muted)
+                            .visible_on_hover("ssh-server")
+                            .tooltip(|cx| Tooltip::text("Edit Nickname", cx))
+                            .on_click(cx.listener(move |this, _, cx| {
+                                this.mode = Mode::EditNickname(EditNicknameState::new(ix, cx));
+                                cx.notify();
+                            })),
                     ),
             )
             .child(
@@ -642,13 +918,49 @@ impl DevServerProjects {
                                 cx,
                             ))
                         }))
+                        .when(ssh_connection.projects.len() > 1, |list| {
+                            list.child(h_flex().map(|this| {
+                                self.selectable_items.add_reorderable_item(
+                                    Box::new({
+                                        let ssh_connection = ssh_connection.clone();
+                                        move |this, cx| {
+                                            this.open_all_ssh_projects(ssh_connection.clone(), cx);
+                                        }
+                                    }),
+                                    ReorderTarget::Server(ix),
+                                );
+                                let is_selected = self.selectable_items.is_selected();
+                                this.child(
+                                    ListItem::new(("open-all-remote-projects", ix))
+                                        .selected(is_selected)
+                                        .inset(true)
+                                        .spacing(ui::ListItemSpacing::Sparse)
+                                        .start_slot(
+                                            Icon::new(IconName::ArrowUpRight).color(Color::Muted),
+                                        )
+                                        .child(Label::new("Open All Projects"))
+                                        .on_click(cx.listener({
+                                            let ssh_connection = ssh_connection.clone();
+                                            move |this, _, cx| {
+                                                this.open_all_ssh_projects(
+                                                    ssh_connection.clone(),
+                                                    cx,
+                                                );
+                                            }
+                                        })),
+                                )
+                            }))
+                        })
                         .child(h_flex().map(|this| {
-                            self.selectable_items.add_item(Box::new({
-                                let ssh_connection = ssh_connection.clone();
-                                move |this, cx| {
-                                    this.create_ssh_project(ix, ssh_connection.clone(), cx);
-                                }
-                            }));
+                            self.selectable_items.add_reorderable_item(
+                                Box::new({
+                                    let ssh_connection = ssh_connection.clone();
+                                    move |this, cx| {
+                                        this.create_ssh_project(ix, ssh_connection.clone(), cx);
+                                    }
+                                }),
+                                ReorderTarget::Server(ix),
+                            );
                             let is_selected = self.selectable_items.is_selected();
                             this.child(
                                 ListItem::new(("new-remote-project", ix))
@@ -666,12 +978,15 @@ impl DevServerProjects {
                             )
                         }))
                         .child(h_flex().map(|this| {
-                            self.selectable_items.add_item(Box::new({
-                                let ssh_connection = ssh_connection.clone();
-                                move |this, cx| {
-                                    this.view_server_options((ix, ssh_connection.clone()), cx);
-                                }
-                            }));
+                            self.selectable_items.add_reorderable_item(
+                                Box::new({
+                                    let ssh_connection = ssh_connection.clone();
+                                    move |this, cx| {
+                                        this.view_server_options((ix, ssh_connection.clone()), cx);
+                                    }
+                                }),
+                                ReorderTarget::Server(ix),
+                            );
                             let is_selected = self.selectable_items.is_selected();
                             this.child(
                                 ListItem::new(("server-options", ix))
@@ -695,6 +1010,21 @@ impl DevServerProjects {
             )
     }
 
+    fn open_all_ssh_projects(&mut self, ssh_connection: SshConnection, cx: &mut ViewContext<Self>) {
+        let Some(app_state) = self
+            .workspace
+            .update(cx, |workspace, _| workspace.app_state().clone())
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn(|_, mut cx| async move {
+            open_all_ssh_projects(ssh_connection, app_state, &mut cx).await
+        })
+        .detach_and_prompt_err("Failed to connect", cx, |_, _| None);
+        cx.emit(DismissEvent);
+    }
+
     fn render_ssh_project(
         &mut self,
         server_ix: usize,
@@ -742,10 +1072,16 @@ impl DevServerProjects {
                 .detach();
             }
         });
-        self.selectable_items.add_item(Box::new({
-            let callback = callback.clone();
-            move |this, cx| callback(this, cx)
-        }));
+        self.selectable_items.add_reorderable_item(
+            Box::new({
+                let callback = callback.clone();
+                move |this, cx| callback(this, cx)
+            }),
+            ReorderTarget::Project {
+                server: server_ix,
+                project: ix,
+            },
+        );
         let is_selected = self.selectable_items.is_selected();
 
         ListItem::new((element_id_base, ix))
@@ -762,9 +1098,12 @@ impl DevServerProjects {
             .end_hover_slot::<AnyElement>(Some(
                 IconButton::new("remove-remote-project", IconName::TrashAlt)
                     .icon_size(IconSize::Small)
-                    .on_click(
-                        cx.listener(move |this, _, cx| this.delete_ssh_project(server_ix, ix, cx)),
-                    )
+                    .on_click(cx.listener({
+                        let project = project.clone();
+                        move |this, _, cx| {
+                            this.confirm_delete_ssh_project(server_ix, project.clone(), cx)
+                        }
+                    }))
                     .size(ButtonSize::Large)
                     .tooltip(|cx| Tooltip::text("Delete Remote Project", cx))
                     .into_any_element(),
@@ -794,14 +1133,50 @@ impl DevServerProjects {
         });
     }
 
-    fn delete_ssh_project(&mut self, server: usize, project: usize, cx: &mut ViewContext<Self>) {
+    fn confirm_delete_ssh_project(
+        &mut self,
+        server: usize,
+        project: SshProject,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let dev_servers = cx.view().clone();
+        let paths = SharedString::from(project.paths.join(", "));
+        self.workspace
+            .update(cx, |workspace, cx| {
+                struct SshProjectRemoval;
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::identified::<SshProjectRemoval>(paths.clone()),
+                        format!("Do you really want to remove project `{}`?", paths),
+                    )
+                    .on_click("Yes, remove it", move |cx| {
+                        dev_servers.update(cx, |this, cx| {
+                            this.delete_ssh_project(server, project.clone(), cx)
+                        })
+                    }),
+                    cx,
+                );
+            })
+            .log_err();
+    }
+
+    /// Removes the project by its paths rather than its index, as the settings may
+    /// have changed while the removal waited for confirmation.
+    fn delete_ssh_project(
+        &mut self,
+        server: usize,
+        project: SshProject,
+        cx: &mut ViewContext<Self>,
+    ) {
         self.update_settings_file(cx, move |setting, _| {
             if let Some(server) = setting
                 .ssh_connections
                 .as_mut()
                 .and_then(|connections| connections.get_mut(server))
             {
-                server.projects.remove(project);
+                server
+                    .projects
+                    .retain(|server_project| server_project.paths != project.paths);
             }
         });
     }
@@ -820,7 +1195,7 @@ impl DevServerProjects {
                     username: connection_options.username,
                     port: connection_options.port,
                     projects: vec![],
-                    nickname: None,
+                    ..Default::default()
                 })
         });
     }
@@ -891,6 +1266,39 @@ impl DevServerProjects {
             )
     }
 
+    fn open_connection_log(&mut self, connection: SshConnection, cx: &mut ViewContext<Self>) {
+        let host = connection.host.clone();
+        let path = connection_log_path(&connection.into());
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        if !path.exists() {
+            workspace.update(cx, |workspace, cx| {
+                struct NoConnectionLog;
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::identified::<NoConnectionLog>(host.clone()),
+                        format!("No connection log for {} yet", host),
+                    )
+                    .autohide(),
+                    cx,
+                );
+            });
+            return;
+        }
+        // Remote projects can't open local files, so hand the log to the system there.
+        if workspace.read(cx).project().read(cx).is_local() {
+            workspace.update(cx, |workspace, cx| {
+                workspace
+                    .open_abs_path(path, false, cx)
+                    .detach_and_log_err(cx);
+            });
+        } else {
+            cx.open_with_system(&path);
+        }
+        cx.emit(DismissEvent);
+    }
+
     fn render_view_options(
         &mut self,
         index: usize,
@@ -905,12 +1313,49 @@ impl DevServerProjects {
                 SshConnectionHeader {
                     connection_string: connection_string.clone(),
                     nickname: connection.nickname.clone(),
+                    latency: None,
                 }
                 .render(cx),
             )
             .child(
                 v_flex()
                     .py_1()
+                    .when(
+                        !connection.port_forwards.is_empty()
+                            || connection.dynamic_forward_port.is_some(),
+                        |this| {
+                            this.children(connection.port_forwards.iter().enumerate().map(
+                                |(ix, forward)| {
+                                    let forward = SshPortForward::from(forward.clone());
+                                    ListItem::new(("port-forward", ix))
+                                        .inset(true)
+                                        .spacing(ui::ListItemSpacing::Sparse)
+                                        .start_slot(
+                                            Icon::new(IconName::ArrowRight).color(Color::Muted),
+                                        )
+                                        .child(Label::new(format!(
+                                            "{} → {}",
+                                            forward.local_address(),
+                                            forward.remote_address()
+                                        )))
+                                        .end_hover_slot(
+                                            Label::new("Forwarded Port").color(Color::Muted),
+                                        )
+                                },
+                            ))
+                            .children(connection.dynamic_forward_port.map(|port| {
+                                ListItem::new("dynamic-forward")
+                                    .inset(true)
+                                    .spacing(ui::ListItemSpacing::Sparse)
+                                    .start_slot(Icon::new(IconName::Public).color(Color::Muted))
+                                    .child(Label::new(format!(
+                                        "SOCKS5 proxy on localhost:{}",
+                                        port
+                                    )))
+                            }))
+                            .child(ListSeparator)
+                        },
+                    )
                     .child({
                         self.selectable_items.add_item(Box::new({
                             move |this, cx| {
@@ -935,6 +1380,27 @@ impl DevServerProjects {
                                 cx.notify();
                             }))
                     })
+                    .child({
+                        self.selectable_items.add_item(Box::new({
+                            move |this, cx| {
+                                this.mode =
+                                    Mode::EditConnection(EditConnectionState::new(index, cx));
+                                cx.notify();
+                            }
+                        }));
+                        let is_selected = self.selectable_items.is_selected();
+                        ListItem::new("edit-connection")
+                            .selected(is_selected)
+                            .inset(true)
+                            .spacing(ui::ListItemSpacing::Sparse)
+                            .start_slot(Icon::new(IconName::Server).color(Color::Muted))
+                            .child(Label::new("Edit Address"))
+                            .on_click(cx.listener(move |this, _, cx| {
+                                this.mode =
+                                    Mode::EditConnection(EditConnectionState::new(index, cx));
+                                cx.notify();
+                            }))
+                    })
                     .child({
                         let workspace = self.workspace.clone();
                         fn callback(
@@ -992,6 +1458,27 @@ impl DevServerProjects {
                                 }
                             })
                     })
+                    .child({
+                        self.selectable_items.add_item(Box::new({
+                            let connection = connection.clone();
+                            move |this, cx| {
+                                this.open_connection_log(connection.clone(), cx);
+                            }
+                        }));
+                        let is_selected = self.selectable_items.is_selected();
+                        ListItem::new("open-connection-log")
+                            .selected(is_selected)
+                            .inset(true)
+                            .spacing(ui::ListItemSpacing::Sparse)
+                            .start_slot(Icon::new(IconName::FileText).color(Color::Muted))
+                            .child(Label::new("Open Connection Log"))
+                            .on_click(cx.listener({
+                                let connection = connection.clone();
+                                move |this, _, cx| {
+                                    this.open_connection_log(connection.clone(), cx);
+                                }
+                            }))
+                    })
                     .child({
                         fn remove_ssh_server(
                             dev_servers: View<DevServerProjects>,
@@ -1100,10 +1587,45 @@ impl DevServerProjects {
                 SshConnectionHeader {
                     connection_string,
                     nickname: connection.nickname.clone(),
+                    latency: None,
+                }
+                .render(cx),
+            )
+            .child(h_flex().p_2().child(state.editor.clone()))
+    }
+
+    fn render_edit_connection(
+        &self,
+        state: &EditConnectionState,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let Some(connection) = SshSettings::get_global(cx)
+            .ssh_connections()
+            .nth(state.index)
+        else {
+            return v_flex();
+        };
+
+        v_flex()
+            .child(
+                SshConnectionHeader {
+                    connection_string: connection.host.clone(),
+                    nickname: connection.nickname.clone(),
+                    latency: None,
                 }
                 .render(cx),
             )
             .child(h_flex().p_2().child(state.editor.clone()))
+            .child(
+                h_flex().px_2().pb_2().child(
+                    Label::new(
+                        "Projects, nickname and other options of the server are kept. \
+                         Windows connected to it keep using the old address until reopened.",
+                    )
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                ),
+            )
     }
 
     fn render_default(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
@@ -1131,21 +1653,85 @@ impl DevServerProjects {
             }));
 
         let footer = format!("Servers: {}", ssh_connections.len() + dev_servers.len());
+
+        let mut tags = ssh_connections
+            .iter()
+            .flat_map(|connection| connection.tags.iter().cloned())
+            .collect::<Vec<_>>();
+        tags.sort();
+        tags.dedup();
+        if self
+            .tag_filter
+            .as_ref()
+            .map_or(false, |tag| !tags.contains(tag))
+        {
+            self.tag_filter = None;
+        }
+
+        // Without a filter, servers are grouped under their first tag, with
+        // untagged servers listed first. Indices stay those of the settings.
+        let mut groups: Vec<(Option<String>, Vec<(usize, SshConnection)>)> = Vec::new();
+        for (ix, connection) in ssh_connections.iter().cloned().enumerate() {
+            let group = match &self.tag_filter {
+                Some(tag) if connection.tags.contains(tag) => None,
+                Some(_) => continue,
+                None => connection.tags.first().cloned(),
+            };
+            match groups.iter_mut().find(|(tag, _)| *tag == group) {
+                Some((_, connections)) => connections.push((ix, connection)),
+                None => groups.push((group, vec![(ix, connection)])),
+            }
+        }
+        groups.sort_by(|(a, _), (b, _)| a.cmp(b));
+
         let mut modal_section = v_flex()
             .id("ssh-server-list")
             .overflow_y_scroll()
             .size_full()
             .child(connect_button)
             .child(ListSeparator)
+            .when(!tags.is_empty(), |this| {
+                this.child(
+                    h_flex()
+                        .px_2()
+                        .pb_1()
+                        .gap_1()
+                        .flex_wrap()
+                        .child(
+                            Button::new("tag-filter-all", "All")
+                                .label_size(LabelSize::Small)
+                                .selected(self.tag_filter.is_none())
+                                .on_click(cx.listener(|this, _, cx| {
+                                    this.tag_filter = None;
+                                    cx.notify();
+                                })),
+                        )
+                        .children(tags.iter().enumerate().map(|(ix, tag)| {
+                            Button::new(("tag-filter", ix), tag.clone())
+                                .label_size(LabelSize::Small)
+                                .selected(self.tag_filter.as_ref() == Some(tag))
+                                .on_click(cx.listener({
+                                    let tag = tag.clone();
+                                    move |this, _, cx| {
+                                        this.tag_filter = Some(tag.clone());
+                                        cx.notify();
+                                    }
+                                }))
+                        })),
+                )
+            })
             .child(
                 List::new()
-                    .empty_message("No dev servers registered yet.")
-                    .children(ssh_connections.iter().cloned().enumerate().map(
-                        |(ix, connection)| {
-                            self.render_ssh_connection(ix, connection, cx)
-                                .into_any_element()
-                        },
-                    )),
+                    .empty_message("No remote servers yet.")
+                    .children(groups.into_iter().flat_map(|(tag, connections)| {
+                        tag.map(|tag| ListSubHeader::new(tag).into_any_element())
+                            .into_iter()
+                            .chain(connections.into_iter().map(|(ix, connection)| {
+                                self.render_ssh_connection(ix, connection, cx)
+                                    .into_any_element()
+                            }))
+                            .collect::<Vec<_>>()
+                    })),
             )
             .into_any_element();
 
@@ -1154,14 +1740,14 @@ impl DevServerProjects {
                 ModalHeader::new().child(
                     h_flex()
                         .justify_between()
-                        .child(Headline::new("Remote Projects (alpha)").size(HeadlineSize::XSmall))
+                        .child(Headline::new("Remote Servers").size(HeadlineSize::XSmall))
                         .child(Label::new(footer).size(LabelSize::Small)),
                 ),
             )
             .section(
                 Section::new().padded(false).child(
                     v_flex()
-                        .min_h(rems(28.))
+                        .min_h(rems(40.))
                         .size_full()
                         .pt_1p5()
                         .border_y_1()
@@ -1189,6 +1775,35 @@ impl DevServerProjects {
     }
 }
 
+/// Parses the address of a server as typed by the user, which may be the ssh command
+/// they use to connect, e.g. `ssh me@my.server -p 2222`.
+fn parse_ssh_address(address: &str) -> remote::SshConnectionOptions {
+    let mut host = address.trim_start_matches("ssh ");
+    let mut username: Option<String> = None;
+    let mut port: Option<u16> = None;
+
+    if let Some((u, rest)) = host.split_once('@') {
+        host = rest;
+        username = Some(u.to_string());
+    }
+    if let Some((rest, p)) = host.split_once(':') {
+        host = rest;
+        port = p.parse().ok()
+    }
+
+    if let Some((rest, p)) = host.split_once(" -p") {
+        host = rest;
+        port = p.trim().parse().ok()
+    }
+
+    remote::SshConnectionOptions {
+        host: host.to_string(),
+        username,
+        port,
+        ..Default::default()
+    }
+}
+
 fn get_text(element: &View<Editor>, cx: &mut WindowContext) -> String {
     element.read(cx).text(cx).trim().to_string()
 }
@@ -1214,6 +1829,8 @@ impl Render for DevServerProjects {
             .on_action(cx.listener(Self::confirm))
             .on_action(cx.listener(Self::prev_item))
             .on_action(cx.listener(Self::next_item))
+            .on_action(cx.listener(Self::move_selected_up))
+            .on_action(cx.listener(Self::move_selected_down))
             .capture_any_mouse_down(cx.listener(|this, _, cx| {
                 this.focus_handle(cx).focus(cx);
             }))
@@ -1222,7 +1839,7 @@ impl Render for DevServerProjects {
                     cx.emit(DismissEvent)
                 }
             }))
-            .w(rems(34.))
+            .w(rems(56.))
             .child(match &self.mode {
                 Mode::Default => self.render_default(cx).into_any_element(),
                 Mode::ViewServerOptions(index, connection) => self
@@ -1235,6 +1852,9 @@ impl Render for DevServerProjects {
                 Mode::EditNickname(state) => {
                     self.render_edit_nickname(state, cx).into_any_element()
                 }
+                Mode::EditConnection(state) => {
+                    self.render_edit_connection(state, cx).into_any_element()
+                }
             })
     }
 }
@@ -1335,6 +1955,7 @@ pub async fn spawn_ssh_task(
         None,
         HashMap::default(),
         None,
+        None,
     );
 
     let terminal = terminal_panel
@@ -1,20 +1,32 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use dev_server_projects::DevServer;
-use gpui::{ClickEvent, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, WeakView};
-use remote::SshConnectionOptions;
+use gpui::{
+    ClickEvent, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Task, WeakView,
+};
+use remote::{ConnectionState, SshConnectionOptions};
+use settings::Settings;
 use ui::{
     div, h_flex, rems, Button, ButtonCommon, ButtonStyle, Clickable, ElevationIndex, FluentBuilder,
     Headline, HeadlineSize, IconName, IconPosition, InteractiveElement, IntoElement, Label, Modal,
     ModalFooter, ModalHeader, ParentElement, Section, Styled, StyledExt, ViewContext,
 };
-use workspace::{notifications::DetachAndPromptErr, ModalView, OpenOptions, Workspace};
+use util::ResultExt;
+use workspace::{
+    notifications::{DetachAndPromptErr, NotificationId},
+    ModalView, OpenOptions, SaveIntent, Toast, Workspace,
+};
 
 use crate::{
-    dev_servers::reconnect_to_dev_server_project, open_dev_server_project, open_ssh_project,
-    DevServerProjects,
+    dev_servers::reconnect_to_dev_server_project,
+    open_dev_server_project, open_ssh_project,
+    ssh_connections::{probe_ssh_host, SshConnectionModal},
+    DevServerProjects, Disconnect, DuplicateWindow, Reconnect, SshSettings,
 };
 
+struct ReconnectingToast;
+struct ConnectionLostToast;
+
 enum Host {
     RemoteProject,
     DevServerProject(DevServer),
@@ -24,6 +36,9 @@ enum Host {
 pub struct DisconnectedOverlay {
     workspace: WeakView<Workspace>,
     host: Host,
+    /// Whether the remote server may still be running, so edits made now can
+    /// be sent once the connection is restored.
+    offline: bool,
     focus_handle: FocusHandle,
 }
 
@@ -41,6 +56,94 @@ impl ModalView for DisconnectedOverlay {
 
 impl DisconnectedOverlay {
     pub fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &Reconnect, cx| {
+            let project = workspace.project().read(cx);
+            if !project.is_via_ssh() || !(project.is_disconnected(cx) || project.is_offline(cx)) {
+                return;
+            }
+            if let Some(connection_options) = project.ssh_connection_options(cx) {
+                reconnect_to_ssh_remote(workspace, connection_options, cx);
+            }
+        });
+
+        workspace.register_action(|workspace, _: &Disconnect, cx| {
+            let project = workspace.project().read(cx);
+            if !project.is_via_ssh() || project.is_disconnected(cx) {
+                return;
+            }
+            disconnect_from_ssh_remote(workspace, cx);
+        });
+
+        workspace.register_action(|workspace, _: &DuplicateWindow, cx| {
+            if !workspace.project().read(cx).is_via_ssh() {
+                return;
+            }
+            duplicate_ssh_window(workspace, cx);
+        });
+
+        let mut last_ssh_state = workspace.project().read(cx).ssh_connection_state(cx);
+        let mut reopen_task = None;
+        cx.observe(workspace.project(), move |workspace, project, cx| {
+            let ssh_state = project.read(cx).ssh_connection_state(cx);
+            if ssh_state == last_ssh_state {
+                return;
+            }
+            let was_reconnecting = last_ssh_state == Some(ConnectionState::Reconnecting);
+            last_ssh_state = ssh_state;
+
+            let Some(connection_options) = project.read(cx).ssh_connection_options(cx) else {
+                return;
+            };
+            let host = SshSettings::get_global(cx)
+                .nickname_for(&connection_options)
+                .map(|nickname| nickname.to_string())
+                .unwrap_or_else(|| connection_options.connection_string());
+            let toast_id = NotificationId::unique::<ReconnectingToast>();
+            let lost_toast_id = NotificationId::identified::<ConnectionLostToast>(
+                cx.window_handle().window_id().as_u64() as usize,
+            );
+            match ssh_state {
+                Some(ConnectionState::Reconnecting) => {
+                    if SshSettings::get_global(cx).reconnect_in_background() {
+                        workspace.show_toast(
+                            Toast::new(toast_id, format!("Reconnecting to {host}…")),
+                            cx,
+                        );
+                    }
+                }
+                Some(ConnectionState::Offline | ConnectionState::Disconnected)
+                    if was_reconnecting =>
+                {
+                    workspace.dismiss_toast(&toast_id, cx);
+                    if SshSettings::get_global(cx).reopen_when_reachable() {
+                        reopen_task = Some(reopen_when_reachable(connection_options.clone(), cx));
+                    }
+                    show_connection_lost_toast(
+                        workspace,
+                        lost_toast_id,
+                        format!("Lost connection to {host}"),
+                        connection_options,
+                        cx,
+                    );
+                }
+                Some(ConnectionState::Connected) if was_reconnecting => {
+                    reopen_task.take();
+                    for_each_workspace(workspace, cx, |workspace, cx| {
+                        workspace.dismiss_toast(&lost_toast_id, cx)
+                    });
+                    workspace.show_toast(
+                        Toast::new(toast_id, format!("Reconnected to {host}")).autohide(),
+                        cx,
+                    );
+                    if let Some(modal) = workspace.active_modal::<SshConnectionModal>(cx) {
+                        modal.update(cx, |_, cx| cx.emit(DismissEvent));
+                    }
+                }
+                _ => workspace.dismiss_toast(&toast_id, cx),
+            }
+        })
+        .detach();
+
         cx.subscribe(workspace.project(), |workspace, project, event, cx| {
             if !matches!(
                 event,
@@ -68,9 +171,12 @@ impl DisconnectedOverlay {
                 Host::RemoteProject
             };
 
+            let offline = project.read(cx).is_offline(cx);
+
             workspace.toggle_modal(cx, |cx| DisconnectedOverlay {
                 workspace: handle,
                 host,
+                offline,
                 focus_handle: cx.focus_handle(),
             });
         })
@@ -144,37 +250,192 @@ impl DisconnectedOverlay {
             return;
         };
 
-        let Some(ssh_project) = workspace.read(cx).serialized_ssh_project() else {
-            return;
-        };
+        workspace.update(cx, |workspace, cx| {
+            reconnect_to_ssh_remote(workspace, connection_options, cx)
+        });
+    }
 
-        let Some(window) = cx.window_handle().downcast::<Workspace>() else {
-            return;
-        };
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent)
+    }
+}
+
+/// Restores the connection in place if the server may still be running, and
+/// otherwise connects to the host again and reopens this window's remote project.
+fn reconnect_to_ssh_remote(
+    workspace: &Workspace,
+    connection_options: SshConnectionOptions,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    if project.read(cx).is_offline(cx) {
+        match project.update(cx, |project, cx| project.reconnect_to_ssh_remote(cx)) {
+            Ok(()) => return,
+            Err(error) => log::error!("failed to reconnect in place: {error:?}"),
+        }
+    }
 
-        let app_state = workspace.read(cx).app_state().clone();
+    let Some(ssh_project) = workspace.serialized_ssh_project() else {
+        return;
+    };
 
-        let paths = ssh_project.paths.iter().map(PathBuf::from).collect();
+    let Some(window) = cx.window_handle().downcast::<Workspace>() else {
+        return;
+    };
 
-        cx.spawn(move |_, mut cx| async move {
-            open_ssh_project(
-                connection_options,
-                paths,
-                app_state,
-                OpenOptions {
-                    replace_window: Some(window),
-                    ..Default::default()
-                },
-                &mut cx,
-            )
-            .await?;
-            Ok(())
-        })
-        .detach_and_prompt_err("Failed to reconnect", cx, |_, _| None);
-    }
+    let app_state = workspace.app_state().clone();
 
-    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
-        cx.emit(DismissEvent)
+    let paths = ssh_project.paths.iter().map(PathBuf::from).collect();
+
+    cx.spawn(move |_, mut cx| async move {
+        open_ssh_project(
+            connection_options,
+            paths,
+            app_state,
+            OpenOptions {
+                replace_window: Some(window),
+                ..Default::default()
+            },
+            &mut cx,
+        )
+        .await?;
+        Ok(())
+    })
+    .detach_and_prompt_err("Failed to reconnect", cx, |_, _| None);
+}
+
+/// Opens this window's remote project in a second window, e.g. for another monitor.
+/// Both windows share the project and its connection to the server. The project's
+/// saved workspace belongs to this window, so the new one isn't saved.
+fn duplicate_ssh_window(workspace: &Workspace, cx: &mut ViewContext<Workspace>) {
+    let Some(ssh_project) = workspace.serialized_ssh_project() else {
+        return;
+    };
+    let project = workspace.project().clone();
+    let app_state = workspace.app_state().clone();
+    let active_path = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx));
+
+    cx.spawn(move |_, mut cx| async move {
+        let window = cx.update(|cx| {
+            let options = (app_state.build_window_options)(None, cx);
+            cx.open_window(options, |cx| {
+                cx.new_view(|cx| {
+                    let mut workspace = Workspace::new(None, project, app_state.clone(), cx);
+                    workspace.set_serialized_ssh_project(ssh_project);
+                    workspace
+                })
+            })
+        })??;
+
+        if let Some(active_path) = active_path {
+            window
+                .update(&mut cx, |workspace, cx| {
+                    workspace.open_path(active_path, None, true, cx)
+                })?
+                .await?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to duplicate window", cx, |_, _| None);
+}
+
+/// Waits for the host of a lost connection to be reachable again, then reconnects, which
+/// reopens the project with the files that were open in it.
+fn reopen_when_reachable(
+    connection_options: SshConnectionOptions,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<()> {
+    const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+    cx.spawn(|workspace, mut cx| async move {
+        loop {
+            cx.background_executor().timer(PROBE_INTERVAL).await;
+            let executor = cx.background_executor().clone();
+            // Hosts that can't be probed, e.g. behind jump hosts, are left to the user.
+            match probe_ssh_host(&connection_options, &executor).await {
+                Some(true) => break,
+                Some(false) => continue,
+                None => return,
+            }
+        }
+        workspace
+            .update(&mut cx, |workspace, cx| {
+                let project = workspace.project().read(cx);
+                if !(project.is_disconnected(cx) || project.is_offline(cx)) {
+                    return;
+                }
+                if let Some(overlay) = workspace.active_modal::<DisconnectedOverlay>(cx) {
+                    overlay.update(cx, |_, cx| cx.emit(DismissEvent));
+                }
+                reconnect_to_ssh_remote(workspace, connection_options, cx);
+            })
+            .log_err();
+    })
+}
+
+/// Saves any dirty items, then shuts down the remote server and leaves this
+/// window in the disconnected state.
+fn disconnect_from_ssh_remote(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let save_all = workspace.save_all_internal(SaveIntent::SaveAll, cx);
+    let project = workspace.project().clone();
+
+    cx.spawn(move |_, mut cx| async move {
+        if !save_all.await? {
+            return Ok(());
+        }
+        project
+            .update(&mut cx, |project, cx| {
+                project.disconnect_from_ssh_remote(cx)
+            })?
+            .await;
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to disconnect", cx, |_, _| None);
+}
+
+/// Shows the toast in every workspace window, so that a dropped connection is
+/// noticed while working in another window. Clicking it reconnects.
+fn show_connection_lost_toast(
+    workspace: &mut Workspace,
+    id: NotificationId,
+    message: String,
+    connection_options: SshConnectionOptions,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let window = cx.window_handle().downcast::<Workspace>();
+    let toast = Toast::new(id, message).on_click("Reconnect", move |cx| {
+        let Some(window) = window else {
+            return;
+        };
+        let connection_options = connection_options.clone();
+        window
+            .update(cx, |workspace, cx| {
+                cx.activate_window();
+                reconnect_to_ssh_remote(workspace, connection_options, cx);
+            })
+            .ok();
+    });
+    for_each_workspace(workspace, cx, |workspace, cx| {
+        workspace.show_toast(toast.clone(), cx)
+    });
+}
+
+fn for_each_workspace(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+    mut f: impl FnMut(&mut Workspace, &mut ViewContext<Workspace>),
+) {
+    f(workspace, cx);
+    let current_window = cx.window_handle();
+    for window in cx.windows() {
+        if window == current_window {
+            continue;
+        }
+        if let Some(window) = window.downcast::<Workspace>() {
+            window.update(cx, |workspace, cx| f(workspace, cx)).ok();
+        }
     }
 }
 
@@ -189,6 +450,12 @@ impl Render for DisconnectedOverlay {
             Host::RemoteProject | Host::DevServerProject(_) => {
                 "Your connection to the remote project has been lost.".to_string()
             }
+            Host::SshRemoteProject(options) if self.offline => {
+                format!(
+                    "Your connection to {} has been lost. You can keep editing, and your changes will be sent when you reconnect.",
+                    options.connection_string()
+                )
+            }
             Host::SshRemoteProject(options) => {
                 format!(
                     "Your connection to {} has been lost",
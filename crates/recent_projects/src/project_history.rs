@@ -0,0 +1,148 @@
+//! Moving recent and pinned projects between machines as JSON.
+
+use std::path::PathBuf;
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use gpui::{PathPromptOptions, ViewContext};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use util::ResultExt;
+use workspace::{
+    notifications::NotificationId, RecentProjectsSettings, SerializedSshProject,
+    SerializedWorkspaceLocation, Toast, Workspace, WORKSPACE_DB,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportedProject {
+    /// The server the project is on, or none for a local project.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ssh: Option<ExportedSshHost>,
+    paths: Vec<String>,
+    #[serde(default)]
+    pinned: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportedSshHost {
+    host: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
+}
+
+pub(crate) fn export_recent_projects(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let fs = workspace.app_state().fs.clone();
+    let path = cx.prompt_for_new_path(util::paths::home_dir());
+    cx.spawn(|workspace, mut cx| async move {
+        let Some(path) = path.await?? else {
+            return Ok(());
+        };
+        let workspaces = WORKSPACE_DB.recent_workspaces_on_disk().await?;
+        let pinned_workspaces = WORKSPACE_DB
+            .pinned_workspaces()
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let projects = cx.update(|cx| {
+            let settings = RecentProjectsSettings::get_global(cx);
+            workspaces
+                .into_iter()
+                .filter(|(_, location)| !settings.is_excluded(location))
+                .filter_map(|(id, location)| {
+                    let (ssh, paths) = match location {
+                        SerializedWorkspaceLocation::Local(paths, order) => (
+                            None,
+                            order
+                                .order()
+                                .iter()
+                                .filter_map(|i| paths.paths().get(*i))
+                                .map(|path| path.to_string_lossy().into_owned())
+                                .collect(),
+                        ),
+                        SerializedWorkspaceLocation::Ssh(ssh_project) => (
+                            Some(ExportedSshHost {
+                                host: ssh_project.host,
+                                port: ssh_project.port,
+                                user: ssh_project.user,
+                            }),
+                            ssh_project.paths,
+                        ),
+                        // Dev server projects only exist on the server they were created on.
+                        SerializedWorkspaceLocation::DevServer(_) => return None,
+                    };
+                    Some(ExportedProject {
+                        ssh,
+                        paths,
+                        pinned: pinned_workspaces.contains(&id),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })?;
+        fs.atomic_write(path, serde_json::to_string_pretty(&projects)?)
+            .await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            show_toast(
+                workspace,
+                format!("Exported {} recent projects", projects.len()),
+                cx,
+            )
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+pub(crate) fn import_recent_projects(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let fs = workspace.app_state().fs.clone();
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
+    });
+    cx.spawn(|workspace, mut cx| async move {
+        let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+            return Ok(());
+        };
+        let projects: Vec<ExportedProject> = serde_json::from_str(&fs.load(&path).await?)
+            .with_context(|| format!("parsing recent projects from {path:?}"))?;
+        let mut imported_count = 0;
+        for project in projects {
+            let location = match project.ssh {
+                Some(ssh) => SerializedWorkspaceLocation::Ssh(SerializedSshProject {
+                    id: None,
+                    host: ssh.host,
+                    port: ssh.port,
+                    paths: project.paths,
+                    user: ssh.user,
+                }),
+                None => SerializedWorkspaceLocation::from_local_paths(
+                    project.paths.iter().map(PathBuf::from),
+                ),
+            };
+            let Some(id) = WORKSPACE_DB.import_workspace(location).await.log_err() else {
+                continue;
+            };
+            if project.pinned {
+                WORKSPACE_DB.set_workspace_pinned(id, true).await.log_err();
+            }
+            imported_count += 1;
+        }
+        workspace.update(&mut cx, |workspace, cx| {
+            show_toast(
+                workspace,
+                format!("Imported {imported_count} recent projects"),
+                cx,
+            )
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn show_toast(workspace: &mut Workspace, message: String, cx: &mut ViewContext<Workspace>) {
+    struct ProjectHistoryToast;
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<ProjectHistoryToast>(), message).autohide(),
+        cx,
+    );
+}
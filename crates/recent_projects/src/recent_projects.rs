@@ -1,10 +1,14 @@
 mod dev_servers;
 pub mod disconnected_overlay;
+mod project_history;
+pub mod recent_projects_panel;
+mod remote_directory_picker;
 mod ssh_connections;
-use remote::SshConnectionOptions;
-pub use ssh_connections::open_ssh_project;
+pub use ssh_connections::{latency_color, open_ssh_project, SshSettings};
+use ssh_connections::{open_all_ssh_projects, probe_ssh_host, SshConnection};
 
 use client::{DevServerProjectId, ProjectId};
+use collections::{HashMap, HashSet};
 use dev_servers::reconnect_to_dev_server_project;
 pub use dev_servers::DevServerProjects;
 use disconnected_overlay::DisconnectedOverlay;
@@ -18,22 +22,26 @@ use picker::{
     highlighted_match_with_paths::{HighlightedMatchWithPaths, HighlightedText},
     Picker, PickerDelegate,
 };
+use remote::SshConnectionOptions;
 use rpc::proto::DevServerStatus;
 use serde::Deserialize;
-use settings::Settings;
-use ssh_connections::SshSettings;
+use settings::{Settings, SettingsStore};
 use std::{
+    cmp::Ordering,
     path::{Path, PathBuf},
     sync::Arc,
 };
+use time::{OffsetDateTime, UtcOffset};
+use time_format::TimestampFormat;
 use ui::{
     prelude::*, tooltip_container, ButtonLike, IconWithIndicator, Indicator, KeyBinding, ListItem,
     ListItemSpacing, Tooltip,
 };
 use util::{paths::PathExt, ResultExt};
 use workspace::{
-    AppState, CloseIntent, ModalView, OpenOptions, SerializedWorkspaceLocation, Workspace,
-    WorkspaceId, WORKSPACE_DB,
+    notifications::DetachAndPromptErr, AppState, CloseIntent, ModalView, OpenOptions,
+    RecentProjectsSettings, RecentProjectsSortOrder, SerializedSshProject,
+    SerializedWorkspaceLocation, Workspace, WorkspaceId, WorkspaceTemplate, WORKSPACE_DB,
 };
 
 #[derive(PartialEq, Clone, Deserialize, Default)]
@@ -47,19 +55,81 @@ fn default_create_new_window() -> bool {
 }
 
 gpui::impl_actions!(projects, [OpenRecent]);
-gpui::actions!(projects, [OpenRemote]);
+gpui::actions!(
+    projects,
+    [
+        OpenRemote,
+        Reconnect,
+        Disconnect,
+        DuplicateWindow,
+        RemoveSelected,
+        RenameSelected,
+        MoveSelectedUp,
+        MoveSelectedDown,
+        CycleSortOrder,
+        OpenRecent1,
+        OpenRecent2,
+        OpenRecent3,
+        OpenRecent4,
+        OpenRecent5,
+        OpenRecent6,
+        OpenRecent7,
+        OpenRecent8,
+        OpenRecent9,
+        ExportRecentProjects,
+        ImportRecentProjects
+    ]
+);
 
 pub fn init(cx: &mut AppContext) {
     SshSettings::register(cx);
     cx.observe_new_views(RecentProjects::register).detach();
     cx.observe_new_views(DevServerProjects::register).detach();
     cx.observe_new_views(DisconnectedOverlay::register).detach();
+    recent_projects_panel::init(cx);
+
+    let mut max_history = RecentProjectsSettings::get_global(cx).max_history;
+    prune_recent_projects(max_history, cx);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let new_max_history = RecentProjectsSettings::get_global(cx).max_history;
+        if new_max_history != max_history {
+            max_history = new_max_history;
+            prune_recent_projects(max_history, cx);
+        }
+    })
+    .detach();
+}
+
+/// Forgets the oldest recent projects past `max_history`, not counting pinned ones.
+fn prune_recent_projects(max_history: Option<usize>, cx: &AppContext) {
+    let Some(max_history) = max_history else {
+        return;
+    };
+    cx.background_executor()
+        .spawn(async move {
+            let pinned_workspaces = WORKSPACE_DB
+                .pinned_workspaces()
+                .await?
+                .into_iter()
+                .collect::<HashSet<_>>();
+            // Workspaces are most recent first, so this forgets the oldest ones.
+            let workspaces = WORKSPACE_DB.recent_workspaces_including_missing().await?;
+            for (id, _) in workspaces
+                .into_iter()
+                .filter(|(id, _)| !pinned_workspaces.contains(id))
+                .skip(max_history)
+            {
+                WORKSPACE_DB.delete_workspace_by_id(id).await.log_err();
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
 }
 
 pub struct RecentProjects {
     pub picker: View<Picker<RecentProjectsDelegate>>,
     rem_width: f32,
-    _subscription: Subscription,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl ModalView for RecentProjects {}
@@ -74,32 +144,36 @@ impl RecentProjects {
                 Picker::uniform_list(delegate, cx)
             }
         });
-        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
-        // We do not want to block the UI on a potentially lengthy call to DB, so we're gonna swap
-        // out workspace locations once the future runs to completion.
-        cx.spawn(|this, mut cx| async move {
-            let workspaces = WORKSPACE_DB
-                .recent_workspaces_on_disk()
-                .await
-                .log_err()
-                .unwrap_or_default();
-            this.update(&mut cx, move |this, cx| {
-                this.picker.update(cx, move |picker, cx| {
-                    picker.delegate.set_workspaces(workspaces);
-                    picker.update_matches(picker.query(cx), cx)
-                })
-            })
-            .ok()
-        })
-        .detach();
+        let _subscriptions = vec![
+            cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent)),
+            // Keep the preview in sync with the selected entry.
+            cx.observe(&picker, |_, _, cx| cx.notify()),
+        ];
+        load_recent_projects(picker.downgrade(), cx);
         Self {
             picker,
             rem_width,
-            _subscription,
+            _subscriptions,
         }
     }
 
     fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        workspace
+            .register_action(|workspace, _: &OpenRecent1, cx| open_nth_recent(workspace, 0, cx))
+            .register_action(|workspace, _: &OpenRecent2, cx| open_nth_recent(workspace, 1, cx))
+            .register_action(|workspace, _: &OpenRecent3, cx| open_nth_recent(workspace, 2, cx))
+            .register_action(|workspace, _: &OpenRecent4, cx| open_nth_recent(workspace, 3, cx))
+            .register_action(|workspace, _: &OpenRecent5, cx| open_nth_recent(workspace, 4, cx))
+            .register_action(|workspace, _: &OpenRecent6, cx| open_nth_recent(workspace, 5, cx))
+            .register_action(|workspace, _: &OpenRecent7, cx| open_nth_recent(workspace, 6, cx))
+            .register_action(|workspace, _: &OpenRecent8, cx| open_nth_recent(workspace, 7, cx))
+            .register_action(|workspace, _: &OpenRecent9, cx| open_nth_recent(workspace, 8, cx))
+            .register_action(|workspace, _: &ExportRecentProjects, cx| {
+                project_history::export_recent_projects(workspace, cx)
+            })
+            .register_action(|workspace, _: &ImportRecentProjects, cx| {
+                project_history::import_recent_projects(workspace, cx)
+            });
         workspace.register_action(|workspace, open_recent: &OpenRecent, cx| {
             let Some(recent_projects) = workspace.active_modal::<Self>(cx) else {
                 Self::open(workspace, open_recent.create_new_window, cx);
@@ -135,11 +209,31 @@ impl RecentProjects {
     ) {
         let weak = cx.view().downgrade();
         workspace.toggle_modal(cx, |cx| {
-            let delegate = RecentProjectsDelegate::new(weak, create_new_window, true);
+            let delegate = RecentProjectsDelegate::new(weak, create_new_window, true, cx);
 
             Self::new(delegate, 34., cx)
         })
     }
+
+    fn handle_cycle_sort_order(&mut self, _: &CycleSortOrder, cx: &mut ViewContext<Self>) {
+        self.picker
+            .update(cx, |picker, cx| picker.delegate.cycle_sort_order(cx));
+    }
+
+    fn handle_rename_selected(&mut self, _: &RenameSelected, cx: &mut ViewContext<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let ix = picker.delegate.selected_index();
+            RecentProjectsDelegate::start_renaming(picker, ix, cx)
+        });
+    }
+
+    fn handle_remove_selected(&mut self, _: &RemoveSelected, cx: &mut ViewContext<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .delete_recent_project(picker.delegate.selected_index(), cx)
+        });
+    }
 }
 
 impl EventEmitter<DismissEvent> for RecentProjects {}
@@ -152,9 +246,16 @@ impl FocusableView for RecentProjects {
 
 impl Render for RecentProjects {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        v_flex()
-            .w(rems(self.rem_width))
-            .child(self.picker.clone())
+        let preview = self.picker.read(cx).delegate.render_preview(cx);
+        h_flex()
+            .key_context("RecentProjects")
+            .items_start()
+            .gap_2()
+            .on_action(cx.listener(Self::handle_remove_selected))
+            .on_action(cx.listener(Self::handle_rename_selected))
+            .on_action(cx.listener(Self::handle_cycle_sort_order))
+            .child(v_flex().w(rems(self.rem_width)).child(self.picker.clone()))
+            .children(preview)
             .on_mouse_down_out(cx.listener(|this, _, cx| {
                 this.picker.update(cx, |this, cx| {
                     this.cancel(&Default::default(), cx);
@@ -163,33 +264,157 @@ impl Render for RecentProjects {
     }
 }
 
+/// The host, port and user that identify an ssh server.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct SshHost {
+    host: String,
+    port: Option<u16>,
+    user: Option<String>,
+}
+
+impl From<&SerializedSshProject> for SshHost {
+    fn from(project: &SerializedSshProject) -> Self {
+        Self {
+            host: project.host.clone(),
+            port: project.port,
+            user: project.user.clone(),
+        }
+    }
+}
+
+/// Which kinds of recent projects the picker lists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ProjectFilter {
+    #[default]
+    All,
+    Local,
+    Remote,
+}
+
+impl ProjectFilter {
+    /// Splits a `local:`, `remote:` or `ssh:` prefix off of the query, which
+    /// takes precedence over the filter selected in the picker.
+    fn from_query_prefix(query: &str) -> (Option<Self>, &str) {
+        if query.starts_with("ssh://") {
+            return (None, query);
+        }
+        for (prefix, filter) in [
+            ("local:", Self::Local),
+            ("remote:", Self::Remote),
+            ("ssh:", Self::Remote),
+        ] {
+            if let Some(query) = query.strip_prefix(prefix) {
+                return (Some(filter), query.trim_start());
+            }
+        }
+        (None, query)
+    }
+
+    fn includes(&self, location: &SerializedWorkspaceLocation) -> bool {
+        let is_local = matches!(location, SerializedWorkspaceLocation::Local(_, _));
+        match self {
+            Self::All => true,
+            Self::Local => is_local,
+            Self::Remote => !is_local,
+        }
+    }
+}
+
+enum RecentProjectsEntry {
+    /// A header above the recent projects on a host that has several of them.
+    Host {
+        host: SshHost,
+        project_count: usize,
+        collapsed: bool,
+    },
+    Project {
+        match_ix: usize,
+        /// Whether the project is listed below its host's header.
+        grouped: bool,
+    },
+    /// A template from the `recent_projects.templates` setting.
+    Template { match_ix: usize },
+}
+
 pub struct RecentProjectsDelegate {
     workspace: WeakView<Workspace>,
-    workspaces: Vec<(WorkspaceId, SerializedWorkspaceLocation)>,
+    /// The saved workspaces, followed by the projects configured in `ssh_connections`
+    /// that were never opened, which have no id.
+    workspaces: Vec<(Option<WorkspaceId>, SerializedWorkspaceLocation)>,
+    /// Workspaces listed above all others, regardless of when they were last opened.
+    pinned_workspaces: HashSet<WorkspaceId>,
+    /// When each workspace was last opened.
+    timestamps: HashMap<WorkspaceId, OffsetDateTime>,
+    /// How many times each workspace has been opened.
+    open_counts: HashMap<WorkspaceId, u32>,
+    sort_order: RecentProjectsSortOrder,
+    /// The git branch each workspace was on when it was last saved.
+    branches: HashMap<WorkspaceId, SharedString>,
+    /// Names given to workspaces in the picker, shown in place of their paths.
+    display_names: HashMap<WorkspaceId, SharedString>,
+    /// The workspace being renamed, and the new name typed in the query editor so far.
+    renaming: Option<(WorkspaceId, String)>,
+    /// Local workspaces whose folders were deleted or moved.
+    missing_workspaces: HashSet<WorkspaceId>,
+    /// Ssh hosts whose ssh port didn't accept a connection when last probed.
+    unreachable_hosts: HashSet<SshHost>,
     selected_match_index: usize,
     matches: Vec<StringMatch>,
+    /// The templates configured in settings, and those matching the query.
+    templates: Vec<WorkspaceTemplate>,
+    template_matches: Vec<StringMatch>,
+    /// What is shown in the picker: the matches, with projects on the same host
+    /// grouped below a header.
+    entries: Vec<RecentProjectsEntry>,
+    collapsed_hosts: HashSet<SshHost>,
+    filter: ProjectFilter,
     render_paths: bool,
     create_new_window: bool,
     // Flag to reset index when there is a new query vs not reset index when user delete an item
     reset_selected_match_index: bool,
     has_any_non_local_projects: bool,
+    /// Set when the query is an `ssh://` url, which can be opened directly.
+    ssh_url_query: Option<(SshConnectionOptions, Vec<PathBuf>)>,
 }
 
 impl RecentProjectsDelegate {
-    fn new(workspace: WeakView<Workspace>, create_new_window: bool, render_paths: bool) -> Self {
+    fn new(
+        workspace: WeakView<Workspace>,
+        create_new_window: bool,
+        render_paths: bool,
+        cx: &AppContext,
+    ) -> Self {
         Self {
             workspace,
             workspaces: Vec::new(),
+            pinned_workspaces: HashSet::default(),
+            timestamps: HashMap::default(),
+            open_counts: HashMap::default(),
+            sort_order: RecentProjectsSettings::get_global(cx).sort_order,
+            branches: HashMap::default(),
+            display_names: HashMap::default(),
+            renaming: None,
+            missing_workspaces: HashSet::default(),
+            unreachable_hosts: HashSet::default(),
             selected_match_index: 0,
             matches: Default::default(),
+            templates: Vec::new(),
+            template_matches: Vec::new(),
+            entries: Vec::new(),
+            collapsed_hosts: HashSet::default(),
+            filter: ProjectFilter::default(),
             create_new_window,
             render_paths,
             reset_selected_match_index: true,
             has_any_non_local_projects: false,
+            ssh_url_query: None,
         }
     }
 
-    pub fn set_workspaces(&mut self, workspaces: Vec<(WorkspaceId, SerializedWorkspaceLocation)>) {
+    pub fn set_workspaces(
+        &mut self,
+        workspaces: Vec<(Option<WorkspaceId>, SerializedWorkspaceLocation)>,
+    ) {
         self.workspaces = workspaces;
         self.has_any_non_local_projects = !self
             .workspaces
@@ -219,7 +444,7 @@ impl PickerDelegate for RecentProjectsDelegate {
     }
 
     fn match_count(&self) -> usize {
-        self.matches.len()
+        self.entries.len()
     }
 
     fn selected_index(&self) -> usize {
@@ -235,14 +460,58 @@ impl PickerDelegate for RecentProjectsDelegate {
         query: String,
         cx: &mut ViewContext<Picker<Self>>,
     ) -> gpui::Task<()> {
+        if let Some((workspace_id, name)) = self.renaming.as_mut() {
+            // The query editor holds the new name, so only the renamed project is listed.
+            *name = query.trim().to_string();
+            let workspace_id = *workspace_id;
+            self.matches = self
+                .workspaces
+                .iter()
+                .position(|(id, _)| *id == Some(workspace_id))
+                .map(|candidate_id| StringMatch {
+                    candidate_id,
+                    score: 0.,
+                    positions: Vec::new(),
+                    string: String::new(),
+                })
+                .into_iter()
+                .collect();
+            self.template_matches.clear();
+            self.build_entries();
+            self.selected_match_index = self.entry_ix_for_match(0).unwrap_or(0);
+            return Task::ready(());
+        }
+
         let query = query.trim_start();
+        self.ssh_url_query = if query.starts_with("ssh://") {
+            SshSettings::get_global(cx)
+                .connection_options_for_url(query.trim_end())
+                .ok()
+        } else {
+            None
+        };
+        let (query_filter, query) = ProjectFilter::from_query_prefix(query);
+        let filter = query_filter.unwrap_or(self.filter);
+        let nicknames = self
+            .workspaces
+            .iter()
+            .map(|(_, location)| ssh_nickname(location, cx))
+            .collect::<Vec<_>>();
         let smart_case = query.chars().any(|c| c.is_uppercase());
         let candidates = self
             .workspaces
             .iter()
             .enumerate()
-            .filter(|(_, (id, _))| !self.is_current_workspace(*id, cx))
-            .map(|(id, (_, location))| {
+            .filter(|(_, (id, location))| {
+                // Projects that were never opened are only listed when searching.
+                filter.includes(location)
+                    && match id {
+                        Some(id) => !self.is_current_workspace(*id, cx),
+                        None => !query.is_empty(),
+                    }
+            })
+            .map(|(id, (workspace_id, location))| {
+                let display_name = workspace_id.and_then(|id| self.display_names.get(&id));
                 let combined_string = match location {
                     SerializedWorkspaceLocation::Local(paths, order) => order
                         .order()
@@ -258,13 +527,23 @@ impl PickerDelegate for RecentProjectsDelegate {
                             dev_server_project.paths.join("")
                         )
                     }
-                    SerializedWorkspaceLocation::Ssh(ssh_project) => ssh_project
-                        .ssh_urls()
+                    SerializedWorkspaceLocation::Ssh(ssh_project) => nicknames[id]
                         .iter()
-                        .map(|path| path.to_string_lossy().to_string())
+                        .map(|nickname| nickname.to_string())
+                        .chain(
+                            ssh_project
+                                .ssh_urls()
+                                .iter()
+                                .map(|path| path.to_string_lossy().to_string()),
+                        )
                         .collect::<Vec<_>>()
                         .join(""),
                 };
+                // The name comes first, as it's what is shown above the paths.
+                let combined_string = match display_name {
+                    Some(display_name) => format!("{display_name}{combined_string}"),
+                    None => combined_string,
+                };
 
                 StringMatchCandidate::new(id, combined_string)
             })
@@ -277,16 +556,34 @@ impl PickerDelegate for RecentProjectsDelegate {
             &Default::default(),
             cx.background_executor().clone(),
         ));
-        self.matches.sort_unstable_by_key(|m| m.candidate_id);
+        self.templates = RecentProjectsSettings::get_global(cx).templates.clone();
+        let template_candidates = self
+            .templates
+            .iter()
+            .enumerate()
+            .filter(|(_, template)| filter.includes(&template.location()))
+            .map(|(id, template)| StringMatchCandidate::new(id, template.name.clone()))
+            .collect::<Vec<_>>();
+        self.template_matches = smol::block_on(fuzzy::match_strings(
+            template_candidates.as_slice(),
+            query,
+            smart_case,
+            100,
+            &Default::default(),
+            cx.background_executor().clone(),
+        ));
+        self.sort_matches();
 
         if self.reset_selected_match_index {
-            self.selected_match_index = self
+            let best_match_ix = self
                 .matches
                 .iter()
                 .enumerate()
                 .rev()
                 .max_by_key(|(_, m)| OrderedFloat(m.score))
-                .map(|(ix, _)| ix)
+                .map(|(ix, _)| ix);
+            self.selected_match_index = best_match_ix
+                .and_then(|best_match_ix| self.entry_ix_for_match(best_match_ix))
                 .unwrap_or(0);
         }
         self.reset_selected_match_index = true;
@@ -294,13 +591,70 @@ impl PickerDelegate for RecentProjectsDelegate {
     }
 
     fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
-        if let Some((selected_match, workspace)) = self
-            .matches
-            .get(self.selected_index())
-            .zip(self.workspace.upgrade())
+        if let Some((workspace_id, name)) = self.renaming.take() {
+            self.rename_workspace(workspace_id, name, cx);
+            return;
+        }
+
+        if let Some((connection_options, paths)) = self.ssh_url_query.clone() {
+            if let Some(workspace) = self.workspace.upgrade() {
+                let app_state = workspace.read(cx).app_state().clone();
+                let replace_current_window = if self.create_new_window {
+                    secondary
+                } else {
+                    !secondary
+                };
+                let open_options = OpenOptions {
+                    replace_window: if replace_current_window {
+                        cx.window_handle().downcast::<Workspace>()
+                    } else {
+                        None
+                    },
+                    ..Default::default()
+                };
+                cx.spawn(|_, mut cx| async move {
+                    open_ssh_project(connection_options, paths, app_state, open_options, &mut cx)
+                        .await
+                })
+                .detach_and_log_err(cx);
+            }
+            cx.emit(DismissEvent);
+            return;
+        }
+
+        if let Some(RecentProjectsEntry::Host { host, .. }) =
+            self.entries.get(self.selected_index())
         {
-            let (candidate_workspace_id, candidate_workspace_location) =
-                &self.workspaces[selected_match.candidate_id];
+            let host = host.clone();
+            if !self.collapsed_hosts.remove(&host) {
+                self.collapsed_hosts.insert(host);
+            }
+            // The header keeps its position, as only the entries below it change.
+            cx.spawn(|this, mut cx| async move {
+                this.update(&mut cx, |picker, cx| {
+                    picker.delegate.reset_selected_match_index = false;
+                    picker.update_matches(picker.query(cx), cx)
+                })
+            })
+            .detach();
+            return;
+        }
+
+        let selected = match self.entries.get(self.selected_index()) {
+            Some(RecentProjectsEntry::Template { match_ix }) => self
+                .template_matches
+                .get(*match_ix)
+                .and_then(|hit| self.templates.get(hit.candidate_id))
+                .map(|template| (None, template.location())),
+            _ => self.match_at(self.selected_index()).map(|selected_match| {
+                let (workspace_id, location) = &self.workspaces[selected_match.candidate_id];
+                (*workspace_id, location.clone())
+            }),
+        };
+        if let Some(((candidate_workspace_id, candidate_workspace_location), workspace)) =
+            selected.zip(self.workspace.upgrade())
+        {
+            let candidate_workspace_location = &candidate_workspace_location;
             let replace_current_window = if self.create_new_window {
                 secondary
             } else {
@@ -308,7 +662,9 @@ impl PickerDelegate for RecentProjectsDelegate {
             };
             workspace
                 .update(cx, |workspace, cx| {
-                    if workspace.database_id() == Some(*candidate_workspace_id) {
+                    if candidate_workspace_id.is_some()
+                        && workspace.database_id() == candidate_workspace_id
+                    {
                         Task::ready(Ok(()))
                     } else {
                         match candidate_workspace_location {
@@ -384,16 +740,26 @@ impl PickerDelegate for RecentProjectsDelegate {
                                 ..Default::default()
                             };
 
-                            let connection_options = SshConnectionOptions {
-                                host: ssh_project.host.clone(),
-                                username: ssh_project.user.clone(),
-                                port: ssh_project.port,
-                                password: None,
-                            };
+                            let connection_options = SshSettings::get_global(cx)
+                                .connection_options_for(
+                                    ssh_project.host.clone(),
+                                    ssh_project.port,
+                                    ssh_project.user.clone(),
+                                );
 
                             let paths = ssh_project.paths.iter().map(PathBuf::from).collect();
 
-                            cx.spawn(|_, mut cx| async move {
+                            cx.spawn(|workspace, mut cx| async move {
+                                if open_options.replace_window.is_some() {
+                                    let continue_replacing = workspace
+                                        .update(&mut cx, |workspace, cx| {
+                                            workspace.prepare_to_close(CloseIntent::ReplaceWindow, cx)
+                                        })?
+                                        .await?;
+                                    if !continue_replacing {
+                                        return Ok(());
+                                    }
+                                }
                                 open_ssh_project(connection_options, paths, app_state, open_options, &mut cx).await
                             })
                         }
@@ -405,10 +771,18 @@ impl PickerDelegate for RecentProjectsDelegate {
         }
     }
 
-    fn dismissed(&mut self, _: &mut ViewContext<Picker<Self>>) {}
+    fn dismissed(&mut self, _: &mut ViewContext<Picker<Self>>) {
+        self.renaming = None;
+    }
 
     fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
-        if self.workspaces.is_empty() {
+        if let Some((connection_options, _)) = &self.ssh_url_query {
+            format!(
+                "Press enter to connect to {}",
+                connection_options.connection_string()
+            )
+            .into()
+        } else if self.workspaces.is_empty() {
             "Recently opened projects will show up here".into()
         } else {
             "No matches".into()
@@ -421,9 +795,32 @@ impl PickerDelegate for RecentProjectsDelegate {
         selected: bool,
         cx: &mut ViewContext<Picker<Self>>,
     ) -> Option<Self::ListItem> {
-        let hit = self.matches.get(ix)?;
-
-        let (_, location) = self.workspaces.get(hit.candidate_id)?;
+        let (match_ix, grouped) = match self.entries.get(ix)? {
+            RecentProjectsEntry::Host {
+                host,
+                project_count,
+                collapsed,
+            } => return Some(self.render_host(ix, host, *project_count, *collapsed, selected, cx)),
+            RecentProjectsEntry::Project { match_ix, grouped } => (*match_ix, *grouped),
+            RecentProjectsEntry::Template { match_ix } => {
+                return self.render_template(ix, *match_ix, selected, cx)
+            }
+        };
+        let hit = self.matches.get(match_ix)?;
+
+        let (workspace_id, location) = self.workspaces.get(hit.candidate_id)?;
+        let is_pinned = workspace_id.map_or(false, |id| self.pinned_workspaces.contains(&id));
+        let is_missing = workspace_id.map_or(false, |id| self.missing_workspaces.contains(&id));
+        let is_unopened = workspace_id.is_none();
+        let is_unreachable = match location {
+            SerializedWorkspaceLocation::Ssh(ssh_project) => {
+                self.unreachable_hosts.contains(&SshHost::from(ssh_project))
+            }
+            _ => false,
+        };
+        let last_opened = workspace_id
+            .and_then(|id| self.timestamps.get(&id))
+            .map(|timestamp| format_timestamp(*timestamp, TimestampFormat::Relative));
 
         let dev_server_status =
             if let SerializedWorkspaceLocation::DevServer(dev_server_project) = location {
@@ -439,7 +836,15 @@ impl PickerDelegate for RecentProjectsDelegate {
                 None
             };
 
-        let mut path_start_offset = 0;
+        // The name and the nickname come before the paths in the string that was matched against.
+        let display_name = workspace_id
+            .and_then(|id| self.display_names.get(&id))
+            .map(|display_name| highlight_prefix(display_name, &hit.positions, 0));
+        let nickname_start_offset = display_name.as_ref().map_or(0, |name| name.char_count);
+        let nickname = ssh_nickname(location, cx)
+            .map(|nickname| highlight_prefix(&nickname, &hit.positions, nickname_start_offset));
+        let mut path_start_offset =
+            nickname_start_offset + nickname.as_ref().map_or(0, |nickname| nickname.char_count);
         let paths = match location {
             SerializedWorkspaceLocation::Local(paths, order) => Arc::new(
                 order
@@ -470,9 +875,24 @@ impl PickerDelegate for RecentProjectsDelegate {
             })
             .unzip();
 
+        let match_label = HighlightedText::join(match_labels.into_iter().flatten(), ", ");
+        let match_label = match nickname {
+            Some(nickname) => HighlightedText::join([nickname, match_label].into_iter(), ": "),
+            None => match_label,
+        };
+        let (match_label, is_renaming) = match &self.renaming {
+            Some((id, name)) if Some(*id) == *workspace_id => {
+                if name.is_empty() {
+                    (match_label, true)
+                } else {
+                    (highlight_prefix(name, &[], 0), true)
+                }
+            }
+            _ => (display_name.unwrap_or(match_label), false),
+        };
         let highlighted_match = HighlightedMatchWithPaths {
-            match_label: HighlightedText::join(match_labels.into_iter().flatten(), ", ").color(
-                if matches!(dev_server_status, Some(DevServerStatus::Offline)) {
+            match_label: match_label.color(
+                if is_missing || matches!(dev_server_status, Some(DevServerStatus::Offline)) {
                     Color::Disabled
                 } else {
                     Color::Default
@@ -486,39 +906,48 @@ impl PickerDelegate for RecentProjectsDelegate {
                 .selected(selected)
                 .inset(true)
                 .spacing(ListItemSpacing::Sparse)
+                .when(grouped, |item| item.indent_level(1))
                 .child(
                     h_flex()
                         .flex_grow()
                         .gap_3()
-                        .when(self.has_any_non_local_projects, |this| {
-                            this.child(match location {
-                                SerializedWorkspaceLocation::Local(_, _) => {
-                                    Icon::new(IconName::Screen)
-                                        .color(Color::Muted)
-                                        .into_any_element()
-                                }
-                                SerializedWorkspaceLocation::Ssh(_) => Icon::new(IconName::Server)
-                                    .color(Color::Muted)
-                                    .into_any_element(),
-                                SerializedWorkspaceLocation::DevServer(_) => {
-                                    let indicator_color = match dev_server_status {
-                                        Some(DevServerStatus::Online) => Color::Created,
-                                        Some(DevServerStatus::Offline) => Color::Hidden,
-                                        _ => unreachable!(),
-                                    };
-                                    IconWithIndicator::new(
-                                        Icon::new(IconName::Server).color(Color::Muted),
-                                        Some(Indicator::dot()),
-                                    )
-                                    .indicator_color(indicator_color)
-                                    .indicator_border_color(if selected {
-                                        Some(cx.theme().colors().element_selected)
-                                    } else {
-                                        None
-                                    })
-                                    .into_any_element()
-                                }
+                        .child(match location {
+                            SerializedWorkspaceLocation::Local(_, _) => Icon::new(IconName::Folder)
+                                .color(if is_missing {
+                                    Color::Disabled
+                                } else {
+                                    Color::Muted
+                                })
+                                .into_any_element(),
+                            SerializedWorkspaceLocation::Ssh(_) => IconWithIndicator::new(
+                                Icon::new(IconName::Server).color(Color::Muted),
+                                is_unreachable.then(Indicator::dot),
+                            )
+                            .indicator_color(Color::Error)
+                            .indicator_border_color(if selected {
+                                Some(cx.theme().colors().element_selected)
+                            } else {
+                                None
                             })
+                            .into_any_element(),
+                            SerializedWorkspaceLocation::DevServer(_) => {
+                                let indicator_color = match dev_server_status {
+                                    Some(DevServerStatus::Online) => Color::Created,
+                                    Some(DevServerStatus::Offline) => Color::Hidden,
+                                    _ => unreachable!(),
+                                };
+                                IconWithIndicator::new(
+                                    Icon::new(IconName::Server).color(Color::Muted),
+                                    Some(Indicator::dot()),
+                                )
+                                .indicator_color(indicator_color)
+                                .indicator_border_color(if selected {
+                                    Some(cx.theme().colors().element_selected)
+                                } else {
+                                    None
+                                })
+                                .into_any_element()
+                            }
                         })
                         .child({
                             let mut highlighted = highlighted_match.clone();
@@ -526,10 +955,101 @@ impl PickerDelegate for RecentProjectsDelegate {
                                 highlighted.paths.clear();
                             }
                             highlighted.render(cx)
-                        }),
+                        })
+                        .when(is_pinned, |this| {
+                            this.child(
+                                Icon::new(IconName::Pin)
+                                    .size(IconSize::XSmall)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .when(is_missing, |this| {
+                            this.child(
+                                Label::new("missing")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Disabled),
+                            )
+                        })
+                        .when(is_renaming, |this| {
+                            this.child(
+                                Label::new("enter a name, or leave empty to show the paths")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .when(is_unopened, |this| {
+                            this.child(
+                                Label::new("never opened")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .when(is_unreachable, |this| {
+                            this.child(
+                                Label::new("unreachable")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Error),
+                            )
+                        })
+                        .children(workspace_id.and_then(|id| self.branches.get(&id)).map(
+                            |branch| {
+                                Label::new(branch.clone())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                            },
+                        ))
+                        .children(last_opened.map(|last_opened| {
+                            div().ml_auto().child(
+                                Label::new(format!("opened {}", last_opened))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .single_line(),
+                            )
+                        })),
                 )
-                .map(|el| {
-                    let delete_button = div()
+                .when(!is_unopened, |el| {
+                    let entry_actions = h_flex()
+                        .gap_1()
+                        .child(
+                            IconButton::new("rename", IconName::Pencil)
+                                .icon_size(IconSize::Small)
+                                .on_click(cx.listener(move |picker, _event, cx| {
+                                    cx.stop_propagation();
+                                    cx.prevent_default();
+
+                                    Self::start_renaming(picker, ix, cx)
+                                }))
+                                .tooltip(|cx| {
+                                    Tooltip::for_action("Rename Project", &RenameSelected, cx)
+                                }),
+                        )
+                        .child(
+                            IconButton::new(
+                                "pin",
+                                if is_pinned {
+                                    IconName::Unpin
+                                } else {
+                                    IconName::Pin
+                                },
+                            )
+                            .icon_size(IconSize::Small)
+                            .on_click(cx.listener(move |this, _event, cx| {
+                                cx.stop_propagation();
+                                cx.prevent_default();
+
+                                this.delegate.toggle_pinned(ix, cx)
+                            }))
+                            .tooltip(move |cx| {
+                                Tooltip::text(
+                                    if is_pinned {
+                                        "Unpin from Top"
+                                    } else {
+                                        "Pin to Top"
+                                    },
+                                    cx,
+                                )
+                            }),
+                        )
                         .child(
                             IconButton::new("delete", IconName::Close)
                                 .icon_size(IconSize::Small)
@@ -539,14 +1059,21 @@ impl PickerDelegate for RecentProjectsDelegate {
 
                                     this.delegate.delete_recent_project(ix, cx)
                                 }))
-                                .tooltip(|cx| Tooltip::text("Delete from Recent Projects...", cx)),
+                                .tooltip(|cx| {
+                                    Tooltip::for_action(
+                                        "Delete from Recent Projects...",
+                                        &RemoveSelected,
+                                        cx,
+                                    )
+                                }),
                         )
                         .into_any_element();
 
-                    if self.selected_index() == ix {
-                        el.end_slot::<AnyElement>(delete_button)
+                    // Missing projects can only be removed, so that stays visible.
+                    if self.selected_index() == ix || is_missing {
+                        el.end_slot::<AnyElement>(entry_actions)
                     } else {
-                        el.end_hover_slot::<AnyElement>(delete_button)
+                        el.end_hover_slot::<AnyElement>(entry_actions)
                     }
                 })
                 .tooltip(move |cx| {
@@ -568,6 +1095,45 @@ impl PickerDelegate for RecentProjectsDelegate {
                 .border_color(cx.theme().colors().border_variant)
                 .justify_end()
                 .gap_4()
+                .child(
+                    h_flex()
+                        .pl_2()
+                        .mr_auto()
+                        .gap_1()
+                        .child(
+                            Button::new("sort-order", sort_order_label(self.sort_order))
+                                .label_size(LabelSize::Small)
+                                .icon(IconName::ArrowDown)
+                                .icon_size(IconSize::XSmall)
+                                .icon_position(IconPosition::Start)
+                                .icon_color(Color::Muted)
+                                .tooltip(|cx| {
+                                    Tooltip::for_action("Change Sort Order", &CycleSortOrder, cx)
+                                })
+                                .on_click(cx.listener(|picker, _, cx| {
+                                    picker.delegate.cycle_sort_order(cx)
+                                })),
+                        )
+                        .when(self.has_any_non_local_projects, |this| {
+                            this.children(
+                                [
+                                    ("All", ProjectFilter::All),
+                                    ("Local", ProjectFilter::Local),
+                                    ("Remote", ProjectFilter::Remote),
+                                ]
+                                .into_iter()
+                                .map(|(label, filter)| {
+                                    Button::new(label, label)
+                                        .label_size(LabelSize::Small)
+                                        .selected(self.filter == filter)
+                                        .on_click(cx.listener(move |picker, _, cx| {
+                                            picker.delegate.filter = filter;
+                                            picker.update_matches(picker.query(cx), cx);
+                                        }))
+                                }),
+                            )
+                        }),
+                )
                 .child(
                     ButtonLike::new("remote")
                         .when_some(KeyBinding::for_action(&OpenRemote, cx), |button, key| {
@@ -643,6 +1209,297 @@ fn open_dev_server_project(
     }
 }
 
+/// Loads the recent projects from the database into the picker.
+///
+/// We do not want to block the UI on a potentially lengthy call to DB, so we're gonna swap
+/// out workspace locations once the future runs to completion.
+pub(crate) fn load_recent_projects(
+    picker: WeakView<Picker<RecentProjectsDelegate>>,
+    cx: &mut WindowContext,
+) {
+    cx.spawn(|mut cx| async move {
+        let workspaces = WORKSPACE_DB
+            .recent_workspaces_including_missing()
+            .await
+            .log_err()
+            .unwrap_or_default();
+        let pinned_workspaces = WORKSPACE_DB
+            .pinned_workspaces()
+            .await
+            .log_err()
+            .unwrap_or_default();
+        let timestamps = WORKSPACE_DB
+            .workspace_timestamps()
+            .await
+            .log_err()
+            .unwrap_or_default();
+        let open_counts = WORKSPACE_DB
+            .workspace_open_counts()
+            .await
+            .log_err()
+            .unwrap_or_default();
+        let branches = WORKSPACE_DB
+            .workspace_branches()
+            .await
+            .log_err()
+            .unwrap_or_default();
+        let display_names = WORKSPACE_DB
+            .workspace_display_names()
+            .await
+            .log_err()
+            .unwrap_or_default();
+        picker
+            .update(&mut cx, move |picker, cx| {
+                let settings = RecentProjectsSettings::get_global(cx);
+                let pinned_workspaces = pinned_workspaces.into_iter().collect::<HashSet<_>>();
+                let workspaces = workspaces
+                    .into_iter()
+                    .filter(|(_, location)| !settings.is_excluded(location))
+                    .collect::<Vec<_>>();
+                let workspaces = with_configured_ssh_projects(workspaces, cx);
+                picker.delegate.set_workspaces(workspaces);
+                picker.delegate.pinned_workspaces = pinned_workspaces;
+                picker.delegate.timestamps = timestamps
+                    .into_iter()
+                    .filter_map(|(id, timestamp)| {
+                        Some((id, OffsetDateTime::from_unix_timestamp(timestamp).ok()?))
+                    })
+                    .collect();
+                picker.delegate.open_counts = open_counts.into_iter().collect();
+                picker.delegate.branches = branches
+                    .into_iter()
+                    .map(|(id, branch)| (id, branch.into()))
+                    .collect();
+                picker.delegate.display_names = display_names
+                    .into_iter()
+                    .map(|(id, display_name)| (id, display_name.into()))
+                    .collect();
+                picker.update_matches(picker.query(cx), cx);
+                picker.delegate.probe_ssh_hosts(cx);
+            })
+            .ok()?;
+
+        // Checking the file system can be slow, e.g. for folders on network drives,
+        // so the list is shown first and missing projects are grayed out afterwards.
+        let local_workspaces = picker
+            .update(&mut cx, |picker, _| {
+                picker
+                    .delegate
+                    .workspaces
+                    .iter()
+                    .filter_map(|(id, location)| match (id, location) {
+                        (Some(id), SerializedWorkspaceLocation::Local(paths, _)) => {
+                            Some((*id, paths.clone()))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .ok()?;
+        let missing_workspaces = cx
+            .background_executor()
+            .spawn(async move {
+                local_workspaces
+                    .into_iter()
+                    .filter(|(_, paths)| !paths.exist_on_disk())
+                    .map(|(id, _)| id)
+                    .collect::<HashSet<_>>()
+            })
+            .await;
+        picker
+            .update(&mut cx, move |picker, cx| {
+                if RecentProjectsSettings::get_global(cx).prune_missing {
+                    for id in &missing_workspaces {
+                        cx.background_executor()
+                            .spawn(WORKSPACE_DB.delete_workspace_by_id(*id))
+                            .detach_and_log_err(cx);
+                    }
+                    let workspaces = picker
+                        .delegate
+                        .workspaces
+                        .drain(..)
+                        .filter(|(id, _)| id.map_or(true, |id| !missing_workspaces.contains(&id)))
+                        .collect();
+                    picker.delegate.set_workspaces(workspaces);
+                    picker.delegate.missing_workspaces.clear();
+                } else {
+                    picker.delegate.missing_workspaces = missing_workspaces;
+                }
+                picker.update_matches(picker.query(cx), cx)
+            })
+            .ok()
+    })
+    .detach();
+}
+
+/// Opens the recent project at `ix`, not counting the one in this window, without
+/// showing the picker. Dev server projects are skipped.
+fn open_nth_recent(workspace: &mut Workspace, ix: usize, cx: &mut ViewContext<Workspace>) {
+    let current_workspace_id = workspace.database_id();
+    let app_state = workspace.app_state().clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let workspaces = WORKSPACE_DB.recent_workspaces_on_disk().await?;
+        let location = cx.update(|cx| {
+            let settings = RecentProjectsSettings::get_global(cx);
+            workspaces
+                .into_iter()
+                .filter(|(id, location)| {
+                    Some(*id) != current_workspace_id
+                        && !settings.is_excluded(location)
+                        && !matches!(location, SerializedWorkspaceLocation::DevServer(_))
+                })
+                .nth(ix)
+                .map(|(_, location)| location)
+        })?;
+        match location {
+            Some(SerializedWorkspaceLocation::Local(paths, _)) => {
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.open_workspace_for_paths(false, paths.paths().to_vec(), cx)
+                    })?
+                    .await
+            }
+            Some(SerializedWorkspaceLocation::Ssh(ssh_project)) => {
+                let connection_options = cx.update(|cx| {
+                    SshSettings::get_global(cx).connection_options_for(
+                        ssh_project.host.clone(),
+                        ssh_project.port,
+                        ssh_project.user.clone(),
+                    )
+                })?;
+                let paths = ssh_project.paths.iter().map(PathBuf::from).collect();
+                open_ssh_project(
+                    connection_options,
+                    paths,
+                    app_state,
+                    OpenOptions::default(),
+                    &mut cx,
+                )
+                .await
+            }
+            Some(SerializedWorkspaceLocation::DevServer(_)) | None => Ok(()),
+        }
+    })
+    .detach_and_log_err(cx);
+}
+
+fn format_timestamp(timestamp: OffsetDateTime, format: TimestampFormat) -> String {
+    time_format::format_localized_timestamp(
+        timestamp,
+        OffsetDateTime::now_utc(),
+        UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC),
+        format,
+    )
+}
+
+fn sort_order_label(sort_order: RecentProjectsSortOrder) -> &'static str {
+    match sort_order {
+        RecentProjectsSortOrder::Recency => "Recent",
+        RecentProjectsSortOrder::Alphabetical => "A–Z",
+        RecentProjectsSortOrder::Frequency => "Frequent",
+    }
+}
+
+/// The lowercased name of a project's first folder, used to sort projects alphabetically.
+fn project_name(location: &SerializedWorkspaceLocation) -> String {
+    let first_path = match location {
+        SerializedWorkspaceLocation::Local(paths, order) => order
+            .order()
+            .iter()
+            .filter_map(|i| paths.paths().get(*i))
+            .next()
+            .map(|path| path.to_string_lossy().into_owned()),
+        SerializedWorkspaceLocation::DevServer(dev_server_project) => dev_server_project
+            .paths
+            .first()
+            .map(|path| path.to_string()),
+        SerializedWorkspaceLocation::Ssh(ssh_project) => ssh_project.paths.first().cloned(),
+    };
+    first_path
+        .map(|path| {
+            let path = Path::new(&path);
+            path.file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .to_lowercase()
+        })
+        .unwrap_or_default()
+}
+
+/// Appends the projects configured in `ssh_connections` that were never opened, so
+/// that searching finds them too.
+fn with_configured_ssh_projects(
+    workspaces: Vec<(WorkspaceId, SerializedWorkspaceLocation)>,
+    cx: &AppContext,
+) -> Vec<(Option<WorkspaceId>, SerializedWorkspaceLocation)> {
+    let mut workspaces = workspaces
+        .into_iter()
+        .map(|(id, location)| (Some(id), location))
+        .collect::<Vec<_>>();
+    let opened_projects = workspaces
+        .iter()
+        .filter_map(|(_, location)| match location {
+            SerializedWorkspaceLocation::Ssh(ssh_project) => {
+                Some((SshHost::from(ssh_project), ssh_project.paths.clone()))
+            }
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    let settings = RecentProjectsSettings::get_global(cx);
+    for connection in SshSettings::get_global(cx).ssh_connections() {
+        let host = SshHost {
+            host: connection.host.to_string(),
+            port: connection.port,
+            user: connection.username.clone(),
+        };
+        for project in connection.projects {
+            if opened_projects.contains(&(host.clone(), project.paths.clone())) {
+                continue;
+            }
+            let location = SerializedWorkspaceLocation::Ssh(SerializedSshProject {
+                id: None,
+                host: host.host.clone(),
+                port: host.port,
+                paths: project.paths,
+                user: host.user.clone(),
+            });
+            if !settings.is_excluded(&location) {
+                workspaces.push((None, location));
+            }
+        }
+    }
+    workspaces
+}
+
+/// Highlights the matched characters of `text`, which starts at `start_offset` in
+/// the string that was matched against.
+fn highlight_prefix(text: &str, positions: &[usize], start_offset: usize) -> HighlightedText {
+    let char_count = text.chars().count();
+    HighlightedText {
+        text: text.to_string(),
+        highlight_positions: positions
+            .iter()
+            .filter_map(|position| position.checked_sub(start_offset))
+            .filter(|position| *position < char_count)
+            .collect(),
+        char_count,
+        color: Color::Default,
+    }
+}
+
+/// The nickname given to the server a remote project is on, if any.
+fn ssh_nickname(location: &SerializedWorkspaceLocation, cx: &AppContext) -> Option<SharedString> {
+    let SerializedWorkspaceLocation::Ssh(ssh_project) = location else {
+        return None;
+    };
+    let settings = SshSettings::get_global(cx);
+    settings.nickname_for(&settings.connection_options_for(
+        ssh_project.host.clone(),
+        ssh_project.port,
+        ssh_project.user.clone(),
+    ))
+}
+
 // Compute the highlighted text for the name and path
 fn highlights_for_path(
     path: &Path,
@@ -693,16 +1550,525 @@ fn highlights_for_path(
     )
 }
 impl RecentProjectsDelegate {
+    /// Orders matches from most to least recently opened, with pinned workspaces first.
+    fn sort_matches(&mut self) {
+        let workspaces = &self.workspaces;
+        let pinned_workspaces = &self.pinned_workspaces;
+        let open_counts = &self.open_counts;
+        let display_names = &self.display_names;
+        let sort_order = self.sort_order;
+        // Workspaces are loaded most recent first, so ties fall back to recency.
+        self.matches.sort_unstable_by(|a, b| {
+            let (a_id, a_location) = &workspaces[a.candidate_id];
+            let (b_id, b_location) = &workspaces[b.candidate_id];
+            let is_pinned =
+                |id: &Option<WorkspaceId>| id.map_or(false, |id| pinned_workspaces.contains(&id));
+            (!is_pinned(a_id))
+                .cmp(&!is_pinned(b_id))
+                .then_with(|| match sort_order {
+                    RecentProjectsSortOrder::Recency => Ordering::Equal,
+                    RecentProjectsSortOrder::Alphabetical => {
+                        let name =
+                            |id: &Option<WorkspaceId>, location: &SerializedWorkspaceLocation| {
+                                match id.and_then(|id| display_names.get(&id)) {
+                                    Some(display_name) => display_name.to_string(),
+                                    None => project_name(location),
+                                }
+                            };
+                        name(a_id, a_location).cmp(&name(b_id, b_location))
+                    }
+                    RecentProjectsSortOrder::Frequency => {
+                        let open_count = |id: &Option<WorkspaceId>| {
+                            id.and_then(|id| open_counts.get(&id).copied())
+                        };
+                        open_count(b_id).cmp(&open_count(a_id))
+                    }
+                })
+                .then(a.candidate_id.cmp(&b.candidate_id))
+        });
+        self.build_entries();
+    }
+
+    fn cycle_sort_order(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.sort_order = self.sort_order.next();
+        cx.spawn(|picker, mut cx| async move {
+            picker.update(&mut cx, |picker, cx| {
+                picker.update_matches(picker.query(cx), cx)
+            })
+        })
+        .detach();
+    }
+
+    fn ssh_host_for_match(&self, match_ix: usize) -> Option<SshHost> {
+        let (workspace_id, location) = &self.workspaces[self.matches[match_ix].candidate_id];
+        match location {
+            SerializedWorkspaceLocation::Ssh(ssh_project)
+                if !workspace_id.map_or(false, |id| self.pinned_workspaces.contains(&id)) =>
+            {
+                Some(SshHost::from(ssh_project))
+            }
+            _ => None,
+        }
+    }
+
+    /// Groups projects on hosts with more than one recent project below a header
+    /// for that host, placed where the first of the host's projects would be.
+    /// Pinned projects stay on their own at the top.
+    fn build_entries(&mut self) {
+        let mut projects_by_host = HashMap::<SshHost, Vec<usize>>::default();
+        for match_ix in 0..self.matches.len() {
+            if let Some(host) = self.ssh_host_for_match(match_ix) {
+                projects_by_host.entry(host).or_default().push(match_ix);
+            }
+        }
+
+        let mut entries = Vec::with_capacity(self.template_matches.len() + self.matches.len());
+        entries.extend(
+            (0..self.template_matches.len())
+                .map(|match_ix| RecentProjectsEntry::Template { match_ix }),
+        );
+        for match_ix in 0..self.matches.len() {
+            let Some((host, host_projects)) = self.ssh_host_for_match(match_ix).and_then(|host| {
+                let host_projects = projects_by_host.get(&host)?;
+                (host_projects.len() > 1).then_some((host, host_projects))
+            }) else {
+                entries.push(RecentProjectsEntry::Project {
+                    match_ix,
+                    grouped: false,
+                });
+                continue;
+            };
+            if host_projects[0] != match_ix {
+                continue;
+            }
+
+            let collapsed = self.collapsed_hosts.contains(&host);
+            entries.push(RecentProjectsEntry::Host {
+                host,
+                project_count: host_projects.len(),
+                collapsed,
+            });
+            if !collapsed {
+                entries.extend(host_projects.iter().map(|&match_ix| {
+                    RecentProjectsEntry::Project {
+                        match_ix,
+                        grouped: true,
+                    }
+                }));
+            }
+        }
+        self.entries = entries;
+    }
+
+    fn entry_ix_for_match(&self, match_ix: usize) -> Option<usize> {
+        self.entries.iter().position(|entry| {
+            matches!(entry, RecentProjectsEntry::Project { match_ix: ix, .. } if *ix == match_ix)
+        })
+    }
+
+    fn match_at(&self, ix: usize) -> Option<&StringMatch> {
+        match self.entries.get(ix)? {
+            RecentProjectsEntry::Project { match_ix, .. } => self.matches.get(*match_ix),
+            RecentProjectsEntry::Host { .. } | RecentProjectsEntry::Template { .. } => None,
+        }
+    }
+
+    /// Details about the selected project, shown next to the picker.
+    fn render_preview(&self, cx: &WindowContext) -> Option<AnyElement> {
+        let hit = self.match_at(self.selected_index())?;
+        let (workspace_id, location) = self.workspaces.get(hit.candidate_id)?;
+
+        let paths: Vec<SharedString> = match location {
+            SerializedWorkspaceLocation::Local(paths, order) => order
+                .order()
+                .iter()
+                .filter_map(|i| paths.paths().get(*i))
+                .map(|path| path.to_string_lossy().to_string().into())
+                .collect(),
+            SerializedWorkspaceLocation::DevServer(dev_server_project) => {
+                dev_server_project.paths.clone()
+            }
+            SerializedWorkspaceLocation::Ssh(ssh_project) => ssh_project
+                .paths
+                .iter()
+                .map(|path| path.clone().into())
+                .collect(),
+        };
+        let (host, configured) = match location {
+            SerializedWorkspaceLocation::Local(_, _) => (None, None),
+            SerializedWorkspaceLocation::DevServer(dev_server_project) => {
+                (Some(dev_server_project.dev_server_name.clone()), None)
+            }
+            SerializedWorkspaceLocation::Ssh(ssh_project) => {
+                let configured = SshSettings::get_global(cx)
+                    .ssh_connections()
+                    .any(|connection| {
+                        connection.host == ssh_project.host
+                            && connection.port == ssh_project.port
+                            && connection.username == ssh_project.user
+                    });
+                let connection_string = SshConnectionOptions {
+                    host: ssh_project.host.clone(),
+                    port: ssh_project.port,
+                    username: ssh_project.user.clone(),
+                    ..Default::default()
+                }
+                .connection_string();
+                let host = match ssh_nickname(location, cx) {
+                    Some(nickname) => format!("{nickname} ({connection_string})"),
+                    None => connection_string,
+                };
+                (Some(host), Some(configured))
+            }
+        };
+        let last_opened = workspace_id
+            .and_then(|id| self.timestamps.get(&id))
+            .map(|timestamp| format_timestamp(*timestamp, TimestampFormat::EnhancedAbsolute));
+
+        let field = |label: &'static str, value: SharedString| {
+            v_flex()
+                .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+                .child(Label::new(value))
+        };
+
+        Some(
+            v_flex()
+                .elevation_3(cx)
+                .w(rems(20.))
+                .p_2()
+                .gap_2()
+                .children(
+                    workspace_id
+                        .and_then(|id| self.display_names.get(&id))
+                        .map(|display_name| field("Name", display_name.clone())),
+                )
+                .child(
+                    v_flex()
+                        .child(
+                            Label::new(if paths.len() == 1 { "Path" } else { "Paths" })
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .children(paths.into_iter().map(Label::new)),
+                )
+                .children(host.map(|host| field("Host", host.into())))
+                .children(
+                    workspace_id
+                        .and_then(|id| self.branches.get(&id))
+                        .map(|branch| field("Branch", branch.clone())),
+                )
+                .children(configured.map(|configured| {
+                    field(
+                        "Settings",
+                        if configured {
+                            "Configured in ssh_connections".into()
+                        } else {
+                            "Not in ssh_connections".into()
+                        },
+                    )
+                }))
+                .children(last_opened.map(|last_opened| field("Last opened", last_opened.into())))
+                .into_any_element(),
+        )
+    }
+
+    fn render_host(
+        &self,
+        ix: usize,
+        host: &SshHost,
+        project_count: usize,
+        collapsed: bool,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> ListItem {
+        let settings = SshSettings::get_global(cx);
+        let connection_options =
+            settings.connection_options_for(host.host.clone(), host.port, host.user.clone());
+        let connection_string = connection_options.connection_string();
+        let (main_label, meta_label) = match settings.nickname_for(&connection_options) {
+            Some(nickname) => (nickname, Some(format!("({})", connection_string))),
+            None => (connection_string.into(), None),
+        };
+        let configured_connection = settings
+            .ssh_connections()
+            .find(|connection| {
+                connection.host == host.host
+                    && connection.port == host.port
+                    && connection.username == host.user
+            })
+            .filter(|connection| !connection.projects.is_empty());
+
+        ListItem::new(ix)
+            .selected(selected)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle(Some(!collapsed))
+            .when_some(configured_connection, |item, connection| {
+                item.end_hover_slot(
+                    IconButton::new("open-all", IconName::ArrowUpRight)
+                        .icon_size(IconSize::Small)
+                        .on_click(cx.listener(move |this, _event, cx| {
+                            cx.stop_propagation();
+                            cx.prevent_default();
+
+                            this.delegate
+                                .open_all_projects_on_host(connection.clone(), cx)
+                        }))
+                        .tooltip(|cx| Tooltip::text("Open All Projects on Server", cx)),
+                )
+            })
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        IconWithIndicator::new(
+                            Icon::new(IconName::Server).size(IconSize::XSmall),
+                            self.unreachable_hosts.contains(host).then(Indicator::dot),
+                        )
+                        .indicator_color(Color::Error)
+                        .indicator_border_color(if selected {
+                            Some(cx.theme().colors().element_selected)
+                        } else {
+                            None
+                        }),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(Label::new(main_label).size(LabelSize::Small).single_line())
+                            .children(meta_label.map(|label| {
+                                Label::new(label)
+                                    .size(LabelSize::Small)
+                                    .single_line()
+                                    .color(Color::Muted)
+                            })),
+                    )
+                    .child(
+                        Label::new(format!("{} projects", project_count))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+    }
+
+    fn render_template(
+        &self,
+        ix: usize,
+        match_ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<ListItem> {
+        let hit = self.template_matches.get(match_ix)?;
+        let template = self.templates.get(hit.candidate_id)?;
+        let paths = match template.location() {
+            SerializedWorkspaceLocation::Ssh(ssh_project) => ssh_project.ssh_urls(),
+            _ => template.paths.iter().map(PathBuf::from).collect(),
+        };
+        let mut highlighted_match = HighlightedMatchWithPaths {
+            match_label: highlight_prefix(&template.name, &hit.positions, 0),
+            paths: paths
+                .iter()
+                .map(|path| highlight_prefix(&path.to_string_lossy(), &[], 0))
+                .collect(),
+        };
+        if !self.render_paths {
+            highlighted_match.paths.clear();
+        }
+
+        Some(
+            ListItem::new(ix)
+                .selected(selected)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .child(
+                    h_flex()
+                        .flex_grow()
+                        .gap_3()
+                        .child(
+                            Icon::new(if template.host.is_some() {
+                                IconName::Server
+                            } else {
+                                IconName::FileTree
+                            })
+                            .color(Color::Muted),
+                        )
+                        .child(highlighted_match.render(cx))
+                        .child(
+                            Label::new("template")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+
+    /// Badges the ssh hosts in the picker that don't answer on their ssh port, so that
+    /// connecting to a machine that is turned off isn't attempted unknowingly.
+    fn probe_ssh_hosts(&self, cx: &mut ViewContext<Picker<Self>>) {
+        let settings = SshSettings::get_global(cx);
+        let ssh_binary = settings
+            .ssh_binary
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("ssh"));
+        let hosts = self
+            .workspaces
+            .iter()
+            .filter_map(|(_, location)| match location {
+                SerializedWorkspaceLocation::Ssh(ssh_project) => Some(SshHost::from(ssh_project)),
+                _ => None,
+            })
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .map(|host| {
+                let connection_options = settings.connection_options_for(
+                    host.host.clone(),
+                    host.port,
+                    host.user.clone(),
+                );
+                (host, connection_options)
+            })
+            .collect::<Vec<_>>();
+        if hosts.is_empty() {
+            return;
+        }
+
+        let executor = cx.background_executor().clone();
+        cx.spawn(|picker, mut cx| async move {
+            let probes = hosts.into_iter().map(|(host, connection_options)| {
+                let executor = executor.clone();
+                let ssh_binary = ssh_binary.clone();
+                async move {
+                    let reachable =
+                        probe_ssh_host(&ssh_binary, &connection_options, &executor).await;
+                    (host, reachable)
+                }
+            });
+            let unreachable_hosts = futures::future::join_all(probes)
+                .await
+                .into_iter()
+                .filter(|(_, reachable)| *reachable == Some(false))
+                .map(|(host, _)| host)
+                .collect();
+            picker.update(&mut cx, |picker, cx| {
+                picker.delegate.unreachable_hosts = unreachable_hosts;
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_all_projects_on_host(
+        &self,
+        connection: SshConnection,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) {
+        let Some(app_state) = self
+            .workspace
+            .update(cx, |workspace, _| workspace.app_state().clone())
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn(
+            |_, mut cx| async move { open_all_ssh_projects(connection, app_state, &mut cx).await },
+        )
+        .detach_and_prompt_err("Failed to connect", cx, |_, _| None);
+        cx.emit(DismissEvent);
+    }
+
+    /// Lists only the project at `ix`, and uses the query editor to type its new name.
+    fn start_renaming(picker: &mut Picker<Self>, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(selected_match) = picker.delegate.match_at(ix) else {
+            return;
+        };
+        let (Some(workspace_id), _) = picker.delegate.workspaces[selected_match.candidate_id]
+        else {
+            return;
+        };
+        let name = picker
+            .delegate
+            .display_names
+            .get(&workspace_id)
+            .map(|name| name.to_string())
+            .unwrap_or_default();
+        picker.delegate.renaming = Some((workspace_id, name.clone()));
+        picker.set_query(name, cx);
+        picker.refresh(cx);
+    }
+
+    fn rename_workspace(
+        &mut self,
+        workspace_id: WorkspaceId,
+        name: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) {
+        let name = Some(name).filter(|name| !name.is_empty());
+        match &name {
+            Some(name) => self.display_names.insert(workspace_id, name.clone().into()),
+            None => self.display_names.remove(&workspace_id),
+        };
+        cx.background_executor()
+            .spawn(WORKSPACE_DB.set_workspace_display_name(workspace_id, name))
+            .detach_and_log_err(cx);
+        // Go back to searching the recent projects.
+        cx.spawn(|picker, mut cx| async move {
+            picker.update(&mut cx, |picker, cx| {
+                picker.set_query("", cx);
+                picker.refresh(cx);
+            })
+        })
+        .detach();
+    }
+
+    fn toggle_pinned(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(selected_match) = self.match_at(ix) else {
+            return;
+        };
+        let candidate_id = selected_match.candidate_id;
+        let (Some(workspace_id), _) = self.workspaces[candidate_id] else {
+            return;
+        };
+        let pinned = self.pinned_workspaces.insert(workspace_id);
+        if !pinned {
+            self.pinned_workspaces.remove(&workspace_id);
+        }
+        cx.background_executor()
+            .spawn(WORKSPACE_DB.set_workspace_pinned(workspace_id, pinned))
+            .detach_and_log_err(cx);
+
+        self.sort_matches();
+        if let Some(ix) = self
+            .matches
+            .iter()
+            .position(|m| m.candidate_id == candidate_id)
+            .and_then(|match_ix| self.entry_ix_for_match(match_ix))
+        {
+            self.selected_match_index = ix;
+        }
+        // Pinning can add or remove host headers, so the picker needs to learn
+        // about the new number of entries.
+        cx.spawn(|this, mut cx| async move {
+            this.update(&mut cx, |picker, cx| {
+                picker.delegate.reset_selected_match_index = false;
+                picker.update_matches(picker.query(cx), cx)
+            })
+        })
+        .detach();
+    }
+
     fn delete_recent_project(&self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
-        if let Some(selected_match) = self.matches.get(ix) {
-            let (workspace_id, _) = self.workspaces[selected_match.candidate_id];
+        if let Some(selected_match) = self.match_at(ix) {
+            let (Some(workspace_id), _) = self.workspaces[selected_match.candidate_id] else {
+                return;
+            };
             cx.spawn(move |this, mut cx| async move {
                 let _ = WORKSPACE_DB.delete_workspace_by_id(workspace_id).await;
                 let workspaces = WORKSPACE_DB
-                    .recent_workspaces_on_disk()
+                    .recent_workspaces_including_missing()
                     .await
                     .unwrap_or_default();
                 this.update(&mut cx, move |picker, cx| {
+                    let workspaces = with_configured_ssh_projects(workspaces, cx);
                     picker.delegate.set_workspaces(workspaces);
                     picker.delegate.set_selected_index(ix.saturating_sub(1), cx);
                     picker.delegate.reset_selected_match_index = false;
@@ -823,9 +2189,10 @@ mod tests {
                         string: "fake candidate".to_string(),
                     }];
                     delegate.set_workspaces(vec![(
-                        WorkspaceId::default(),
+                        Some(WorkspaceId::default()),
                         SerializedWorkspaceLocation::from_local_paths(vec!["/test/path/"]),
                     )]);
+                    delegate.build_entries();
                 });
             })
             .unwrap();
@@ -863,6 +2230,23 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn test_project_filter_query_prefix() {
+        assert_eq!(
+            ProjectFilter::from_query_prefix("local: zed"),
+            (Some(ProjectFilter::Local), "zed")
+        );
+        assert_eq!(
+            ProjectFilter::from_query_prefix("ssh:zed"),
+            (Some(ProjectFilter::Remote), "zed")
+        );
+        assert_eq!(
+            ProjectFilter::from_query_prefix("ssh://host/zed"),
+            (None, "ssh://host/zed")
+        );
+        assert_eq!(ProjectFilter::from_query_prefix("zed"), (None, "zed"));
+    }
+
     fn open_recent_projects(
         workspace: &WindowHandle<Workspace>,
         cx: &mut TestAppContext,
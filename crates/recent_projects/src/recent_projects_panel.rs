@@ -0,0 +1,164 @@
+use anyhow::Result;
+use fs::Fs;
+use gpui::{
+    actions, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView, Pixels,
+    Task, View, ViewContext, WeakView,
+};
+use picker::Picker;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use std::sync::Arc;
+use ui::prelude::*;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::{load_recent_projects, RecentProjectsDelegate};
+
+actions!(recent_projects_panel, [ToggleFocus]);
+
+pub fn init(cx: &mut AppContext) {
+    RecentProjectsPanelSettings::register(cx);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<RecentProjectsPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+/// The recent projects picker, kept in a dock rather than a modal.
+pub struct RecentProjectsPanel {
+    picker: View<Picker<RecentProjectsDelegate>>,
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+}
+
+impl RecentProjectsPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let workspace_handle = workspace.weak_handle();
+        cx.new_view(|cx| {
+            let delegate = RecentProjectsDelegate::new(workspace_handle, false, true, cx);
+            let picker = cx.new_view(|cx| Picker::list(delegate, cx).modal(false));
+            load_recent_projects(picker.downgrade(), cx);
+            Self {
+                picker,
+                fs,
+                width: None,
+            }
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move { workspace.update(&mut cx, Self::new) })
+    }
+}
+
+impl EventEmitter<PanelEvent> for RecentProjectsPanel {}
+
+impl FocusableView for RecentProjectsPanel {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for RecentProjectsPanel {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("RecentProjectsPanel")
+            .size_full()
+            .child(self.picker.clone())
+    }
+}
+
+impl Panel for RecentProjectsPanel {
+    fn persistent_name() -> &'static str {
+        "RecentProjectsPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        RecentProjectsPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<RecentProjectsPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| RecentProjectsPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        // Projects may have been opened or forgotten since the panel was last shown.
+        if active {
+            load_recent_projects(self.picker.downgrade(), cx);
+        }
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        RecentProjectsPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::HistoryRerun)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Recent Projects Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RecentProjectsPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct RecentProjectsPanelSettingsContent {
+    /// Whether to show the panel button in the status bar.
+    ///
+    /// Default: false
+    pub button: Option<bool>,
+    /// Where to dock the panel.
+    ///
+    /// Default: left
+    pub dock: Option<DockPosition>,
+    /// Default width of the panel in pixels.
+    ///
+    /// Default: 240
+    pub default_width: Option<f32>,
+}
+
+impl Settings for RecentProjectsPanelSettings {
+    const KEY: Option<&'static str> = Some("recent_projects_panel");
+
+    type FileContent = RecentProjectsPanelSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
@@ -0,0 +1,548 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use futures::channel::oneshot;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{actions, AnyElement, DismissEvent, Model, Task};
+use picker::{Picker, PickerDelegate};
+use project::{PathKind, Project};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing, Tooltip};
+use util::{
+    paths::{compare_paths, PathMatcher},
+    ResultExt,
+};
+
+actions!(remote_directory_picker, [ToggleHiddenFolders]);
+
+enum DirectoryEntry {
+    /// Opens the listed directory as a project.
+    Open,
+    Parent,
+    /// Paths previously opened on the server, indexed by the match's candidate id.
+    Suggestion(StringMatch),
+    Child(StringMatch),
+    /// Opens the file on its own, indexed by the match's candidate id.
+    File(StringMatch),
+    /// Creates a folder with the typed name in the listed directory, and opens it.
+    Create(String),
+    /// Opens the typed paths, with globs expanded, as one project.
+    OpenPaths(Vec<String>),
+}
+
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// Fuzzy-matches `query` against `candidates`, keeping all of them in order if it's empty.
+fn match_candidates(
+    candidates: Vec<StringMatchCandidate>,
+    query: &str,
+    cx: &mut ViewContext<Picker<RemoteDirectoryDelegate>>,
+) -> Vec<StringMatch> {
+    if query.is_empty() {
+        candidates
+            .into_iter()
+            .map(|candidate| StringMatch {
+                candidate_id: candidate.id,
+                score: 0.,
+                positions: Vec::new(),
+                string: candidate.string,
+            })
+            .collect()
+    } else {
+        smol::block_on(fuzzy::match_strings(
+            candidates.as_slice(),
+            query,
+            false,
+            100,
+            &Default::default(),
+            cx.background_executor().clone(),
+        ))
+    }
+}
+
+/// Browses the directories on a server, for picking one to open as a project, or a single
+/// file to open on its own.
+pub(crate) struct RemoteDirectoryDelegate {
+    project: Model<Project>,
+    tx: Option<oneshot::Sender<Option<Vec<PathBuf>>>>,
+    /// The directory whose subdirectories are listed, as typed on the server (e.g. `~/src`).
+    directory: String,
+    subdirectories: Vec<String>,
+    files: Vec<String>,
+    /// Paths opened on the server before, each of which opens as one project.
+    suggestions: Vec<Vec<String>>,
+    error: Option<SharedString>,
+    show_hidden: bool,
+    entries: Vec<DirectoryEntry>,
+    selected_index: usize,
+    _list_task: Task<()>,
+}
+
+impl RemoteDirectoryDelegate {
+    pub(crate) fn new(
+        project: Model<Project>,
+        tx: oneshot::Sender<Option<Vec<PathBuf>>>,
+        directory: String,
+    ) -> Self {
+        Self {
+            project,
+            tx: Some(tx),
+            directory,
+            subdirectories: Vec::new(),
+            files: Vec::new(),
+            suggestions: Vec::new(),
+            error: None,
+            show_hidden: false,
+            entries: Vec::new(),
+            selected_index: 0,
+            _list_task: Task::ready(()),
+        }
+    }
+
+    /// Lists the contents of `directory`, replacing the ones shown.
+    pub(crate) fn navigate(&mut self, directory: String, cx: &mut ViewContext<Picker<Self>>) {
+        let list = self.project.update(cx, |project, cx| {
+            project.list_directory_entries(directory.clone(), cx)
+        });
+        self.directory = directory;
+        self.subdirectories.clear();
+        self.files.clear();
+        self.error = None;
+        self._list_task = cx.spawn(|picker, mut cx| async move {
+            let result = list.await;
+            picker
+                .update(&mut cx, |picker, cx| {
+                    match result {
+                        Ok(mut entries) => {
+                            entries.sort_by(|(a, _), (b, _)| compare_paths((a, true), (b, true)));
+                            let (subdirectories, files): (Vec<_>, Vec<_>) = entries
+                                .into_iter()
+                                .partition(|(_, kind)| *kind == PathKind::Directory);
+                            let names = |entries: Vec<(PathBuf, PathKind)>| {
+                                entries
+                                    .into_iter()
+                                    .map(|(path, _)| path.to_string_lossy().into_owned())
+                                    .collect()
+                            };
+                            picker.delegate.subdirectories = names(subdirectories);
+                            picker.delegate.files = names(files);
+                        }
+                        Err(error) => picker.delegate.error = Some(error.to_string().into()),
+                    }
+                    picker.refresh(cx);
+                })
+                .log_err();
+        });
+    }
+
+    pub(crate) fn set_suggestions(&mut self, suggestions: Vec<Vec<String>>) {
+        self.suggestions = suggestions;
+    }
+
+    pub(crate) fn toggle_hidden_folders(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.show_hidden = !self.show_hidden;
+        cx.spawn(|picker, mut cx| async move {
+            picker.update(&mut cx, |picker, cx| picker.refresh(cx))
+        })
+        .detach();
+    }
+
+    fn open(&mut self, paths: Vec<PathBuf>, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(tx) = self.tx.take() {
+            tx.send(Some(paths)).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    /// Creates `path` on the server, then opens it as the project.
+    fn create_directory(&mut self, path: String, cx: &mut ViewContext<Picker<Self>>) {
+        let create = self
+            .project
+            .update(cx, |project, cx| project.create_directory(path.clone(), cx));
+        cx.spawn(|picker, mut cx| async move {
+            let result = create.await;
+            picker.update(&mut cx, |picker, cx| match result {
+                Ok(()) => picker.delegate.open(vec![PathBuf::from(path)], cx),
+                Err(error) => {
+                    picker.delegate.error =
+                        Some(format!("Failed to create {path}: {error}").into());
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Opens `paths` as one project. A glob in a path's last component, e.g. `~/src/api-*`,
+    /// is expanded to the folders on the server that it matches.
+    fn open_paths(&mut self, paths: Vec<String>, cx: &mut ViewContext<Picker<Self>>) {
+        let mut pending = Vec::new();
+        for path in paths {
+            let path = self.resolve_path(&path).unwrap_or(path);
+            let glob = path
+                .rsplit_once('/')
+                .filter(|(_, pattern)| is_glob(pattern))
+                .map(|(parent, pattern)| {
+                    let parent = if parent.is_empty() { "/" } else { parent }.to_string();
+                    let list = self.project.update(cx, |project, cx| {
+                        project.list_subdirectories(parent.clone(), cx)
+                    });
+                    (parent, pattern.to_string(), list)
+                });
+            pending.push((path, glob));
+        }
+        cx.spawn(|picker, mut cx| async move {
+            let result = async {
+                let mut paths = Vec::new();
+                for (path, glob) in pending {
+                    let Some((parent, pattern, list)) = glob else {
+                        paths.push(PathBuf::from(path));
+                        continue;
+                    };
+                    let matcher = PathMatcher::new(&[pattern.clone()])?;
+                    let mut names = list
+                        .await?
+                        .into_iter()
+                        .filter(|name| {
+                            pattern.starts_with('.') || !name.to_string_lossy().starts_with('.')
+                        })
+                        .filter(|name| matcher.is_match(name))
+                        .collect::<Vec<_>>();
+                    anyhow::ensure!(!names.is_empty(), "No folders match {path}");
+                    names.sort_by(|a, b| compare_paths((a, true), (b, true)));
+                    paths.extend(names.into_iter().map(|name| Path::new(&parent).join(name)));
+                }
+                anyhow::Ok(paths)
+            }
+            .await;
+            picker.update(&mut cx, |picker, cx| match result {
+                Ok(paths) => picker.delegate.open(paths, cx),
+                Err(error) => {
+                    picker.delegate.error = Some(error.to_string().into());
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn child_path(&self, name: &str) -> String {
+        if self.directory.ends_with('/') {
+            format!("{}{}", self.directory, name)
+        } else {
+            format!("{}/{}", self.directory, name)
+        }
+    }
+
+    /// Resolves a directory typed into the query, relative to the listed directory.
+    fn resolve_path(&self, path: &str) -> Option<String> {
+        match path {
+            "" => Some("/".to_string()),
+            "." => Some(self.directory.clone()),
+            ".." => self.parent_path(),
+            _ if path.starts_with('/') || path.starts_with('~') => Some(path.to_string()),
+            _ => Some(self.child_path(path)),
+        }
+    }
+
+    /// Splits a query that names several paths, or a glob, which open together as one
+    /// project. Quoted paths are taken as written; unquoted words are only split apart when
+    /// each of them names an entry of the listed directory, so that names containing spaces
+    /// can still be filtered and created.
+    fn split_paths(&self, query: &str) -> Option<Vec<String>> {
+        let paths = if query.contains(['"', '\'']) {
+            shlex::split(query)?
+        } else {
+            let paths = query
+                .split_whitespace()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            let resolves = |path: &String| {
+                is_glob(path) || self.subdirectories.contains(path) || self.files.contains(path)
+            };
+            if paths.len() > 1 && !paths.iter().all(resolves) {
+                return None;
+            }
+            paths
+        };
+        (paths.len() > 1 || paths.iter().any(|path| is_glob(path))).then_some(paths)
+    }
+
+    /// The parent of the listed directory. The home directory's absolute path isn't known
+    /// here, so going up from it keeps the `~`.
+    fn parent_path(&self) -> Option<String> {
+        let directory = self.directory.trim_end_matches('/');
+        if directory.is_empty() {
+            return None;
+        }
+        if directory == "~" || directory.ends_with("/..") {
+            return Some(format!("{directory}/.."));
+        }
+        match directory.rsplit_once('/') {
+            Some(("", _)) => Some("/".to_string()),
+            Some((parent, _)) => Some(parent.to_string()),
+            None => None,
+        }
+    }
+}
+
+impl PickerDelegate for RemoteDirectoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Filter or create folders…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let query = query.trim();
+        if let Some(paths) = self.split_paths(query) {
+            self.entries = vec![DirectoryEntry::OpenPaths(paths)];
+            self.selected_index = 0;
+            return Task::ready(());
+        }
+        // Typing a path like a shell would, e.g. `~/src/` or `../`, lists that directory and
+        // keeps filtering by what follows the last `/`.
+        if let Some((directory, rest)) = query.rsplit_once('/') {
+            if let Some(directory) = self.resolve_path(directory) {
+                self.navigate(directory, cx);
+                let rest = rest.to_string();
+                cx.spawn(|picker, mut cx| async move {
+                    picker.update(&mut cx, |picker, cx| picker.set_query(rest, cx))
+                })
+                .detach_and_log_err(cx);
+                return Task::ready(());
+            }
+        }
+        // Hidden entries are listed when they are toggled on, or searched for by name.
+        let show_hidden = self.show_hidden || query.starts_with('.');
+        let name_candidates = |names: &[String]| {
+            names
+                .iter()
+                .enumerate()
+                .filter(|(_, name)| show_hidden || !name.starts_with('.'))
+                .map(|(id, name)| StringMatchCandidate::new(id, name.clone()))
+                .collect::<Vec<_>>()
+        };
+        let suggestion_candidates = self
+            .suggestions
+            .iter()
+            .enumerate()
+            .map(|(id, paths)| StringMatchCandidate::new(id, paths.join(", ")))
+            .collect::<Vec<_>>();
+
+        let suggestions = match_candidates(suggestion_candidates, query, cx);
+        let children = match_candidates(name_candidates(&self.subdirectories), query, cx);
+        let files = match_candidates(name_candidates(&self.files), query, cx);
+        let exists = children.iter().chain(&files).any(|hit| hit.string == query);
+
+        self.entries.clear();
+        if query.is_empty() {
+            self.entries.push(DirectoryEntry::Open);
+            if self.parent_path().is_some() {
+                self.entries.push(DirectoryEntry::Parent);
+            }
+        }
+        self.entries
+            .extend(suggestions.into_iter().map(DirectoryEntry::Suggestion));
+        self.entries
+            .extend(children.into_iter().map(DirectoryEntry::Child));
+        self.entries
+            .extend(files.into_iter().map(DirectoryEntry::File));
+        if !query.is_empty() && !exists {
+            self.entries.push(DirectoryEntry::Create(query.to_string()));
+        }
+        self.selected_index = 0;
+        Task::ready(())
+    }
+
+    fn confirm_update_query(&mut self, cx: &mut ViewContext<Picker<Self>>) -> Option<String> {
+        let directory = match self.entries.get(self.selected_index)? {
+            DirectoryEntry::Open
+            | DirectoryEntry::Suggestion(_)
+            | DirectoryEntry::File(_)
+            | DirectoryEntry::Create(_)
+            | DirectoryEntry::OpenPaths(_) => return None,
+            DirectoryEntry::Parent => self.parent_path()?,
+            DirectoryEntry::Child(hit) => self.child_path(&hit.string),
+        };
+        self.navigate(directory, cx);
+        Some(String::new())
+    }
+
+    /// Completes the typed name to the entries it prefixes, entering the folder once only one
+    /// matches.
+    fn confirm_completion(&self, query: String) -> Option<String> {
+        let query = query.trim();
+        if query == "~" || query == ".." {
+            return Some(format!("{query}/"));
+        }
+        let show_hidden = self.show_hidden || query.starts_with('.');
+        let completions = self
+            .subdirectories
+            .iter()
+            .map(|name| (name, true))
+            .chain(self.files.iter().map(|name| (name, false)))
+            .filter(|(name, _)| name.starts_with(query))
+            .filter(|(name, _)| show_hidden || !name.starts_with('.'))
+            .collect::<Vec<_>>();
+        let (first, first_is_dir) = *completions.first()?;
+        let mut common_prefix = first.as_str();
+        for (name, _) in &completions[1..] {
+            let common_len = common_prefix
+                .char_indices()
+                .zip(name.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(common_prefix.len().min(name.len()), |((ix, _), _)| ix);
+            common_prefix = &common_prefix[..common_len];
+        }
+        if completions.len() == 1 {
+            Some(if first_is_dir {
+                format!("{first}/")
+            } else {
+                first.clone()
+            })
+        } else if common_prefix.len() > query.len() {
+            Some(common_prefix.to_string())
+        } else {
+            None
+        }
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        match self.entries.get(self.selected_index) {
+            Some(DirectoryEntry::Open) => {
+                let path = PathBuf::from(&self.directory);
+                self.open(vec![path], cx);
+            }
+            Some(DirectoryEntry::Suggestion(hit)) => {
+                let Some(paths) = self.suggestions.get(hit.candidate_id) else {
+                    return;
+                };
+                let paths = paths.iter().map(PathBuf::from).collect();
+                self.open(paths, cx);
+            }
+            Some(DirectoryEntry::File(hit)) => {
+                let path = PathBuf::from(self.child_path(&hit.string));
+                self.open(vec![path], cx);
+            }
+            Some(DirectoryEntry::Create(name)) => {
+                let path = self.child_path(name);
+                self.create_directory(path, cx);
+            }
+            Some(DirectoryEntry::OpenPaths(paths)) => {
+                let paths = paths.clone();
+                self.open_paths(paths, cx);
+            }
+            _ => {}
+        }
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(tx) = self.tx.take() {
+            tx.send(None).ok();
+        }
+        cx.emit(DismissEvent)
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        self.error
+            .clone()
+            .unwrap_or_else(|| "No matching folders".into())
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let (icon, label) = match self.entries.get(ix)? {
+            DirectoryEntry::Open => (
+                IconName::Check,
+                Label::new(format!("Open {}", self.directory)).into_any_element(),
+            ),
+            DirectoryEntry::Parent => (IconName::ArrowUp, Label::new("..").into_any_element()),
+            DirectoryEntry::Suggestion(hit) => (
+                IconName::HistoryRerun,
+                HighlightedLabel::new(hit.string.clone(), hit.positions.clone()).into_any_element(),
+            ),
+            DirectoryEntry::Child(hit) => (
+                IconName::Folder,
+                HighlightedLabel::new(hit.string.clone(), hit.positions.clone()).into_any_element(),
+            ),
+            DirectoryEntry::File(hit) => (
+                IconName::File,
+                HighlightedLabel::new(hit.string.clone(), hit.positions.clone()).into_any_element(),
+            ),
+            DirectoryEntry::OpenPaths(paths) => (
+                IconName::Check,
+                Label::new(format!("Open {}", paths.join(", "))).into_any_element(),
+            ),
+            DirectoryEntry::Create(name) => (
+                IconName::Plus,
+                Label::new(format!("Create and Open \"{name}\"")).into_any_element(),
+            ),
+        };
+        Some(
+            ListItem::new(ix)
+                .spacing(ListItemSpacing::Sparse)
+                .inset(true)
+                .selected(selected)
+                .start_slot(Icon::new(icon).color(Color::Muted))
+                .child(label),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_2()
+                .justify_between()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(match &self.error {
+                    Some(error) => Label::new(error.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Error)
+                        .single_line(),
+                    None => Label::new(self.directory.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .single_line(),
+                })
+                .child(
+                    Button::new("toggle-hidden-folders", "Hidden Folders")
+                        .label_size(LabelSize::Small)
+                        .selected(self.show_hidden)
+                        .on_click(
+                            cx.listener(|picker, _, cx| picker.delegate.toggle_hidden_folders(cx)),
+                        )
+                        .tooltip(|cx| {
+                            Tooltip::for_action("Toggle Hidden Folders", &ToggleHiddenFolders, cx)
+                        }),
+                )
+                .into_any_element(),
+        )
+    }
+}
@@ -1,36 +1,143 @@
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    collections::VecDeque,
+    mem,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use anyhow::Result;
-use auto_update::AutoUpdater;
+use auto_update::{AutoUpdater, DownloadProgress};
+use collections::HashMap;
 use editor::Editor;
-use futures::channel::oneshot;
+use fs::Fs;
+use futures::{
+    channel::{mpsc, oneshot},
+    future::Either,
+    StreamExt as _,
+};
 use gpui::{
-    percentage, px, Animation, AnimationExt, AnyWindowHandle, AsyncAppContext, DismissEvent,
-    EventEmitter, FocusableView, ParentElement as _, Render, SemanticVersion, SharedString, Task,
-    Transformation, View,
+    percentage, px, Animation, AnimationExt, AnyWindowHandle, AsyncAppContext, BackgroundExecutor,
+    DismissEvent, EventEmitter, FocusableView, ParentElement as _, Render, SemanticVersion,
+    SharedString, Task, Transformation, View,
 };
 use gpui::{AppContext, Model};
 use release_channel::{AppVersion, ReleaseChannel};
-use remote::{SshConnectionOptions, SshPlatform, SshRemoteClient};
+use remote::{
+    SshAddressFamily, SshConnectionOptions, SshJumpHost, SshPlatform, SshPortForward,
+    SshRemoteClient, SshUploadMethod,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use settings::{Settings, SettingsSources};
+use settings::{update_settings_file, Settings, SettingsSources};
 use ui::{
-    div, h_flex, prelude::*, v_flex, ActiveTheme, Color, Icon, IconName, IconSize,
-    InteractiveElement, IntoElement, Label, LabelCommon, Styled, ViewContext, VisualContext,
-    WindowContext,
+    div, h_flex, prelude::*, v_flex, ActiveTheme, Checkbox, Color, Icon, IconName, IconSize,
+    InteractiveElement, IntoElement, Label, LabelCommon, Selection, Styled, ViewContext,
+    VisualContext, WindowContext,
 };
-use workspace::{AppState, ModalView, Workspace};
+use util::ResultExt;
+use workspace::{AppState, ModalView, RemotePathsNotFound, Workspace};
+
+use crate::DevServerProjects;
 
 #[derive(Deserialize)]
 pub struct SshSettings {
     pub ssh_connections: Option<Vec<SshConnection>>,
+    pub ssh_binary: Option<PathBuf>,
+    pub remote_server_binary: Option<PathBuf>,
+    pub remote_server_download_url: Option<String>,
+    pub heartbeat_interval: Option<u64>,
+    pub max_missed_heartbeats: Option<usize>,
+    pub reconnect_in_background: Option<bool>,
+    pub reopen_when_reachable: Option<bool>,
 }
 
 impl SshSettings {
+    /// Fills in options a connection leaves unset from the global settings.
+    pub fn apply_defaults(&self, connection_options: &mut SshConnectionOptions) {
+        connection_options.heartbeat_interval = connection_options
+            .heartbeat_interval
+            .or(self.heartbeat_interval.map(Duration::from_secs));
+        connection_options.max_missed_heartbeats = connection_options
+            .max_missed_heartbeats
+            .or(self.max_missed_heartbeats);
+    }
+
+    pub fn reconnect_in_background(&self) -> bool {
+        self.reconnect_in_background.unwrap_or(true)
+    }
+
+    pub fn reopen_when_reachable(&self) -> bool {
+        self.reopen_when_reachable.unwrap_or(true)
+    }
+
     pub fn ssh_connections(&self) -> impl Iterator<Item = SshConnection> {
         self.ssh_connections.clone().into_iter().flatten()
     }
+
+    /// The nickname given to the host in settings, if any.
+    pub fn nickname_for(&self, connection_options: &SshConnectionOptions) -> Option<SharedString> {
+        self.ssh_connections()
+            .find(|connection| {
+                connection.host == connection_options.host
+                    && connection.port == connection_options.port
+                    && connection.username == connection_options.username
+            })
+            .and_then(|connection| connection.nickname)
+    }
+
+    /// The path to open when connecting to the given host without one, like `ssh host`
+    /// starts in the home directory.
+    pub fn default_path_for(&self, connection_options: &SshConnectionOptions) -> String {
+        self.ssh_connections()
+            .find(|connection| {
+                connection.host == connection_options.host
+                    && connection.port == connection_options.port
+                    && connection.username == connection_options.username
+            })
+            .and_then(|connection| connection.default_path)
+            .unwrap_or_else(|| "~".to_string())
+    }
+
+    /// Builds connection options for the given host, applying any per-connection
+    /// configuration from settings that matches it.
+    pub fn connection_options_for(
+        &self,
+        host: String,
+        port: Option<u16>,
+        username: Option<String>,
+    ) -> SshConnectionOptions {
+        self.ssh_connections()
+            .find(|connection| {
+                connection.host == host
+                    && connection.port == port
+                    && connection.username == username
+            })
+            .map(SshConnectionOptions::from)
+            .unwrap_or_else(|| SshConnectionOptions {
+                host,
+                port,
+                username,
+                ..Default::default()
+            })
+    }
+
+    /// Parses an `ssh://` url into connection options, applying any settings
+    /// configured for its host, and the paths to open there.
+    pub fn connection_options_for_url(
+        &self,
+        url: &str,
+    ) -> Result<(SshConnectionOptions, Vec<PathBuf>)> {
+        let (url_options, paths) = SshConnectionOptions::parse_ssh_url(url)?;
+        let connection_options = SshConnectionOptions {
+            password: url_options.password,
+            ..self.connection_options_for(url_options.host, url_options.port, url_options.username)
+        };
+        Ok((connection_options, paths))
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -41,10 +148,197 @@ pub struct SshConnection {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub port: Option<u16>,
     pub projects: Vec<SshProject>,
+    /// Path opened when connecting to this server without one, e.g. "~/src".
+    ///
+    /// Default: the home directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_path: Option<String>,
     /// Name to use for this server in UI.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nickname: Option<SharedString>,
+    /// Tags used to group and filter servers in UI, e.g. "work" or "homelab".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Seconds of inactivity after which a keepalive message is sent to the server.
+    ///
+    /// Default: none (ssh's own configuration applies)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_alive_interval: Option<u64>,
+    /// Number of unanswered keepalive messages before the connection is considered lost.
+    ///
+    /// Default: none (ssh's own configuration applies)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_alive_count_max: Option<u64>,
+    /// Local ports to forward to the remote host while connected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub port_forwards: Vec<SshPortForwardOption>,
+    /// Local port on which to open a SOCKS5 proxy into the remote network.
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dynamic_forward_port: Option<u16>,
+    /// Whether to compress traffic over the connection. Useful on
+    /// high-latency or low-bandwidth links.
+    ///
+    /// Default: false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<bool>,
+    /// Whether to share one authenticated ssh connection between all projects
+    /// opened on this host, so that authentication only happens once.
+    ///
+    /// Default: false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiplex: Option<bool>,
+    /// Path to an ssh certificate to authenticate with, e.g. a short-lived
+    /// certificate issued by your organization's certificate authority.
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate_file: Option<String>,
+    /// Whether to authenticate using GSSAPI, so that hosts accepting Kerberos
+    /// tickets can be opened without a password prompt.
+    ///
+    /// Default: none (ssh's own configuration applies)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gssapi_authentication: Option<bool>,
+    /// Whether to delegate your Kerberos credentials to the remote host.
+    ///
+    /// Default: none (ssh's own configuration applies)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gssapi_delegate_credentials: Option<bool>,
+    /// Environment variables to set for the remote server and remote terminals
+    /// on this host, e.g. `HTTP_PROXY` or toolchain paths.
+    ///
+    /// Default: {}
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    /// Shell used to launch the remote server and remote terminals on this
+    /// host, for hosts with a restricted or unusual login shell.
+    ///
+    /// Default: none (the login shell is used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    /// Command to run on the remote host right after connecting, e.g.
+    /// `mkdir -p ~/.cache/zed`. Its output is shown while connecting. It runs in a
+    /// shell of its own, so environment variables it sets don't carry over to the
+    /// remote server.
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init_command: Option<String>,
+    /// Which IP address family to connect over. Setting this to "inet" avoids
+    /// hangs on hosts that resolve to unreachable IPv6 addresses.
+    ///
+    /// Default: none (ssh's own configuration applies)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address_family: Option<AddressFamily>,
+    /// Local address to connect from, e.g. to go through a VPN rather than the
+    /// LAN on a machine with multiple network interfaces.
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bind_address: Option<String>,
+    /// Local network interface to connect from, e.g. "utun3".
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bind_interface: Option<String>,
+    /// Hosts to connect through, in order, to reach hosts that aren't
+    /// directly reachable. Each hop authenticates separately.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jump_hosts: Vec<SshJumpHostOption>,
+    /// Local command to run before connecting, e.g. to bring up a VPN or
+    /// run `aws sso login`. The connection is aborted if it fails.
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pre_connect_command: Option<String>,
+    /// Local command to run after the remote project is closed or the
+    /// connection drops, e.g. to tear down a VPN or stop a cloud instance.
+    /// It is killed if it runs longer than 30 seconds.
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_disconnect_command: Option<String>,
+    /// HTTP(S) or SOCKS proxy used to download the remote server binary for
+    /// this connection, e.g. "http://proxy.example.com:8080". Falls back to
+    /// the global `proxy` setting.
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_proxy: Option<String>,
+    /// Path to a locally built `remote_server` binary, optionally gzipped, to
+    /// upload to this host instead of downloading a release. Overrides the
+    /// global `remote_server_binary` setting.
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_binary: Option<PathBuf>,
+    /// How to copy the remote server binary to the host. Use "sftp" for hosts
+    /// that restrict the exec channels scp relies on.
+    ///
+    /// Default: scp, falling back to sftp if it fails
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upload_method: Option<UploadMethod>,
+    /// Whether to only use a `zed-remote-server` that is already installed on
+    /// the host's PATH, instead of uploading one when none matches this
+    /// version of Zed. A matching installed server is always preferred.
+    ///
+    /// Default: false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_system_server: Option<bool>,
+    /// Seconds the connection may be idle before a heartbeat is sent.
+    /// Overrides the global `heartbeat_interval` setting.
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heartbeat_interval: Option<u64>,
+    /// Number of consecutive missed heartbeats after which the connection is
+    /// considered lost. Overrides the global `max_missed_heartbeats` setting.
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_missed_heartbeats: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressFamily {
+    /// Use either IPv4 or IPv6.
+    Any,
+    /// Use IPv4 only.
+    Inet,
+    /// Use IPv6 only.
+    Inet6,
+}
+
+impl From<AddressFamily> for SshAddressFamily {
+    fn from(val: AddressFamily) -> Self {
+        match val {
+            AddressFamily::Any => SshAddressFamily::Any,
+            AddressFamily::Inet => SshAddressFamily::Inet,
+            AddressFamily::Inet6 => SshAddressFamily::Inet6,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadMethod {
+    /// Copy with scp only.
+    Scp,
+    /// Copy with sftp only.
+    Sftp,
 }
+
+impl From<UploadMethod> for SshUploadMethod {
+    fn from(val: UploadMethod) -> Self {
+        match val {
+            UploadMethod::Scp => SshUploadMethod::Scp,
+            UploadMethod::Sftp => SshUploadMethod::Sftp,
+        }
+    }
+}
+
 impl From<SshConnection> for SshConnectionOptions {
     fn from(val: SshConnection) -> Self {
         SshConnectionOptions {
@@ -52,6 +346,80 @@ impl From<SshConnection> for SshConnectionOptions {
             username: val.username,
             port: val.port,
             password: None,
+            server_alive_interval: val.server_alive_interval,
+            server_alive_count_max: val.server_alive_count_max,
+            port_forwards: val
+                .port_forwards
+                .into_iter()
+                .map(SshPortForward::from)
+                .collect(),
+            dynamic_forward_port: val.dynamic_forward_port,
+            compression: val.compression,
+            multiplex: val.multiplex.unwrap_or(false),
+            certificate_file: val.certificate_file,
+            gssapi_authentication: val.gssapi_authentication,
+            gssapi_delegate_credentials: val.gssapi_delegate_credentials,
+            env: val.env,
+            shell: val.shell,
+            init_command: val.init_command,
+            address_family: val.address_family.map(SshAddressFamily::from),
+            bind_address: val.bind_address,
+            bind_interface: val.bind_interface,
+            jump_hosts: val.jump_hosts.into_iter().map(SshJumpHost::from).collect(),
+            pre_connect_command: val.pre_connect_command,
+            post_disconnect_command: val.post_disconnect_command,
+            download_proxy: val.download_proxy,
+            server_binary: val.server_binary,
+            upload_method: val.upload_method.map(SshUploadMethod::from),
+            require_system_server: val.require_system_server.unwrap_or(false),
+            heartbeat_interval: val.heartbeat_interval.map(Duration::from_secs),
+            max_missed_heartbeats: val.max_missed_heartbeats,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SshPortForwardOption {
+    /// Local address to bind the forwarded port to.
+    ///
+    /// Default: "localhost"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_host: Option<String>,
+    pub local_port: u16,
+    /// Host to connect to, as seen from the remote machine.
+    ///
+    /// Default: "localhost"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_host: Option<String>,
+    pub remote_port: u16,
+}
+
+impl From<SshPortForwardOption> for SshPortForward {
+    fn from(val: SshPortForwardOption) -> Self {
+        SshPortForward {
+            local_host: val.local_host,
+            local_port: val.local_port,
+            remote_host: val.remote_host.unwrap_or_else(|| "localhost".to_string()),
+            remote_port: val.remote_port,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SshJumpHostOption {
+    pub host: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+}
+
+impl From<SshJumpHostOption> for SshJumpHost {
+    fn from(val: SshJumpHostOption) -> Self {
+        SshJumpHost {
+            host: val.host,
+            username: val.username,
+            port: val.port,
         }
     }
 }
@@ -64,6 +432,45 @@ pub struct SshProject {
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct RemoteSettingsContent {
     pub ssh_connections: Option<Vec<SshConnection>>,
+    /// Path to the ssh executable to use for remote projects, e.g. a newer
+    /// OpenSSH than the system one. PuTTY's plink can be used too, in which
+    /// case authentication goes through Pageant or a password prompt.
+    ///
+    /// Default: none (ssh is looked up on PATH, or plink on Windows)
+    pub ssh_binary: Option<PathBuf>,
+    /// Path to a locally built `remote_server` binary, optionally gzipped, to
+    /// upload to every remote host instead of downloading a release.
+    ///
+    /// Default: none
+    pub remote_server_binary: Option<PathBuf>,
+    /// Base URL of a mirror to download remote server releases from instead
+    /// of zed.dev, e.g. for air-gapped networks. It must serve the same
+    /// `/api/releases/latest` endpoint.
+    ///
+    /// Default: none
+    pub remote_server_download_url: Option<String>,
+    /// Seconds the connection to a remote server may be idle before a
+    /// heartbeat is sent to check that it's still alive.
+    ///
+    /// Default: 5
+    pub heartbeat_interval: Option<u64>,
+    /// Number of consecutive missed heartbeats after which the connection is
+    /// considered lost and Zed tries to reconnect. Raise this on
+    /// high-latency links such as satellite connections.
+    ///
+    /// Default: 5
+    pub max_missed_heartbeats: Option<usize>,
+    /// Whether to reconnect to a lost host without opening the connection
+    /// modal. Progress is shown in a toast instead, and the modal only
+    /// appears when a password or other input is needed.
+    ///
+    /// Default: true
+    pub reconnect_in_background: Option<bool>,
+    /// Whether to reopen a project whose connection was lost as soon as its
+    /// host is reachable again, restoring the files that were open.
+    ///
+    /// Default: true
+    pub reopen_when_reachable: Option<bool>,
 }
 
 impl Settings for SshSettings {
@@ -78,15 +485,196 @@ impl Settings for SshSettings {
 
 pub struct SshPrompt {
     connection_string: SharedString,
+    connection_options: SshConnectionOptions,
     status_message: Option<SharedString>,
     error_message: Option<SharedString>,
     prompt: Option<(SharedString, oneshot::Sender<Result<String>>)>,
+    /// Prompts that arrived while another one was still waiting for an answer.
+    queued_prompts: VecDeque<(String, oneshot::Sender<Result<String>>)>,
+    /// Prompts already answered during this connection attempt, shown as
+    /// completed steps of a multi-step login.
+    answered_prompts: Vec<SharedString>,
+    host_key: Option<HostKeyPrompt>,
+    /// Whether the user rejected the host key during this connection attempt, after
+    /// which ssh fails the same way as when the key doesn't match.
+    rejected_host_key: bool,
+    security_key_prompt: Option<SharedString>,
+    /// Whether to store the next password entered in the system keychain.
+    remember_password: bool,
+    /// The password to store in the keychain once the connection succeeds, so that
+    /// mistyped passwords aren't remembered.
+    password_to_remember: Option<String>,
+    retry: Option<oneshot::Sender<()>>,
+    /// Paths to open that don't exist on the server, waiting for the user to create or
+    /// correct them.
+    missing_paths: Option<(Vec<PathBuf>, oneshot::Sender<MissingPathsAction>)>,
     editor: View<Editor>,
+    /// Status messages, errors and output from ssh and the remote server,
+    /// shown on request to help debug failed connections.
+    log: VecDeque<SharedString>,
+    show_log: bool,
+    log_tx: mpsc::UnboundedSender<String>,
+    /// Receives the same lines as `log`, to be appended to the host's log file.
+    log_file_tx: mpsc::UnboundedSender<String>,
+    _log_task: Task<()>,
+}
+
+/// What to do about paths to open that don't exist on the server.
+pub enum MissingPathsAction {
+    /// Create the missing paths as directories.
+    Create,
+    /// Open `replacement` instead of the missing `path`.
+    Replace { path: PathBuf, replacement: PathBuf },
+}
+
+const MAX_LOG_LINES: usize = 1000;
+
+/// Log files that grow larger than this are moved aside to a `.log.old` file, replacing
+/// the previous one, and started over.
+const MAX_LOG_FILE_SIZE: u64 = 1024 * 1024;
+
+/// The file that output of connection attempts to this host is persisted to.
+pub fn connection_log_path(connection_options: &SshConnectionOptions) -> PathBuf {
+    let file_name = connection_options
+        .connection_string()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '@') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    paths::remote_connection_logs_dir().join(format!("{file_name}.log"))
+}
+
+async fn write_connection_log(path: PathBuf, mut lines: mpsc::UnboundedReceiver<String>) {
+    use std::io::Write as _;
+
+    let open = || -> Result<(std::fs::File, u64)> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let len = std::fs::metadata(&path).map_or(0, |metadata| metadata.len());
+        if len > MAX_LOG_FILE_SIZE {
+            std::fs::rename(&path, path.with_extension("log.old"))?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        let len = file.metadata()?.len();
+        Ok((file, len))
+    };
+
+    let Some((mut file, mut len)) = open().log_err() else {
+        return;
+    };
+    let mut line = format!(
+        "--- connection attempt at {} ---",
+        chrono::Local::now().to_rfc3339()
+    );
+    loop {
+        if writeln!(file, "{line}").log_err().is_none() {
+            break;
+        }
+        len += line.len() as u64 + 1;
+        if len > MAX_LOG_FILE_SIZE {
+            let Some(reopened) = open().log_err() else {
+                break;
+            };
+            (file, len) = reopened;
+        }
+        match lines.next().await {
+            Some(next_line) => line = next_line,
+            None => break,
+        }
+    }
+}
+
+/// The key under which the password for a connection is stored in the system keychain.
+fn keychain_url(connection_options: &SshConnectionOptions) -> String {
+    format!("ssh://{}", connection_options.connection_string())
+}
+
+fn is_password_prompt(prompt: &str) -> bool {
+    prompt.to_lowercase().contains("password")
+}
+
+/// Whether the prompt asks for a one-time code from a second factor, which
+/// isn't secret once used and is easier to type when visible.
+fn is_verification_code_prompt(prompt: &str) -> bool {
+    let prompt = prompt.to_lowercase();
+    // These are one-time codes even when the prompt calls them a password,
+    // e.g. "One-time password:".
+    if ["verification", "otp", "one-time", "one time"]
+        .iter()
+        .any(|pattern| prompt.contains(pattern))
+    {
+        return true;
+    }
+    if is_password_prompt(&prompt) {
+        return false;
+    }
+    [
+        "2fa",
+        "two-factor",
+        "authenticator",
+        "token code",
+        "passcode",
+    ]
+    .iter()
+    .any(|pattern| prompt.contains(pattern))
+}
+
+/// How long ssh waits for a security key to be touched before giving up.
+const SECURITY_KEY_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn is_security_key_prompt(prompt: &str) -> bool {
+    prompt.contains("Confirm user presence") || prompt.contains("touch your security key")
+}
+
+/// An unknown host key that ssh asks the user to accept before connecting.
+#[derive(Debug, Clone, PartialEq)]
+struct HostKeyPrompt {
+    host: SharedString,
+    key_type: SharedString,
+    fingerprint: SharedString,
+}
+
+impl HostKeyPrompt {
+    fn parse(prompt: &str) -> Option<Self> {
+        if !prompt.contains("yes/no") {
+            return None;
+        }
+        let host = prompt
+            .lines()
+            .find_map(|line| {
+                let rest = line.trim().strip_prefix("The authenticity of host '")?;
+                Some(rest.split('\'').next()?.to_string())
+            })
+            .unwrap_or_else(|| "unknown host".to_string());
+        let (key_type, fingerprint) = prompt.lines().find_map(|line| {
+            let (key_type, fingerprint) = line.trim().split_once(" key fingerprint is ")?;
+            Some((
+                key_type.to_string(),
+                fingerprint.trim_end_matches('.').to_string(),
+            ))
+        })?;
+        Some(Self {
+            host: host.into(),
+            key_type: key_type.into(),
+            fingerprint: fingerprint.into(),
+        })
+    }
 }
 
 pub struct SshConnectionModal {
     pub(crate) prompt: View<SshPrompt>,
     is_separate_window: bool,
+    /// Dropped along with the modal, which cancels the connection attempt.
+    cancel_tx: Option<oneshot::Sender<()>>,
 }
 
 impl SshPrompt {
@@ -95,13 +683,63 @@ impl SshPrompt {
         cx: &mut ViewContext<Self>,
     ) -> Self {
         let connection_string = connection_options.connection_string().into();
+        let (log_tx, mut log_rx) = mpsc::unbounded::<String>();
+        let (log_file_tx, log_file_rx) = mpsc::unbounded::<String>();
+        // Detached so that lines logged just before the prompt is dropped still
+        // make it to disk; the task ends once `log_file_tx` is dropped.
+        cx.background_executor()
+            .spawn(write_connection_log(
+                connection_log_path(connection_options),
+                log_file_rx,
+            ))
+            .detach();
+        let _log_task = cx.spawn(|this, mut cx| async move {
+            while let Some(output) = log_rx.next().await {
+                if this
+                    .update(&mut cx, |this, cx| this.append_log(&output, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
         Self {
             connection_string,
+            connection_options: connection_options.clone(),
             status_message: None,
             error_message: None,
             prompt: None,
+            queued_prompts: VecDeque::new(),
+            answered_prompts: Vec::new(),
+            host_key: None,
+            rejected_host_key: false,
+            security_key_prompt: None,
+            remember_password: false,
+            password_to_remember: None,
+            retry: None,
+            missing_paths: None,
             editor: cx.new_view(Editor::single_line),
+            log: VecDeque::new(),
+            show_log: false,
+            log_tx,
+            log_file_tx,
+            _log_task,
+        }
+    }
+
+    pub(crate) fn log_sender(&self) -> mpsc::UnboundedSender<String> {
+        self.log_tx.clone()
+    }
+
+    fn append_log(&mut self, output: &str, cx: &mut ViewContext<Self>) {
+        for line in output.lines().filter(|line| !line.trim().is_empty()) {
+            if self.log.len() == MAX_LOG_LINES {
+                self.log.pop_front();
+            }
+            self.log.push_back(line.to_string().into());
+            self.log_file_tx.unbounded_send(line.to_string()).ok();
         }
+        cx.notify();
     }
 
     pub fn set_prompt(
@@ -110,42 +748,350 @@ impl SshPrompt {
         tx: oneshot::Sender<Result<String>>,
         cx: &mut ViewContext<Self>,
     ) {
+        // Security key prompts are only notifications: ssh doesn't read an answer
+        // and kills the askpass program once the key has been touched.
+        if is_security_key_prompt(&prompt) {
+            tx.send(Ok(String::new())).ok();
+            self.security_key_prompt = Some(prompt.into());
+            self.status_message.take();
+            cx.notify();
+            return;
+        }
+
+        if self.prompt.is_some() {
+            self.queued_prompts.push_back((prompt, tx));
+            cx.notify();
+            return;
+        }
+
+        self.show_prompt(prompt, tx, cx);
+    }
+
+    fn show_prompt(
+        &mut self,
+        prompt: String,
+        tx: oneshot::Sender<Result<String>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let is_verification_code = is_verification_code_prompt(&prompt);
         self.editor.update(cx, |editor, cx| {
-            if prompt.contains("yes/no") {
+            if prompt.contains("yes/no") || is_verification_code {
                 editor.set_masked(false, cx);
             } else {
                 editor.set_masked(true, cx);
             }
+            editor.set_placeholder_text(
+                if is_verification_code {
+                    "Numeric code from your authenticator"
+                } else {
+                    ""
+                },
+                cx,
+            );
         });
+        self.host_key = HostKeyPrompt::parse(&prompt);
         self.prompt = Some((prompt.into(), tx));
         self.status_message.take();
         cx.focus_view(&self.editor);
         cx.notify();
     }
 
+    /// When connecting through jump hosts, works out which hop a prompt comes
+    /// from, returning its position and name.
+    fn hop_for_prompt(&self, prompt: &str) -> Option<(usize, String)> {
+        let jump_hosts = &self.connection_options.jump_hosts;
+        if jump_hosts.is_empty() {
+            return None;
+        }
+        let mentions_host = |host: &str| {
+            prompt.contains(&format!("@{}'", host)) || prompt.contains(&format!("@{})", host))
+        };
+        jump_hosts
+            .iter()
+            .enumerate()
+            .find(|(_, jump_host)| mentions_host(&jump_host.host))
+            .map(|(ix, jump_host)| (ix + 1, jump_host.to_string()))
+            .or_else(|| {
+                mentions_host(&self.connection_options.host)
+                    .then(|| (jump_hosts.len() + 1, self.connection_options.host.clone()))
+            })
+    }
+
+    fn show_next_prompt(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some((prompt, tx)) = self.queued_prompts.pop_front() {
+            self.show_prompt(prompt, tx, cx);
+        }
+        cx.notify();
+    }
+
     pub fn set_status(&mut self, status: Option<String>, cx: &mut ViewContext<Self>) {
+        if let Some(status) = &status {
+            self.append_log(status, cx);
+        }
+        self.security_key_prompt.take();
         self.status_message = status.map(|s| s.into());
         cx.notify();
     }
 
     pub fn set_error(&mut self, error_message: String, cx: &mut ViewContext<Self>) {
+        self.append_log(&error_message, cx);
         self.error_message = Some(error_message.into());
         cx.notify();
     }
 
+    /// Offers to retry the connection. `tx` is notified when the user asks for a retry.
+    pub fn set_retry(&mut self, tx: oneshot::Sender<()>, cx: &mut ViewContext<Self>) {
+        self.retry = Some(tx);
+        cx.notify();
+    }
+
+    /// Offers to create the missing `paths`, or to correct the first of them in the editor.
+    pub fn set_missing_paths(
+        &mut self,
+        paths: Vec<PathBuf>,
+        tx: oneshot::Sender<MissingPathsAction>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let first_path = paths
+            .first()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.editor.update(cx, |editor, cx| {
+            editor.set_masked(false, cx);
+            editor.set_placeholder_text("Path to open", cx);
+            editor.set_text(first_path, cx);
+        });
+        self.missing_paths = Some((paths, tx));
+        cx.focus_view(&self.editor);
+        cx.notify();
+    }
+
+    fn resolve_missing_paths(&mut self, create: bool, cx: &mut ViewContext<Self>) {
+        let Some((paths, tx)) = self.missing_paths.take() else {
+            return;
+        };
+        let action = if create {
+            MissingPathsAction::Create
+        } else {
+            let replacement = self.editor.read(cx).text(cx).trim().to_string();
+            match paths.first() {
+                Some(path) if !replacement.is_empty() => MissingPathsAction::Replace {
+                    path: path.clone(),
+                    replacement: PathBuf::from(replacement),
+                },
+                _ => {
+                    self.missing_paths = Some((paths, tx));
+                    return;
+                }
+            }
+        };
+        self.editor.update(cx, |editor, cx| editor.clear(cx));
+        self.error_message.take();
+        self.status_message.take();
+        tx.send(action).ok();
+        cx.notify();
+    }
+
+    fn retry(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(tx) = self.retry.take() {
+            self.error_message.take();
+            self.status_message.take();
+            self.answered_prompts.clear();
+            self.rejected_host_key = false;
+            tx.send(()).ok();
+            cx.notify();
+        }
+    }
+
+    /// Stores the password entered during this connection attempt in the keychain,
+    /// if the user asked to remember it.
+    fn store_remembered_password(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(password) = self.password_to_remember.take() {
+            let username = self.connection_options.username.clone().unwrap_or_default();
+            cx.write_credentials(
+                &keychain_url(&self.connection_options),
+                &username,
+                password.as_bytes(),
+            )
+            .detach_and_log_err(cx);
+        }
+    }
+
+    fn is_host_key_mismatch(&self) -> bool {
+        self.error_message.as_ref().map_or(false, |error| {
+            error.contains("REMOTE HOST IDENTIFICATION HAS CHANGED")
+                || (error.contains("Host key verification failed") && !self.rejected_host_key)
+        })
+    }
+
+    fn forget_host_key_and_retry(&mut self, cx: &mut ViewContext<Self>) {
+        let connection_options = self.connection_options.clone();
+        let ssh_binary = SshSettings::get_global(cx)
+            .ssh_binary
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("ssh"));
+        cx.spawn(|this, mut cx| async move {
+            let result = remote::remove_known_host(&ssh_binary, &connection_options).await;
+            this.update(&mut cx, |this, cx| match result {
+                Ok(()) => this.retry(cx),
+                // Replacing the mismatch error lets the user retry anyway.
+                Err(error) => this.set_error(format!("Failed to forget the host key: {error}"), cx),
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn confirm(&mut self, cx: &mut ViewContext<Self>) {
-        if let Some((_, tx)) = self.prompt.take() {
-            self.editor.update(cx, |editor, cx| {
-                tx.send(Ok(editor.text(cx))).ok();
+        // Host keys have to be accepted or rejected explicitly.
+        if self.host_key.is_some() {
+            return;
+        }
+        if let Some((prompt, tx)) = self.prompt.take() {
+            let text = self.editor.update(cx, |editor, cx| {
+                let text = editor.text(cx);
                 editor.clear(cx);
+                text
             });
+            if self.remember_password && is_password_prompt(&prompt) {
+                self.password_to_remember = Some(text.clone());
+            }
+            tx.send(Ok(text)).ok();
+            self.answered_prompts.push(prompt);
+            self.show_next_prompt(cx);
+        } else if self.missing_paths.is_some() {
+            self.resolve_missing_paths(false, cx);
+        } else if self.error_message.is_some() && !self.is_host_key_mismatch() {
+            self.retry(cx);
+        }
+    }
+
+    fn respond_to_host_key(&mut self, accept: bool, cx: &mut ViewContext<Self>) {
+        if let Some((_, tx)) = self.prompt.take() {
+            self.host_key.take();
+            self.rejected_host_key = !accept;
+            let answer = if accept { "yes" } else { "no" };
+            tx.send(Ok(answer.to_string())).ok();
+            self.show_next_prompt(cx);
         }
     }
+
+    fn render_prompt_steps(&self, cx: &ViewContext<Self>) -> impl IntoElement {
+        let step = self.answered_prompts.len() + 1;
+        let total = step + self.queued_prompts.len();
+        v_flex()
+            .px_4()
+            .pt_2()
+            .gap_1()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .children(self.answered_prompts.iter().map(|prompt| {
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Icon::new(IconName::Check)
+                            .size(IconSize::Small)
+                            .color(Color::Success),
+                    )
+                    .child(
+                        Label::new(prompt.trim().trim_end_matches(':').to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+            }))
+            .child(
+                Label::new(if total > step {
+                    format!("Step {} of {}", step, total)
+                } else {
+                    format!("Step {}", step)
+                })
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+    }
+
+    fn render_security_key_prompt(
+        &self,
+        prompt: &SharedString,
+        cx: &ViewContext<Self>,
+    ) -> impl IntoElement {
+        let accent = Color::Accent.color(cx);
+        v_flex()
+            .p_4()
+            .gap_2()
+            .w_full()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Icon::new(IconName::FileLock).color(Color::Accent))
+                    .child(Label::new("Touch your security key")),
+            )
+            .child(
+                Label::new(prompt.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                div()
+                    .h_1()
+                    .w_full()
+                    .rounded_sm()
+                    .bg(cx.theme().colors().border_variant)
+                    .child(div().h_full().rounded_sm().bg(accent).with_animation(
+                        "security-key-timeout",
+                        Animation::new(SECURITY_KEY_TIMEOUT),
+                        |bar, delta| bar.w(relative(1. - delta)),
+                    )),
+            )
+    }
+
+    fn render_host_key_prompt(
+        &self,
+        host_key: &HostKeyPrompt,
+        cx: &ViewContext<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .p_4()
+            .gap_2()
+            .w_full()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(format!(
+                "The authenticity of host {} can't be established.",
+                host_key.host
+            )))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .font_buffer(cx)
+                    .child(Label::new(host_key.key_type.clone()).color(Color::Muted))
+                    .child(Label::new(host_key.fingerprint.clone())),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .justify_end()
+                    .child(
+                        Button::new("reject-host-key", "Reject")
+                            .style(ButtonStyle::Filled)
+                            .on_click(
+                                cx.listener(|this, _, cx| this.respond_to_host_key(false, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("accept-host-key", "Accept")
+                            .style(ButtonStyle::Filled)
+                            .on_click(
+                                cx.listener(|this, _, cx| this.respond_to_host_key(true, cx)),
+                            ),
+                    ),
+            )
+    }
 }
 
 impl Render for SshPrompt {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        let cx = cx.window_context();
         let theme = cx.theme();
         v_flex()
             .key_context("PasswordPrompt")
@@ -199,16 +1145,188 @@ impl Render for SshPrompt {
                             ),
                     ),
             )
-            .child(div().when_some(self.prompt.as_ref(), |el, prompt| {
+            .when(!self.log.is_empty(), |el| {
                 el.child(
-                    h_flex()
-                        .p_4()
+                    h_flex().px_2().pb_1().justify_center().child(
+                        Button::new(
+                            "toggle-connection-log",
+                            if self.show_log {
+                                "Hide Logs"
+                            } else {
+                                "Show Logs"
+                            },
+                        )
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(|this, _, cx| {
+                            this.show_log = !this.show_log;
+                            cx.notify();
+                        })),
+                    ),
+                )
+            })
+            .when(self.show_log, |el| {
+                el.child(
+                    v_flex()
+                        .id("connection-log")
+                        .max_h(px(200.))
+                        .overflow_y_scroll()
+                        .p_2()
                         .border_t_1()
                         .border_color(theme.colors().border_variant)
                         .font_buffer(cx)
-                        .child(Label::new(prompt.0.clone()))
-                        .child(self.editor.clone()),
+                        .children(self.log.iter().map(|line| {
+                            Label::new(line.clone())
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted)
+                        })),
                 )
+            })
+            .when(self.retry.is_some() && self.is_host_key_mismatch(), |el| {
+                el.child(
+                    h_flex()
+                        .p_2()
+                        .gap_2()
+                        .justify_center()
+                        .border_t_1()
+                        .border_color(theme.colors().border_variant)
+                        .child(
+                            Label::new("The host key of this server has changed.")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            Button::new("forget-host-key", "Forget Old Key and Retry")
+                                .style(ButtonStyle::Filled)
+                                .on_click(
+                                    cx.listener(|this, _, cx| this.forget_host_key_and_retry(cx)),
+                                ),
+                        ),
+                )
+            })
+            .when(self.retry.is_some() && !self.is_host_key_mismatch(), |el| {
+                el.child(
+                    h_flex()
+                        .p_2()
+                        .justify_end()
+                        .border_t_1()
+                        .border_color(theme.colors().border_variant)
+                        .child(
+                            Button::new("retry-connection", "Retry")
+                                .style(ButtonStyle::Filled)
+                                .icon(IconName::ArrowCircle)
+                                .icon_position(IconPosition::Start)
+                                .on_click(cx.listener(|this, _, cx| this.retry(cx))),
+                        ),
+                )
+            })
+            .when_some(self.missing_paths.as_ref(), |el, (paths, _)| {
+                el.child(
+                    v_flex()
+                        .p_2()
+                        .gap_2()
+                        .border_t_1()
+                        .border_color(theme.colors().border_variant)
+                        .child(self.editor.clone())
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .justify_end()
+                                .child(
+                                    Button::new(
+                                        "create-missing-paths",
+                                        if paths.len() == 1 {
+                                            "Create Folder"
+                                        } else {
+                                            "Create Folders"
+                                        },
+                                    )
+                                    .icon(IconName::Plus)
+                                    .icon_position(IconPosition::Start)
+                                    .on_click(cx.listener(
+                                        |this, _, cx| this.resolve_missing_paths(true, cx),
+                                    )),
+                                )
+                                .child(
+                                    Button::new("open-corrected-path", "Open")
+                                        .style(ButtonStyle::Filled)
+                                        .on_click(cx.listener(|this, _, cx| {
+                                            this.resolve_missing_paths(false, cx)
+                                        })),
+                                ),
+                        ),
+                )
+            })
+            .when_some(self.security_key_prompt.as_ref(), |el, prompt| {
+                el.child(self.render_security_key_prompt(prompt, cx))
+            })
+            .when(
+                self.prompt.is_some()
+                    && !(self.answered_prompts.is_empty() && self.queued_prompts.is_empty()),
+                |el| el.child(self.render_prompt_steps(cx)),
+            )
+            .child(div().when_some(self.prompt.as_ref(), |el, prompt| {
+                if let Some(host_key) = self.host_key.as_ref() {
+                    el.child(self.render_host_key_prompt(host_key, cx))
+                } else {
+                    el.when_some(self.hop_for_prompt(&prompt.0), |el, (hop, host)| {
+                        el.child(
+                            h_flex()
+                                .px_4()
+                                .pt_2()
+                                .gap_1()
+                                .child(Icon::new(IconName::Server).size(IconSize::XSmall))
+                                .child(
+                                    Label::new(format!(
+                                        "Hop {} of {}: {}",
+                                        hop,
+                                        self.connection_options.jump_hosts.len() + 1,
+                                        host
+                                    ))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                                ),
+                        )
+                    })
+                    .child(
+                        h_flex()
+                            .p_4()
+                            .border_t_1()
+                            .border_color(theme.colors().border_variant)
+                            .gap_1()
+                            .font_buffer(cx)
+                            .when(is_verification_code_prompt(&prompt.0), |el| {
+                                el.child(Icon::new(IconName::CountdownTimer).color(Color::Accent))
+                            })
+                            .child(Label::new(prompt.0.clone()))
+                            .child(self.editor.clone()),
+                    )
+                    .when(is_password_prompt(&prompt.0), |el| {
+                        el.child(
+                            h_flex()
+                                .px_4()
+                                .pb_2()
+                                .gap_1()
+                                .child(
+                                    Checkbox::new(
+                                        "remember-password",
+                                        if self.remember_password {
+                                            Selection::Selected
+                                        } else {
+                                            Selection::Unselected
+                                        },
+                                    )
+                                    .on_click(cx.listener(
+                                        |this, selection, cx| {
+                                            this.remember_password =
+                                                *selection == Selection::Selected;
+                                            cx.notify();
+                                        },
+                                    )),
+                                )
+                                .child(Label::new("Remember password").size(LabelSize::Small)),
+                        )
+                    })
+                }
             }))
     }
 }
@@ -222,9 +1340,25 @@ impl SshConnectionModal {
         Self {
             prompt: cx.new_view(|cx| SshPrompt::new(connection_options, cx)),
             is_separate_window,
+            cancel_tx: None,
         }
     }
 
+    fn with_prompt(prompt: View<SshPrompt>) -> Self {
+        Self {
+            prompt,
+            is_separate_window: false,
+            cancel_tx: None,
+        }
+    }
+
+    /// Resolves once the modal is dismissed, e.g. by pressing Cancel or Escape.
+    pub(crate) fn cancellation(&mut self) -> oneshot::Receiver<()> {
+        let (tx, rx) = oneshot::channel();
+        self.cancel_tx = Some(tx);
+        rx
+    }
+
     fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
         self.prompt.update(cx, |prompt, cx| prompt.confirm(cx))
     }
@@ -240,6 +1374,18 @@ impl SshConnectionModal {
 pub(crate) struct SshConnectionHeader {
     pub(crate) connection_string: SharedString,
     pub(crate) nickname: Option<SharedString>,
+    pub(crate) latency: Option<Duration>,
+}
+
+/// Color to show a connection's round-trip time in, from good to poor.
+pub fn latency_color(latency: Duration) -> Color {
+    if latency < Duration::from_millis(100) {
+        Color::Success
+    } else if latency < Duration::from_millis(300) {
+        Color::Warning
+    } else {
+        Color::Error
+    }
 }
 
 impl RenderOnce for SshConnectionHeader {
@@ -280,12 +1426,20 @@ impl RenderOnce for SshConnectionHeader {
                             .color(Color::Muted)
                     })),
             )
+            .children(self.latency.map(|latency| {
+                Label::new(format!("{} ms", latency.as_millis()))
+                    .size(ui::LabelSize::Small)
+                    .single_line()
+                    .color(latency_color(latency))
+            }))
     }
 }
 
 impl Render for SshConnectionModal {
     fn render(&mut self, cx: &mut ui::ViewContext<Self>) -> impl ui::IntoElement {
         let connection_string = self.prompt.read(cx).connection_string.clone();
+        let is_connecting =
+            self.cancel_tx.is_some() && self.prompt.read(cx).error_message.is_none();
         let theme = cx.theme();
 
         let body_color = theme.colors().editor_background;
@@ -302,6 +1456,7 @@ impl Render for SshConnectionModal {
                 SshConnectionHeader {
                     connection_string,
                     nickname: None,
+                    latency: None,
                 }
                 .render(cx),
             )
@@ -312,6 +1467,22 @@ impl Render for SshConnectionModal {
                     .w_full()
                     .child(self.prompt.clone()),
             )
+            .when(is_connecting, |el| {
+                el.child(
+                    h_flex()
+                        .p_2()
+                        .justify_end()
+                        .border_t_1()
+                        .border_color(theme.colors().border_variant)
+                        .child(
+                            Button::new("cancel-connection", "Cancel")
+                                .style(ButtonStyle::Filled)
+                                .on_click(
+                                    cx.listener(|this, _, cx| this.dismiss(&menu::Cancel, cx)),
+                                ),
+                        ),
+                )
+            })
     }
 }
 
@@ -330,26 +1501,63 @@ pub struct SshClientDelegate {
     window: AnyWindowHandle,
     ui: View<SshPrompt>,
     known_password: Option<String>,
+    keychain_url: String,
+    /// Whether a password from the keychain has already been offered, so a
+    /// stale stored password doesn't get retried until ssh gives up.
+    used_keychain_password: Arc<AtomicBool>,
+    /// Whether the password last sent to ssh came from the keychain, so that it can
+    /// be deleted when ssh asks for a password again.
+    sent_keychain_password: Arc<AtomicBool>,
+    download_proxy: Option<String>,
+    server_binary: Option<PathBuf>,
+    log_tx: mpsc::UnboundedSender<String>,
 }
 
 impl remote::SshClientDelegate for SshClientDelegate {
+    fn log_output(&self, output: &str) {
+        self.log_tx.unbounded_send(output.to_string()).ok();
+    }
+
     fn ask_password(
         &self,
         prompt: String,
         cx: &mut AsyncAppContext,
     ) -> oneshot::Receiver<Result<String>> {
         let (tx, rx) = oneshot::channel();
+        if is_password_prompt(&prompt) && self.sent_keychain_password.swap(false, Ordering::SeqCst)
+        {
+            // The stored password was rejected.
+            let keychain_url = self.keychain_url.clone();
+            cx.update(|cx| cx.delete_credentials(&keychain_url).detach_and_log_err(cx))
+                .ok();
+        }
         let mut known_password = self.known_password.clone();
         if let Some(password) = known_password.take() {
             tx.send(Ok(password)).ok();
+        } else if is_password_prompt(&prompt)
+            && !self.used_keychain_password.swap(true, Ordering::SeqCst)
+        {
+            let this = self.clone();
+            cx.spawn(|mut cx| async move {
+                let stored_password = match cx.update(|cx| cx.read_credentials(&this.keychain_url))
+                {
+                    Ok(read_credentials) => read_credentials
+                        .await
+                        .log_err()
+                        .flatten()
+                        .and_then(|(_, password)| String::from_utf8(password).ok()),
+                    Err(_) => None,
+                };
+                if let Some(password) = stored_password {
+                    this.sent_keychain_password.store(true, Ordering::SeqCst);
+                    tx.send(Ok(password)).ok();
+                } else {
+                    this.show_prompt(prompt, tx, &mut cx);
+                }
+            })
+            .detach();
         } else {
-            self.window
-                .update(cx, |_, cx| {
-                    self.ui.update(cx, |modal, cx| {
-                        modal.set_prompt(prompt, tx, cx);
-                    })
-                })
-                .ok();
+            self.show_prompt(prompt, tx, cx);
         }
         rx
     }
@@ -377,6 +1585,12 @@ impl remote::SshClientDelegate for SshClientDelegate {
         rx
     }
 
+    fn ssh_binary(&self, cx: &mut AsyncAppContext) -> Option<PathBuf> {
+        cx.update(|cx| SshSettings::get_global(cx).ssh_binary.clone())
+            .ok()
+            .flatten()
+    }
+
     fn remote_server_binary_path(&self, cx: &mut AsyncAppContext) -> Result<PathBuf> {
         let release_channel = cx.update(|cx| ReleaseChannel::global(cx))?;
         Ok(format!(".local/zed-remote-server-{}", release_channel.dev_name()).into())
@@ -384,7 +1598,49 @@ impl remote::SshClientDelegate for SshClientDelegate {
 }
 
 impl SshClientDelegate {
+    /// Opens a connection modal for this prompt, unless one is already showing.
+    /// Reconnects happen without any modal, so this brings the prompt back up
+    /// when it needs the user's attention.
+    fn show_modal(&self, cx: &mut AsyncAppContext) {
+        let Some(window) = self.window.downcast::<Workspace>() else {
+            return;
+        };
+        window
+            .update(cx, |workspace, cx| {
+                if workspace.active_modal::<SshConnectionModal>(cx).is_some()
+                    || workspace.active_modal::<DevServerProjects>(cx).is_some()
+                {
+                    return;
+                }
+                let prompt = self.ui.clone();
+                workspace.toggle_modal(cx, |_| SshConnectionModal::with_prompt(prompt));
+            })
+            .ok();
+    }
+
+    fn show_prompt(
+        &self,
+        prompt: String,
+        tx: oneshot::Sender<Result<String>>,
+        cx: &mut AsyncAppContext,
+    ) {
+        self.show_modal(cx);
+        self.window
+            .update(cx, |_, cx| {
+                self.ui.update(cx, |modal, cx| {
+                    modal.set_prompt(prompt, tx, cx);
+                })
+            })
+            .ok();
+    }
+
     fn update_status(&self, status: Option<&str>, cx: &mut AsyncAppContext) {
+        let reconnect_in_background = cx
+            .update(|cx| SshSettings::get_global(cx).reconnect_in_background())
+            .unwrap_or(true);
+        if !reconnect_in_background {
+            self.show_modal(cx);
+        }
         self.window
             .update(cx, |_, cx| {
                 self.ui.update(cx, |modal, cx| {
@@ -394,6 +1650,46 @@ impl SshClientDelegate {
             .ok();
     }
 
+    fn wait_for_retry(&self, cx: &mut AsyncAppContext) -> oneshot::Receiver<()> {
+        let (tx, rx) = oneshot::channel();
+        self.window
+            .update(cx, |_, cx| {
+                self.ui.update(cx, |modal, cx| {
+                    modal.set_retry(tx, cx);
+                })
+            })
+            .ok();
+        rx
+    }
+
+    fn wait_for_missing_paths(
+        &self,
+        paths: Vec<PathBuf>,
+        cx: &mut AsyncAppContext,
+    ) -> oneshot::Receiver<MissingPathsAction> {
+        let (tx, rx) = oneshot::channel();
+        self.window
+            .update(cx, |_, cx| {
+                self.ui.update(cx, |modal, cx| {
+                    modal.set_missing_paths(paths, tx, cx);
+                })
+            })
+            .ok();
+        rx
+    }
+
+    /// Called once ssh accepted the connection, so that only passwords that worked
+    /// are stored in the keychain.
+    fn connection_established(&self, cx: &mut AsyncAppContext) {
+        self.window
+            .update(cx, |_, cx| {
+                self.ui.update(cx, |modal, cx| {
+                    modal.store_remembered_password(cx);
+                })
+            })
+            .ok();
+    }
+
     fn update_error(&self, error: String, cx: &mut AsyncAppContext) {
         self.window
             .update(cx, |_, cx| {
@@ -414,6 +1710,14 @@ impl SshClientDelegate {
             (global, ReleaseChannel::global(cx))
         })?;
 
+        let custom_binary = match self.server_binary.clone() {
+            Some(server_binary) => Some(server_binary),
+            None => cx.update(|cx| SshSettings::get_global(cx).remote_server_binary.clone())?,
+        };
+        if let Some(custom_binary) = custom_binary {
+            return self.custom_server_binary(custom_binary, version, cx).await;
+        }
+
         // In dev mode, build the remote server binary from source
         #[cfg(debug_assertions)]
         if release_channel == ReleaseChannel::Dev {
@@ -425,25 +1729,104 @@ impl SshClientDelegate {
         }
 
         self.update_status(Some("checking for latest version of remote server"), cx);
+        let download_url = cx.update(|cx| {
+            SshSettings::get_global(cx)
+                .remote_server_download_url
+                .clone()
+        })?;
         let binary_path = AutoUpdater::get_latest_remote_server_release(
-            platform.os,
+            platform.release_os(),
             platform.arch,
             release_channel,
+            self.download_proxy.clone(),
+            download_url,
+            &mut |progress, cx| self.update_status(Some(&download_progress_message(progress)), cx),
             cx,
         )
         .await
         .map_err(|e| {
             anyhow::anyhow!(
                 "failed to download remote server binary (os: {}, arch: {}): {}",
-                platform.os,
+                platform.release_os(),
                 platform.arch,
                 e
             )
-        })?;
+        });
+        let (binary_path, version) = match binary_path {
+            Ok(binary_path) => (binary_path, version),
+            Err(error) => {
+                let cached = AutoUpdater::cached_remote_server_release(
+                    platform.release_os(),
+                    platform.arch,
+                    release_channel,
+                    version,
+                )
+                .await;
+                let Some(cached) = cached else {
+                    self.update_error(error.to_string(), cx);
+                    return Err(error);
+                };
+                log::warn!(
+                    "{:?}; falling back to cached server binary {:?}",
+                    error,
+                    cached
+                );
+                self.update_status(
+                    Some(&format!(
+                        "Couldn't check for the latest remote server, using cached version {}",
+                        cached.1
+                    )),
+                    cx,
+                );
+                cached
+            }
+        };
 
         Ok((binary_path, version))
     }
 
+    /// Prepares a user-provided server binary for upload, which expects a
+    /// gzipped binary, compressing it first if it isn't already.
+    async fn custom_server_binary(
+        &self,
+        path: PathBuf,
+        version: SemanticVersion,
+        cx: &mut AsyncAppContext,
+    ) -> Result<(PathBuf, SemanticVersion)> {
+        log::info!("using custom remote server binary {:?}", path);
+        if path
+            .extension()
+            .map_or(false, |extension| extension == "gz")
+        {
+            return Ok((path, version));
+        }
+
+        self.update_status(Some("Compressing custom remote server binary"), cx);
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("invalid remote server binary path {:?}", path))?;
+        let compressed_dir = paths::remote_servers_dir().join("custom");
+        let compressed_path = compressed_dir.join(format!("{}.gz", file_name.to_string_lossy()));
+        smol::fs::create_dir_all(&compressed_dir).await?;
+
+        let output = smol::process::Command::new("gzip")
+            .arg("-9")
+            .arg("-c")
+            .arg(&path)
+            .output()
+            .await?;
+        if !output.status.success() {
+            Err(anyhow::anyhow!(
+                "failed to compress {:?}: {}",
+                path,
+                String::from_utf8_lossy(&output.stderr)
+            ))?;
+        }
+        smol::fs::write(&compressed_path, output.stdout).await?;
+
+        Ok((compressed_path, version))
+    }
+
     #[cfg(debug_assertions)]
     async fn build_local(
         &self,
@@ -461,16 +1844,30 @@ impl SshClientDelegate {
             Ok(())
         }
 
-        if platform.arch == std::env::consts::ARCH && platform.os == std::env::consts::OS {
+        // Release builds are much smaller and faster to upload, at the cost of build time.
+        let release = std::env::var("ZED_REMOTE_SERVER_RELEASE")
+            .map_or(false, |value| !value.is_empty() && value != "0");
+        let profile = if release { "release" } else { "debug" };
+        let target_dir = std::env::var("ZED_REMOTE_SERVER_TARGET_DIR")
+            .unwrap_or_else(|_| "target/remote_server".to_string());
+
+        if platform.arch == std::env::consts::ARCH
+            && platform.os == std::env::consts::OS
+            && !platform.musl
+        {
             self.update_status(Some("Building remote server binary from source"), cx);
-            log::info!("building remote server binary from source");
-            run_cmd(Command::new("cargo").args([
-                "build",
-                "--package",
-                "remote_server",
-                "--target-dir",
-                "target/remote_server",
-            ]))
+            log::info!("building remote server binary from source ({})", profile);
+            run_cmd(
+                Command::new("cargo")
+                    .args([
+                        "build",
+                        "--package",
+                        "remote_server",
+                        "--target-dir",
+                        &target_dir,
+                    ])
+                    .args(release.then_some("--release")),
+            )
             .await?;
 
             self.update_status(Some("Compressing binary"), cx);
@@ -478,24 +1875,44 @@ impl SshClientDelegate {
             run_cmd(Command::new("gzip").args([
                 "-9",
                 "-f",
-                "target/remote_server/debug/remote_server",
+                &format!("{}/{}/remote_server", target_dir, profile),
             ]))
             .await?;
 
-            let path = std::env::current_dir()?.join("target/remote_server/debug/remote_server.gz");
+            let path = std::env::current_dir()?
+                .join(format!("{}/{}/remote_server.gz", target_dir, profile));
             return Ok(Some((path, version)));
         } else if let Some(triple) = platform.triple() {
             smol::fs::create_dir_all("target/remote-server").await?;
 
-            self.update_status(Some("Installing cross.rs for cross-compilation"), cx);
-            log::info!("installing cross");
-            run_cmd(Command::new("cargo").args([
-                "install",
-                "cross",
-                "--git",
-                "https://github.com/cross-rs/cross",
-            ]))
-            .await?;
+            // cross builds in Docker containers; cargo-zigbuild only needs zig
+            // installed, so it's used when asked for or when Docker is missing.
+            let use_zigbuild = match std::env::var("ZED_REMOTE_SERVER_CROSS").as_deref() {
+                Ok("zigbuild") => true,
+                Ok("cross") => false,
+                _ => !Command::new("docker")
+                    .arg("version")
+                    .output()
+                    .await
+                    .map_or(false, |output| output.status.success()),
+            };
+
+            if use_zigbuild {
+                self.update_status(Some("Installing cargo-zigbuild for cross-compilation"), cx);
+                log::info!("installing cargo-zigbuild");
+                run_cmd(Command::new("cargo").args(["install", "--locked", "cargo-zigbuild"]))
+                    .await?;
+            } else {
+                self.update_status(Some("Installing cross.rs for cross-compilation"), cx);
+                log::info!("installing cross");
+                run_cmd(Command::new("cargo").args([
+                    "install",
+                    "cross",
+                    "--git",
+                    "https://github.com/cross-rs/cross",
+                ]))
+                .await?;
+            }
 
             self.update_status(
                 Some(&format!(
@@ -504,39 +1921,55 @@ impl SshClientDelegate {
                 )),
                 cx,
             );
-            log::info!("building remote server binary from source for {}", &triple);
-            run_cmd(
-                Command::new("cross")
-                    .args([
-                        "build",
-                        "--package",
-                        "remote_server",
-                        "--features",
-                        "debug-embed",
-                        "--target-dir",
-                        "target/remote_server",
-                        "--target",
-                        &triple,
-                    ])
-                    .env(
-                        "CROSS_CONTAINER_OPTS",
-                        "--mount type=bind,src=./target,dst=/app/target",
-                    ),
-            )
-            .await?;
+            log::info!(
+                "building remote server binary from source for {} ({})",
+                &triple,
+                profile
+            );
+            let build_args = [
+                "--package",
+                "remote_server",
+                "--features",
+                "debug-embed",
+                "--target-dir",
+                &target_dir,
+                "--target",
+                &triple,
+            ];
+            if use_zigbuild {
+                run_cmd(
+                    Command::new("cargo")
+                        .arg("zigbuild")
+                        .args(build_args)
+                        .args(release.then_some("--release")),
+                )
+                .await?;
+            } else {
+                run_cmd(
+                    Command::new("cross")
+                        .arg("build")
+                        .args(build_args)
+                        .args(release.then_some("--release"))
+                        .env(
+                            "CROSS_CONTAINER_OPTS",
+                            "--mount type=bind,src=./target,dst=/app/target",
+                        ),
+                )
+                .await?;
+            }
 
             self.update_status(Some("Compressing binary"), cx);
 
             run_cmd(Command::new("gzip").args([
                 "-9",
                 "-f",
-                &format!("target/remote_server/{}/debug/remote_server", triple),
+                &format!("{}/{}/{}/remote_server", target_dir, triple, profile),
             ]))
             .await?;
 
             let path = std::env::current_dir()?.join(format!(
-                "target/remote_server/{}/debug/remote_server.gz",
-                triple
+                "{}/{}/{}/remote_server.gz",
+                target_dir, triple, profile
             ));
 
             return Ok(Some((path, version)));
@@ -546,38 +1979,193 @@ impl SshClientDelegate {
     }
 }
 
+fn download_progress_message(progress: DownloadProgress) -> String {
+    const MEGABYTE: f64 = 1024. * 1024.;
+    let downloaded = progress.downloaded as f64 / MEGABYTE;
+    match progress.total {
+        Some(total) if total > 0 => format!(
+            "Downloading remote server: {:.0}% ({:.1} MB of {:.1} MB)",
+            progress.downloaded as f64 / total as f64 * 100.,
+            downloaded,
+            total as f64 / MEGABYTE
+        ),
+        _ => format!("Downloading remote server: {:.1} MB", downloaded),
+    }
+}
+
 pub fn connect_over_ssh(
     unique_identifier: String,
-    connection_options: SshConnectionOptions,
+    mut connection_options: SshConnectionOptions,
     ui: View<SshPrompt>,
     cx: &mut WindowContext,
 ) -> Task<Result<Model<SshRemoteClient>>> {
+    SshSettings::get_global(cx).apply_defaults(&mut connection_options);
     let window = cx.window_handle();
     let known_password = connection_options.password.clone();
+    let keychain_url = keychain_url(&connection_options);
+    let download_proxy = connection_options.download_proxy.clone();
+    let server_binary = connection_options.server_binary.clone();
+    let log_tx = ui.read(cx).log_sender();
+    let delegate = Arc::new(SshClientDelegate {
+        window,
+        ui,
+        known_password,
+        keychain_url,
+        used_keychain_password: Arc::default(),
+        sent_keychain_password: Arc::default(),
+        download_proxy,
+        server_binary,
+        log_tx,
+    });
 
-    remote::SshRemoteClient::new(
-        unique_identifier,
-        connection_options,
-        Arc::new(SshClientDelegate {
-            window,
-            ui,
-            known_password,
-        }),
-        cx,
-    )
+    let connect =
+        remote::SshRemoteClient::new(unique_identifier, connection_options, delegate.clone(), cx);
+    cx.spawn(|mut cx| async move {
+        let client = connect.await?;
+        delegate.connection_established(&mut cx);
+        Ok(client)
+    })
+}
+
+/// Adds the project to its connection in `ssh_connections`, adding the connection
+/// too if needed, so that remote projects travel with the settings file.
+fn remember_ssh_project(
+    connection_options: &SshConnectionOptions,
+    paths: &[PathBuf],
+    fs: Arc<dyn Fs>,
+    cx: &AppContext,
+) {
+    let host = connection_options.host.clone();
+    let port = connection_options.port;
+    let username = connection_options.username.clone();
+    let paths = paths
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    let is_remembered = SshSettings::get_global(cx)
+        .ssh_connections()
+        .any(|connection| {
+            connection.host == host
+                && connection.port == port
+                && connection.username == username
+                && connection
+                    .projects
+                    .iter()
+                    .any(|project| project.paths == paths)
+        });
+    if is_remembered {
+        return;
+    }
+
+    update_settings_file::<SshSettings>(fs, cx, move |settings, _| {
+        let connections = settings.ssh_connections.get_or_insert_with(Vec::new);
+        let connection = connections.iter_mut().find(|connection| {
+            connection.host == host && connection.port == port && connection.username == username
+        });
+        match connection {
+            Some(connection) => connection.projects.push(SshProject { paths }),
+            None => connections.push(SshConnection {
+                host: host.into(),
+                port,
+                username,
+                projects: vec![SshProject { paths }],
+                ..Default::default()
+            }),
+        }
+    });
+}
+
+/// Opens every project configured for the connection, each in its own window. The
+/// projects are opened one after another, so that only one authentication prompt is
+/// shown at a time, and with `multiplex` the others reuse the first one's connection.
+pub(crate) async fn open_all_ssh_projects(
+    connection: SshConnection,
+    app_state: Arc<AppState>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let projects = connection.projects.clone();
+    let connection_options = SshConnectionOptions::from(connection);
+    for project in projects {
+        open_ssh_project(
+            connection_options.clone(),
+            project.paths.into_iter().map(PathBuf::from).collect(),
+            app_state.clone(),
+            workspace::OpenOptions::default(),
+            cx,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Checks whether anything accepts TCP connections on the host's ssh port, to tell
+/// powered-off or disconnected machines apart without starting ssh.
+///
+/// The host and port are the ones ssh resolves from the user's ssh_config. Returns
+/// `None` when that can't be known cheaply: for hosts reached through jump hosts, a
+/// proxy or a pre-connect command, or when ssh can't resolve its configuration.
+pub(crate) async fn probe_ssh_host(
+    ssh_binary: &Path,
+    connection_options: &SshConnectionOptions,
+    executor: &BackgroundExecutor,
+) -> Option<bool> {
+    const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+    if !connection_options.jump_hosts.is_empty() || connection_options.pre_connect_command.is_some()
+    {
+        return None;
+    }
+    // The user's ssh_config can point the host elsewhere, or connect through a proxy
+    // that can't be probed directly.
+    let config = remote::resolve_ssh_config(ssh_binary, connection_options)
+        .await
+        .log_err()?;
+    if config.proxy_command.is_some() || config.proxy_jump.is_some() {
+        return None;
+    }
+    let addresses = smol::net::resolve((config.hostname.as_str(), config.port))
+        .await
+        .ok()?;
+    if addresses.is_empty() {
+        return None;
+    }
+    let connect = async {
+        smol::net::TcpStream::connect(addresses.as_slice())
+            .await
+            .is_ok()
+    };
+    let timeout = async {
+        executor.timer(PROBE_TIMEOUT).await;
+        false
+    };
+    Some(smol::future::or(connect, timeout).await)
 }
 
 pub async fn open_ssh_project(
-    connection_options: SshConnectionOptions,
+    mut connection_options: SshConnectionOptions,
     paths: Vec<PathBuf>,
     app_state: Arc<AppState>,
     open_options: workspace::OpenOptions,
     cx: &mut AsyncAppContext,
 ) -> Result<()> {
+    let mut paths = cx.update(|cx| {
+        let settings = SshSettings::get_global(cx);
+        settings.apply_defaults(&mut connection_options);
+        if paths.is_empty() {
+            vec![PathBuf::from(
+                settings.default_path_for(&connection_options),
+            )]
+        } else {
+            paths
+        }
+    })?;
+    let created_window = open_options.replace_window.is_none();
     let window = if let Some(window) = open_options.replace_window {
         window
     } else {
-        let options = cx.update(|cx| (app_state.build_window_options)(None, cx))?;
+        let options =
+            workspace::ssh_project_window_options(&connection_options, &paths, &app_state, cx)
+                .await?;
         cx.open_window(options, |cx| {
             let project = project::Project::local(
                 app_state.client.clone(),
@@ -592,45 +2180,108 @@ pub async fn open_ssh_project(
         })?
     };
 
-    let delegate = window.update(cx, |workspace, cx| {
+    let (delegate, mut cancelled) = window.update(cx, |workspace, cx| {
         cx.activate_window();
         workspace.toggle_modal(cx, |cx| {
-            SshConnectionModal::new(&connection_options, true, cx)
+            SshConnectionModal::new(&connection_options, created_window, cx)
         });
-        let ui = workspace
-            .active_modal::<SshConnectionModal>(cx)
-            .unwrap()
-            .read(cx)
-            .prompt
-            .clone();
-
-        Arc::new(SshClientDelegate {
+        let modal = workspace.active_modal::<SshConnectionModal>(cx).unwrap();
+        let cancelled = modal.update(cx, |modal, _| modal.cancellation());
+        let ui = modal.read(cx).prompt.clone();
+        let log_tx = ui.read(cx).log_sender();
+
+        let delegate = Arc::new(SshClientDelegate {
             window: cx.window_handle(),
             ui,
             known_password: connection_options.password.clone(),
-        })
+            keychain_url: keychain_url(&connection_options),
+            used_keychain_password: Arc::default(),
+            sent_keychain_password: Arc::default(),
+            download_proxy: connection_options.download_proxy.clone(),
+            server_binary: connection_options.server_binary.clone(),
+            log_tx,
+        });
+        (delegate, cancelled)
     })?;
 
-    let did_open_ssh_project = cx
-        .update(|cx| {
+    // Giving up on the connection shouldn't leave behind an empty window that
+    // was only opened for it.
+    let close_created_window = |cx: &mut AsyncAppContext| {
+        if created_window {
+            window.update(cx, |_, cx| cx.remove_window()).ok();
+        }
+    };
+
+    let mut paths_to_create = Vec::new();
+    // Kept once connected, so that fixing up missing paths doesn't connect again.
+    let mut connected_project = None;
+    loop {
+        let open_ssh_project = cx.update(|cx| {
             workspace::open_ssh_project(
                 window,
-                connection_options,
+                connection_options.clone(),
                 delegate.clone(),
-                app_state,
-                paths,
+                app_state.clone(),
+                connected_project.take(),
+                paths.clone(),
+                mem::take(&mut paths_to_create),
                 cx,
             )
-        })?
-        .await;
+        })?;
 
-    let did_open_ssh_project = match did_open_ssh_project {
-        Ok(ok) => Ok(ok),
-        Err(e) => {
-            delegate.update_error(e.to_string(), cx);
-            Err(e)
-        }
-    };
+        // Dropping the task when the modal is dismissed aborts the connection
+        // attempt, which kills any ssh processes it started.
+        let did_open_ssh_project =
+            match futures::future::select(open_ssh_project, &mut cancelled).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => {
+                    close_created_window(cx);
+                    return Ok(());
+                }
+            };
 
-    did_open_ssh_project
+        match did_open_ssh_project {
+            Ok(()) => {
+                delegate.connection_established(cx);
+                cx.update(|cx| {
+                    remember_ssh_project(&connection_options, &paths, app_state.fs.clone(), cx)
+                })?;
+                return Ok(());
+            }
+            Err(e) => {
+                delegate.update_error(e.to_string(), cx);
+                if let Some((missing_paths, project)) = e
+                    .downcast_ref::<RemotePathsNotFound>()
+                    .map(|not_found| (not_found.paths.clone(), not_found.project.clone()))
+                {
+                    delegate.connection_established(cx);
+                    connected_project = Some(project);
+                    match delegate
+                        .wait_for_missing_paths(missing_paths.clone(), cx)
+                        .await
+                    {
+                        Ok(MissingPathsAction::Create) => paths_to_create = missing_paths,
+                        Ok(MissingPathsAction::Replace { path, replacement }) => {
+                            for open_path in &mut paths {
+                                if *open_path == path {
+                                    *open_path = replacement.clone();
+                                }
+                            }
+                        }
+                        Err(_) => {
+                            close_created_window(cx);
+                            return Err(e);
+                        }
+                    }
+                    continue;
+                }
+                // Wait for the user to retry from the prompt; the sender is
+                // dropped when the modal is dismissed.
+                if delegate.wait_for_retry(cx).await.is_err() {
+                    close_created_window(cx);
+                    return Err(e);
+                }
+            }
+        }
+    }
 }
@@ -4,6 +4,8 @@ pub mod proxy;
 pub mod ssh_session;
 
 pub use ssh_session::{
-    ConnectionState, SshClientDelegate, SshConnectionOptions, SshPlatform, SshRemoteClient,
-    SshRemoteEvent,
+    remove_known_host, resolve_ssh_config, ConnectionState, DisconnectReason, ResolvedSshConfig,
+    SshAddressFamily, SshClientDelegate, SshConnectionEvent, SshConnectionEvents,
+    SshConnectionOptions, SshConnectionStateChange, SshJumpHost, SshPlatform, SshPortForward,
+    SshRemoteClient, SshRemoteEvent, SshUploadMethod,
 };
@@ -13,14 +13,16 @@ use futures::{
         oneshot,
     },
     future::BoxFuture,
-    select_biased, AsyncReadExt as _, AsyncWriteExt as _, Future, FutureExt as _, SinkExt,
-    StreamExt as _,
+    io::BufReader,
+    select_biased, AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _, Future,
+    FutureExt as _, SinkExt, StreamExt as _,
 };
 use gpui::{
-    AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, SemanticVersion, Task,
-    WeakModel,
+    AppContext, AsyncAppContext, Context, EventEmitter, Global, Model, ModelContext,
+    SemanticVersion, Task, WeakModel,
 };
 use parking_lot::Mutex;
+use release_channel::AppVersion;
 use rpc::{
     proto::{self, build_typed_envelope, Envelope, EnvelopedMessage, PeerId, RequestMessage},
     AnyProtoClient, EntityMessageSubscriber, ProtoClient, ProtoMessageHandlerSet, RpcError,
@@ -31,6 +33,7 @@ use smol::{
 };
 use std::{
     any::TypeId,
+    collections::VecDeque,
     ffi::OsStr,
     fmt,
     ops::ControlFlow,
@@ -53,6 +56,42 @@ pub struct SshProjectId(pub u64);
 pub struct SshSocket {
     connection_options: SshConnectionOptions,
     socket_path: PathBuf,
+    ssh_binary: PathBuf,
+    /// Set when commands are run through PuTTY's plink instead of OpenSSH.
+    plink: Option<PlinkAuth>,
+}
+
+/// Plink has no control socket to share an authenticated connection, so every
+/// command re-authenticates using what the user accepted or entered once.
+#[derive(Clone, Default)]
+struct PlinkAuth {
+    host_key: Option<String>,
+    /// File in the connection's private temp directory holding the password,
+    /// so that it doesn't show up in the arguments of every plink process.
+    password_file: Option<PathBuf>,
+}
+
+fn is_plink(ssh_binary: &Path) -> bool {
+    ssh_binary
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map_or(false, |stem| stem.eq_ignore_ascii_case("plink"))
+}
+
+/// Extracts the key type and fingerprint from plink's "host key is not cached" message.
+fn parse_plink_host_key(output: &str) -> Option<(String, String)> {
+    let mut lines = output.lines();
+    lines.find(|line| line.contains("key fingerprint is"))?;
+    let fingerprint_line = lines.find(|line| !line.trim().is_empty())?;
+    let mut parts = fingerprint_line.split_whitespace();
+    let key_type = parts.next()?.to_string();
+    let fingerprint = parts.last()?.to_string();
+    Some((key_type, fingerprint))
+}
+
+/// Whether the output of `zed-remote-server version` is the given version.
+fn is_server_version(output: &str, version: SemanticVersion) -> bool {
+    output.trim() == version.to_string()
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -61,9 +100,204 @@ pub struct SshConnectionOptions {
     pub username: Option<String>,
     pub port: Option<u16>,
     pub password: Option<String>,
+    /// Seconds of inactivity after which ssh sends a keepalive message to the server.
+    pub server_alive_interval: Option<u64>,
+    /// Number of unanswered keepalive messages before ssh drops the connection.
+    pub server_alive_count_max: Option<u64>,
+    pub port_forwards: Vec<SshPortForward>,
+    /// Local port on which ssh opens a SOCKS5 proxy into the remote network.
+    pub dynamic_forward_port: Option<u16>,
+    /// Whether ssh should compress the connection.
+    pub compression: Option<bool>,
+    /// Whether to share a single authenticated ssh connection between all
+    /// projects opened on this host.
+    pub multiplex: bool,
+    /// Certificate to present alongside the identity key when authenticating.
+    pub certificate_file: Option<String>,
+    /// Whether to authenticate using GSSAPI (e.g. Kerberos tickets).
+    pub gssapi_authentication: Option<bool>,
+    /// Whether to forward GSSAPI credentials to the server.
+    pub gssapi_delegate_credentials: Option<bool>,
+    /// Environment variables set for the remote server and remote terminals.
+    pub env: HashMap<String, String>,
+    /// Shell used to launch the remote server and remote terminals, instead of
+    /// relying on the user's login shell.
+    pub shell: Option<String>,
+    /// Command run on the remote host right after connecting, before the
+    /// remote server is started. It runs in a shell of its own, so changes it
+    /// makes to the environment aren't seen by the server.
+    pub init_command: Option<String>,
+    /// Address family ssh uses to connect to the host.
+    pub address_family: Option<SshAddressFamily>,
+    /// Local address to connect from, on machines with multiple interfaces.
+    pub bind_address: Option<String>,
+    /// Local network interface to connect from.
+    pub bind_interface: Option<String>,
+    /// Hosts to connect through, in order, before reaching `host`.
+    pub jump_hosts: Vec<SshJumpHost>,
+    /// Local command run before connecting, e.g. to bring up a VPN.
+    pub pre_connect_command: Option<String>,
+    /// Local command run once the connection is shut down or lost for good.
+    pub post_disconnect_command: Option<String>,
+    /// Proxy used to download the remote server binary, overriding the global proxy.
+    pub download_proxy: Option<String>,
+    /// Locally built remote server binary to upload instead of a downloaded release.
+    pub server_binary: Option<PathBuf>,
+    /// How to copy the server binary to the host. When unset, scp is tried
+    /// first and sftp is used if it fails.
+    pub upload_method: Option<SshUploadMethod>,
+    /// Only use a `zed-remote-server` already installed on the host's PATH,
+    /// failing instead of uploading one.
+    pub require_system_server: bool,
+    /// How long the connection may be idle before a heartbeat is sent.
+    pub heartbeat_interval: Option<Duration>,
+    /// Number of consecutive missed heartbeats after which the connection is
+    /// considered lost and a reconnect is attempted.
+    pub max_missed_heartbeats: Option<usize>,
+}
+
+/// Mechanism used to copy files to the remote host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SshUploadMethod {
+    Scp,
+    Sftp,
+}
+
+/// Which IP address family to use when connecting, equivalent to ssh's `AddressFamily` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SshAddressFamily {
+    Any,
+    Inet,
+    Inet6,
+}
+
+impl SshAddressFamily {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SshAddressFamily::Any => "any",
+            SshAddressFamily::Inet => "inet",
+            SshAddressFamily::Inet6 => "inet6",
+        }
+    }
+}
+
+/// A local port forward, equivalent to ssh's `-L [local_host:]local_port:remote_host:remote_port`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshPortForward {
+    pub local_host: Option<String>,
+    pub local_port: u16,
+    pub remote_host: String,
+    pub remote_port: u16,
+}
+
+impl SshPortForward {
+    pub fn local_address(&self) -> String {
+        format!(
+            "{}:{}",
+            self.local_host.as_deref().unwrap_or("localhost"),
+            self.local_port
+        )
+    }
+
+    pub fn remote_address(&self) -> String {
+        format!("{}:{}", self.remote_host, self.remote_port)
+    }
+}
+
+/// An intermediate host the connection is tunneled through, equivalent to an
+/// entry of ssh's `-J` option.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshJumpHost {
+    pub host: String,
+    pub username: Option<String>,
+    pub port: Option<u16>,
+}
+
+impl fmt::Display for SshJumpHost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(username) = &self.username {
+            write!(f, "{}@", username)?;
+        }
+        write!(f, "{}", self.host)?;
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SshPortForward {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(local_host) = &self.local_host {
+            write!(f, "{}:", local_host)?;
+        }
+        write!(
+            f,
+            "{}:{}:{}",
+            self.local_port, self.remote_host, self.remote_port
+        )
+    }
 }
 
 impl SshConnectionOptions {
+    /// Names of the `env` entries that can't be exported to the remote server:
+    /// names have to be shell identifiers, and values can't contain NUL bytes.
+    fn invalid_env_names(&self) -> Vec<&str> {
+        let mut names = self
+            .env
+            .iter()
+            .filter(|(name, value)| !is_env_var_name(name) || value.contains('\0'))
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    /// Parses an `ssh://[user[:password]@]host[:port][/path]` url into connection
+    /// options and the paths to open on the remote host. A path starting with
+    /// `/~/` is taken to be relative to the remote home directory.
+    pub fn parse_ssh_url(url: &str) -> Result<(Self, Vec<PathBuf>)> {
+        let url = url::Url::parse(url)?;
+        if url.scheme() != "ssh" {
+            Err(anyhow!("not an ssh url: {}", url))?;
+        }
+        let host = match url
+            .host()
+            .ok_or_else(|| anyhow!("missing host in ssh url: {}", url))?
+        {
+            url::Host::Ipv6(address) => address.to_string(),
+            host => host.to_string(),
+        };
+        let username = Some(urlencoding::decode(url.username())?.into_owned())
+            .filter(|username| !username.is_empty());
+        let password = url
+            .password()
+            .map(|password| urlencoding::decode(password).map(|p| p.into_owned()))
+            .transpose()?;
+
+        let path = urlencoding::decode(url.path())?.into_owned();
+        let path = match path.strip_prefix("/~") {
+            Some(rest) => format!("~{}", rest),
+            None => path,
+        };
+        let paths = if path.is_empty() || path == "/" {
+            Vec::new()
+        } else {
+            vec![PathBuf::from(path)]
+        };
+
+        Ok((
+            Self {
+                host,
+                username,
+                port: url.port(),
+                password,
+                ..Default::default()
+            },
+            paths,
+        ))
+    }
+
     pub fn ssh_url(&self) -> String {
         let mut result = String::from("ssh://");
         if let Some(username) = &self.username {
@@ -99,6 +333,134 @@ impl SshConnectionOptions {
         }
     }
 
+    /// Additional `-o` options to pass to the ssh master process.
+    pub fn additional_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(interval) = self.server_alive_interval {
+            args.push("-o".to_string());
+            args.push(format!("ServerAliveInterval={}", interval));
+        }
+        if let Some(count_max) = self.server_alive_count_max {
+            args.push("-o".to_string());
+            args.push(format!("ServerAliveCountMax={}", count_max));
+        }
+        for forward in &self.port_forwards {
+            args.push("-L".to_string());
+            args.push(forward.to_string());
+        }
+        if let Some(port) = self.dynamic_forward_port {
+            args.push("-D".to_string());
+            args.push(port.to_string());
+        }
+        if let Some(compression) = self.compression {
+            args.push("-o".to_string());
+            args.push(format!(
+                "Compression={}",
+                if compression { "yes" } else { "no" }
+            ));
+        }
+        if let Some(certificate_file) = &self.certificate_file {
+            args.push("-o".to_string());
+            args.push(format!("CertificateFile={}", certificate_file));
+        }
+        if let Some(gssapi) = self.gssapi_authentication {
+            args.push("-o".to_string());
+            args.push(format!(
+                "GSSAPIAuthentication={}",
+                if gssapi { "yes" } else { "no" }
+            ));
+        }
+        if let Some(delegate) = self.gssapi_delegate_credentials {
+            args.push("-o".to_string());
+            args.push(format!(
+                "GSSAPIDelegateCredentials={}",
+                if delegate { "yes" } else { "no" }
+            ));
+        }
+        if let Some(address_family) = self.address_family {
+            args.push("-o".to_string());
+            args.push(format!("AddressFamily={}", address_family.as_str()));
+        }
+        if let Some(bind_address) = &self.bind_address {
+            args.push("-o".to_string());
+            args.push(format!("BindAddress={}", bind_address));
+        }
+        if let Some(bind_interface) = &self.bind_interface {
+            args.push("-o".to_string());
+            args.push(format!("BindInterface={}", bind_interface));
+        }
+        if !self.jump_hosts.is_empty() {
+            args.push("-J".to_string());
+            args.push(
+                self.jump_hosts
+                    .iter()
+                    .map(|jump_host| jump_host.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+        }
+        args
+    }
+
+    /// Translates these options into arguments for PuTTY's plink (and pscp),
+    /// which don't understand OpenSSH's `-o` options.
+    fn plink_args(&self) -> Vec<String> {
+        let mut args = vec!["-batch".to_string()];
+        if let Some(port) = self.port {
+            args.push("-P".to_string());
+            args.push(port.to_string());
+        }
+        if self.compression == Some(true) {
+            args.push("-C".to_string());
+        }
+        match self.address_family {
+            Some(SshAddressFamily::Inet) => args.push("-4".to_string()),
+            Some(SshAddressFamily::Inet6) => args.push("-6".to_string()),
+            _ => {}
+        }
+        args
+    }
+
+    /// Wraps a command so that it runs in the configured remote shell, if any.
+    fn wrap_in_shell(&self, command: &str) -> Result<String> {
+        let Some(shell) = &self.shell else {
+            return Ok(command.to_string());
+        };
+        let quoted_command =
+            shlex::try_quote(command).map_err(|_| anyhow!("failed to quote remote command"))?;
+        Ok(format!("{} -c {}", shell, quoted_command))
+    }
+
+    /// Path of the control socket shared by all multiplexed connections to this host,
+    /// in a directory only the current user can access.
+    #[cfg(unix)]
+    async fn shared_control_path(&self) -> Result<PathBuf> {
+        use sha2::{Digest as _, Sha256};
+        use smol::fs::unix::{DirBuilderExt as _, PermissionsExt as _};
+
+        let dir = paths::ssh_control_sockets_dir();
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+            .await
+            .context("failed to create ssh control socket directory")?;
+        // The directory may predate this check, or have been created by someone else.
+        let metadata = fs::metadata(dir).await?;
+        if metadata.permissions().mode() & 0o077 != 0 {
+            fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)).await?;
+        }
+
+        // Hashed so that the path fits the socket path length limit, with a hash that stays
+        // the same across builds so that other Zed processes find the same socket.
+        let digest = Sha256::digest(self.connection_string().as_bytes());
+        let name = digest[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        Ok(dir.join(format!("{name}.sock")))
+    }
+
     // Uniquely identifies dev server projects on a remote host. Needs to be
     // stable for the same dev server project.
     pub fn dev_server_identifier(&self) -> String {
@@ -115,6 +477,9 @@ impl SshConnectionOptions {
 pub struct SshPlatform {
     pub os: &'static str,
     pub arch: &'static str,
+    /// Whether this is a Linux host without glibc (e.g. Alpine or a BusyBox
+    /// container), which needs a statically linked musl build of the server.
+    pub musl: bool,
 }
 
 impl SshPlatform {
@@ -123,12 +488,27 @@ impl SshPlatform {
             "{}-{}",
             self.arch,
             match self.os {
+                // 32-bit ARM Linux targets use the hard-float ABI.
+                "linux" if self.arch == "armv7" && self.musl => "unknown-linux-musleabihf",
+                "linux" if self.arch == "armv7" => "unknown-linux-gnueabihf",
+                "linux" if self.musl => "unknown-linux-musl",
                 "linux" => "unknown-linux-gnu",
                 "macos" => "apple-darwin",
+                "freebsd" => "unknown-freebsd",
                 _ => return None,
             }
         ))
     }
+
+    /// Operating system name used to look up remote server releases, which
+    /// publish musl builds separately from glibc ones.
+    pub fn release_os(&self) -> &'static str {
+        if self.musl {
+            "linux-musl"
+        } else {
+            self.os
+        }
+    }
 }
 
 pub trait SshClientDelegate: Send + Sync {
@@ -145,13 +525,18 @@ pub trait SshClientDelegate: Send + Sync {
     ) -> oneshot::Receiver<Result<(PathBuf, SemanticVersion)>>;
     fn set_status(&self, status: Option<&str>, cx: &mut AsyncAppContext);
     fn set_error(&self, error_message: String, cx: &mut AsyncAppContext);
+    /// The ssh executable to use, if it shouldn't be looked up on `PATH`.
+    fn ssh_binary(&self, cx: &mut AsyncAppContext) -> Option<PathBuf>;
+    /// Receives output from ssh and the remote server, to help debug failed
+    /// connections. May be called from a background thread.
+    fn log_output(&self, _output: &str) {}
 }
 
 impl SshSocket {
     fn ssh_command<S: AsRef<OsStr>>(&self, program: S) -> process::Command {
-        let mut command = process::Command::new("ssh");
+        let mut command = process::Command::new(&self.ssh_binary);
         self.ssh_options(&mut command)
-            .arg(self.connection_options.ssh_url())
+            .arg(self.destination())
             .arg(program);
         command
     }
@@ -161,18 +546,190 @@ impl SshSocket {
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .args(["-o", "ControlMaster=no", "-o"])
-            .arg(format!("ControlPath={}", self.socket_path.display()))
+            .args(self.connection_args())
+    }
+
+    /// Arguments that make ssh (or plink) reuse the authenticated connection.
+    fn connection_args(&self) -> Vec<String> {
+        if let Some(plink) = &self.plink {
+            let mut args = self.connection_options.plink_args();
+            if let Some(host_key) = &plink.host_key {
+                args.push("-hostkey".to_string());
+                args.push(host_key.clone());
+            }
+            if let Some(password_file) = &plink.password_file {
+                args.push("-pwfile".to_string());
+                args.push(password_file.to_string_lossy().into_owned());
+            }
+            args
+        } else {
+            vec![
+                "-o".to_string(),
+                "ControlMaster=no".to_string(),
+                "-o".to_string(),
+                format!("ControlPath={}", self.socket_path.display()),
+            ]
+        }
+    }
+
+    fn destination(&self) -> String {
+        if self.plink.is_some() {
+            self.connection_options.scp_url()
+        } else {
+            self.connection_options.ssh_url()
+        }
+    }
+
+    #[cfg(unix)]
+    async fn is_master_alive(&self) -> bool {
+        let mut command = process::Command::new(&self.ssh_binary);
+        run_cmd(
+            self.ssh_options(&mut command)
+                .args(["-O", "check"])
+                .arg(self.connection_options.ssh_url()),
+        )
+        .await
+        .is_ok()
     }
 
     fn ssh_args(&self) -> Vec<String> {
-        vec![
-            "-o".to_string(),
-            "ControlMaster=no".to_string(),
-            "-o".to_string(),
-            format!("ControlPath={}", self.socket_path.display()),
-            self.connection_options.ssh_url(),
-        ]
+        let mut args = self.connection_args();
+        args.push(self.destination());
+        args
+    }
+}
+
+/// The settings ssh uses for a connection once the user's ssh_config is applied, as
+/// printed by `ssh -G`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedSshConfig {
+    pub hostname: String,
+    pub port: u16,
+    pub host_key_alias: Option<String>,
+    pub proxy_command: Option<String>,
+    pub proxy_jump: Option<String>,
+    pub user_known_hosts_files: Vec<PathBuf>,
+}
+
+impl ResolvedSshConfig {
+    fn parse(output: &str) -> Result<Self> {
+        let mut values = HashMap::default();
+        for line in output.lines() {
+            if let Some((key, value)) = line.trim().split_once(' ') {
+                values.entry(key.to_lowercase()).or_insert(value.trim());
+            }
+        }
+        let value = |key: &str| {
+            values
+                .get(key)
+                .filter(|value| !value.eq_ignore_ascii_case("none"))
+                .map(|value| value.to_string())
+        };
+        Ok(Self {
+            hostname: value("hostname").context("ssh didn't resolve a hostname")?,
+            port: value("port").map_or(Ok(22), |port| port.parse())?,
+            host_key_alias: value("hostkeyalias"),
+            proxy_command: value("proxycommand"),
+            proxy_jump: value("proxyjump"),
+            user_known_hosts_files: value("userknownhostsfile")
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(|path| match path.strip_prefix("~/") {
+                    Some(relative_path) => util::paths::home_dir().join(relative_path),
+                    None => PathBuf::from(path),
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Asks ssh how it would connect with the given options.
+pub async fn resolve_ssh_config(
+    ssh_binary: &Path,
+    connection_options: &SshConnectionOptions,
+) -> Result<ResolvedSshConfig> {
+    if is_plink(ssh_binary) {
+        return Err(anyhow!("plink can't resolve the ssh configuration"));
+    }
+    let output = run_cmd(
+        process::Command::new(ssh_binary)
+            .arg("-G")
+            .args(connection_options.additional_args())
+            .arg(connection_options.ssh_url()),
+    )
+    .await?;
+    ResolvedSshConfig::parse(&output)
+}
+
+/// Removes the stored host keys of the given host from the `known_hosts` files ssh
+/// reads for it.
+pub async fn remove_known_host(
+    ssh_binary: &Path,
+    connection_options: &SshConnectionOptions,
+) -> Result<()> {
+    let config = resolve_ssh_config(ssh_binary, connection_options).await?;
+    let name = config.host_key_alias.unwrap_or(config.hostname);
+    let host = if config.port == 22 {
+        name
+    } else {
+        format!("[{}]:{}", name, config.port)
+    };
+    for known_hosts_file in config.user_known_hosts_files {
+        if smol::fs::metadata(&known_hosts_file).await.is_err() {
+            continue;
+        }
+        run_cmd(
+            process::Command::new("ssh-keygen")
+                .arg("-R")
+                .arg(&host)
+                .arg("-f")
+                .arg(&known_hosts_file),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Whether `name` can be assigned in a POSIX shell, i.e. matches `[A-Za-z_][A-Za-z0-9_]*`.
+fn is_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|char| char.is_ascii_alphanumeric() || char == '_')
+}
+
+/// Builds a command that runs `command` through the local shell.
+fn local_shell_command(command: &str) -> process::Command {
+    let mut shell_command = if cfg!(windows) {
+        let mut shell_command = process::Command::new("cmd");
+        shell_command.arg("/C");
+        shell_command
+    } else {
+        let mut shell_command = process::Command::new("sh");
+        shell_command.arg("-c");
+        shell_command
+    };
+    shell_command.arg(command);
+    shell_command
+}
+
+/// Runs the user's post-disconnect command, killing it if it outlives
+/// `POST_DISCONNECT_COMMAND_TIMEOUT` so a hung teardown can't linger.
+async fn run_post_disconnect_command(command: String) {
+    log::info!("running post-disconnect command: {}", command);
+    let mut shell_command = local_shell_command(&command);
+    shell_command.kill_on_drop(true);
+    let timeout = async {
+        smol::Timer::after(POST_DISCONNECT_COMMAND_TIMEOUT).await;
+        Err(anyhow!(
+            "timed out after {:?}",
+            POST_DISCONNECT_COMMAND_TIMEOUT
+        ))
+    };
+    match smol::future::or(run_cmd(&mut shell_command), timeout).await {
+        Ok(output) => log::info!("post-disconnect command finished: {}", output.trim()),
+        Err(error) => log::error!("post-disconnect command failed: {:?}", error),
     }
 }
 
@@ -255,6 +812,14 @@ const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(5);
 
 const MAX_RECONNECT_ATTEMPTS: usize = 3;
+/// How often the round-trip time is measured, even while the connection is busy.
+const LATENCY_INTERVAL: Duration = Duration::from_secs(10);
+const POST_DISCONNECT_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+/// How many sent messages may await acknowledgement before sending fails.
+const MAX_BUFFERED_MESSAGES: usize = 10_000;
+/// How long a shared ssh master outlives the last connection using it.
+#[cfg(unix)]
+const SHARED_MASTER_PERSIST: Duration = Duration::from_secs(60);
 
 enum State {
     Connecting,
@@ -285,7 +850,13 @@ enum State {
         error: anyhow::Error,
         attempts: usize,
     },
-    ReconnectExhausted,
+    /// Automatic reconnects have given up, but the server may still be running.
+    /// Messages keep being queued until the user reconnects manually.
+    ReconnectExhausted {
+        ssh_connection: SshRemoteConnection,
+        delegate: Arc<dyn SshClientDelegate>,
+        forwarder: ChannelForwarder,
+    },
     ServerNotRunning,
 }
 
@@ -296,7 +867,7 @@ impl fmt::Display for State {
             Self::Connected { .. } => write!(f, "connected"),
             Self::Reconnecting => write!(f, "reconnecting"),
             Self::ReconnectFailed { .. } => write!(f, "reconnect failed"),
-            Self::ReconnectExhausted => write!(f, "reconnect exhausted"),
+            Self::ReconnectExhausted { .. } => write!(f, "reconnect exhausted"),
             Self::HeartbeatMissed { .. } => write!(f, "heartbeat missed"),
             Self::ServerNotRunning { .. } => write!(f, "server not running"),
         }
@@ -309,6 +880,7 @@ impl State {
             Self::Connected { ssh_connection, .. } => Some(ssh_connection),
             Self::HeartbeatMissed { ssh_connection, .. } => Some(ssh_connection),
             Self::ReconnectFailed { ssh_connection, .. } => Some(ssh_connection),
+            Self::ReconnectExhausted { ssh_connection, .. } => Some(ssh_connection),
             _ => None,
         }
     }
@@ -317,11 +889,9 @@ impl State {
         match self {
             Self::Connected { .. }
             | Self::HeartbeatMissed { .. }
-            | Self::ReconnectFailed { .. } => true,
-            State::Connecting
-            | State::Reconnecting
-            | State::ReconnectExhausted
-            | State::ServerNotRunning => false,
+            | Self::ReconnectFailed { .. }
+            | Self::ReconnectExhausted { .. } => true,
+            State::Connecting | State::Reconnecting | State::ServerNotRunning => false,
         }
     }
 
@@ -337,6 +907,10 @@ impl State {
         matches!(self, Self::Reconnecting { .. })
     }
 
+    fn is_connected(&self) -> bool {
+        matches!(self, Self::Connected { .. })
+    }
+
     fn heartbeat_recovered(self) -> Self {
         match self {
             Self::HeartbeatMissed {
@@ -400,6 +974,9 @@ pub enum ConnectionState {
     Connected,
     HeartbeatMissed,
     Reconnecting,
+    /// The connection was lost and couldn't be re-established automatically,
+    /// but edits are queued and sent once the user reconnects.
+    Offline,
     Disconnected,
 }
 
@@ -410,7 +987,7 @@ impl From<&State> for ConnectionState {
             State::Connected { .. } => Self::Connected,
             State::Reconnecting | State::ReconnectFailed { .. } => Self::Reconnecting,
             State::HeartbeatMissed { .. } => Self::HeartbeatMissed,
-            State::ReconnectExhausted => Self::Disconnected,
+            State::ReconnectExhausted { .. } => Self::Offline,
             State::ServerNotRunning => Self::Disconnected,
         }
     }
@@ -421,15 +998,120 @@ pub struct SshRemoteClient {
     unique_identifier: String,
     connection_options: SshConnectionOptions,
     state: Arc<Mutex<Option<State>>>,
+    /// Round-trip time of the most recent successful ping.
+    latency: Option<Duration>,
+    /// Set when a reconnect found that the server process is gone, e.g. because
+    /// the host rebooted. The next reconnect starts a new server instead.
+    server_not_running: bool,
 }
 
 #[derive(Debug)]
 pub enum SshRemoteEvent {
+    Offline,
     Disconnected,
+    /// The connection to the same server process was restored.
+    Reconnected,
+    /// The server process had gone away and a new one was started. State held
+    /// by the old server, like worktrees and open buffers, is lost.
+    ServerRestarted,
 }
 
 impl EventEmitter<SshRemoteEvent> for SshRemoteClient {}
 
+/// Why an ssh connection ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// The connection couldn't be established.
+    ConnectionFailed(String),
+    /// The connection was lost and automatic reconnects gave up. The server may
+    /// still be running, so the connection can be resumed.
+    ReconnectsExhausted,
+    /// The remote server is no longer running.
+    ServerNotRunning,
+    /// The connection was closed on request.
+    Requested,
+}
+
+/// A change in the state of an ssh connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SshConnectionEvent {
+    Connecting,
+    /// ssh is waiting for the user to answer a prompt, e.g. for a password.
+    AuthPrompt {
+        prompt: String,
+    },
+    Connected,
+    Reconnecting {
+        attempt: usize,
+    },
+    Disconnected {
+        reason: DisconnectReason,
+    },
+}
+
+/// An [`SshConnectionEvent`] along with the connection it happened on.
+#[derive(Clone, Debug)]
+pub struct SshConnectionStateChange {
+    pub connection_options: SshConnectionOptions,
+    pub event: SshConnectionEvent,
+}
+
+/// Emits an [`SshConnectionStateChange`] for every ssh connection made by the
+/// app, including ones that are still being established and so don't have an
+/// [`SshRemoteClient`] to subscribe to yet.
+pub struct SshConnectionEvents;
+
+struct GlobalSshConnectionEvents(Model<SshConnectionEvents>);
+
+impl Global for GlobalSshConnectionEvents {}
+
+impl EventEmitter<SshConnectionStateChange> for SshConnectionEvents {}
+
+impl SshConnectionEvents {
+    pub fn global(cx: &mut AppContext) -> Model<Self> {
+        if let Some(global) = cx.try_global::<GlobalSshConnectionEvents>() {
+            return global.0.clone();
+        }
+        let events = cx.new_model(|_| Self);
+        cx.set_global(GlobalSshConnectionEvents(events.clone()));
+        events
+    }
+
+    fn emit(
+        connection_options: &SshConnectionOptions,
+        event: SshConnectionEvent,
+        cx: &mut AppContext,
+    ) {
+        Self::global(cx).update(cx, |_, cx| {
+            cx.emit(SshConnectionStateChange {
+                connection_options: connection_options.clone(),
+                event,
+            })
+        });
+    }
+}
+
+/// Asks the user to answer a prompt from ssh, letting observers know that the
+/// connection is waiting on them.
+fn prompt_user(
+    delegate: &Arc<dyn SshClientDelegate>,
+    connection_options: &SshConnectionOptions,
+    prompt: String,
+    cx: &mut AsyncAppContext,
+) -> oneshot::Receiver<Result<String>> {
+    cx.update(|cx| {
+        SshConnectionEvents::emit(
+            connection_options,
+            SshConnectionEvent::AuthPrompt {
+                prompt: prompt.clone(),
+            },
+            cx,
+        )
+    })
+    .ok();
+    delegate.ask_password(prompt, cx)
+}
+
 impl SshRemoteClient {
     pub fn new(
         unique_identifier: String,
@@ -438,59 +1120,86 @@ impl SshRemoteClient {
         cx: &AppContext,
     ) -> Task<Result<Model<Self>>> {
         cx.spawn(|mut cx| async move {
-            let (outgoing_tx, outgoing_rx) = mpsc::unbounded::<Envelope>();
-            let (incoming_tx, incoming_rx) = mpsc::unbounded::<Envelope>();
-            let (connection_activity_tx, connection_activity_rx) = mpsc::channel::<()>(1);
-
-            let client = cx.update(|cx| ChannelClient::new(incoming_rx, outgoing_tx, cx))?;
-            let this = cx.new_model(|_| Self {
-                client: client.clone(),
-                unique_identifier: unique_identifier.clone(),
-                connection_options: connection_options.clone(),
-                state: Arc::new(Mutex::new(Some(State::Connecting))),
+            let options = connection_options.clone();
+            cx.update(|cx| {
+                SshConnectionEvents::emit(&options, SshConnectionEvent::Connecting, cx)
             })?;
+            let result =
+                Self::connect(unique_identifier, connection_options, delegate, cx.clone()).await;
+            cx.update(|cx| {
+                let event = match &result {
+                    Ok(_) => SshConnectionEvent::Connected,
+                    Err(error) => SshConnectionEvent::Disconnected {
+                        reason: DisconnectReason::ConnectionFailed(error.to_string()),
+                    },
+                };
+                SshConnectionEvents::emit(&options, event, cx)
+            })?;
+            result
+        })
+    }
 
-            let (proxy, proxy_incoming_tx, proxy_outgoing_rx) =
-                ChannelForwarder::new(incoming_tx, outgoing_rx, &mut cx);
-
-            let (ssh_connection, ssh_proxy_process) = Self::establish_connection(
-                unique_identifier,
-                false,
-                connection_options,
-                delegate.clone(),
-                &mut cx,
-            )
-            .await?;
+    async fn connect(
+        unique_identifier: String,
+        connection_options: SshConnectionOptions,
+        delegate: Arc<dyn SshClientDelegate>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Model<Self>> {
+        let (outgoing_tx, outgoing_rx) = mpsc::unbounded::<Envelope>();
+        let (incoming_tx, incoming_rx) = mpsc::unbounded::<Envelope>();
+        let (connection_activity_tx, connection_activity_rx) = mpsc::channel::<()>(1);
+
+        let client = cx.update(|cx| ChannelClient::new(incoming_rx, outgoing_tx, cx))?;
+        let this = cx.new_model(|_| Self {
+            client: client.clone(),
+            unique_identifier: unique_identifier.clone(),
+            connection_options: connection_options.clone(),
+            state: Arc::new(Mutex::new(Some(State::Connecting))),
+            latency: None,
+            server_not_running: false,
+        })?;
+
+        let (proxy, proxy_incoming_tx, proxy_outgoing_rx) =
+            ChannelForwarder::new(incoming_tx, outgoing_rx, &mut cx);
+
+        let (ssh_connection, ssh_proxy_process) = Self::establish_connection(
+            unique_identifier,
+            false,
+            connection_options,
+            delegate.clone(),
+            &mut cx,
+        )
+        .await?;
 
-            let multiplex_task = Self::multiplex(
-                this.downgrade(),
-                ssh_proxy_process,
-                proxy_incoming_tx,
-                proxy_outgoing_rx,
-                connection_activity_tx,
-                &mut cx,
-            );
+        let multiplex_task = Self::multiplex(
+            this.downgrade(),
+            ssh_proxy_process,
+            proxy_incoming_tx,
+            proxy_outgoing_rx,
+            connection_activity_tx,
+            delegate.clone(),
+            &mut cx,
+        );
 
-            if let Err(error) = client.ping(HEARTBEAT_TIMEOUT).await {
-                log::error!("failed to establish connection: {}", error);
-                delegate.set_error(error.to_string(), &mut cx);
-                return Err(error);
-            }
+        if let Err(error) = client.ping(HEARTBEAT_TIMEOUT).await {
+            log::error!("failed to establish connection: {}", error);
+            delegate.set_error(error.to_string(), &mut cx);
+            return Err(error);
+        }
 
-            let heartbeat_task = Self::heartbeat(this.downgrade(), connection_activity_rx, &mut cx);
+        let heartbeat_task = Self::heartbeat(this.downgrade(), connection_activity_rx, &mut cx);
 
-            this.update(&mut cx, |this, _| {
-                *this.state.lock() = Some(State::Connected {
-                    ssh_connection,
-                    delegate,
-                    forwarder: proxy,
-                    multiplex_task,
-                    heartbeat_task,
-                });
-            })?;
+        this.update(&mut cx, |this, _| {
+            *this.state.lock() = Some(State::Connected {
+                ssh_connection,
+                delegate,
+                forwarder: proxy,
+                multiplex_task,
+                heartbeat_task,
+            });
+        })?;
 
-            Ok(this)
-        })
+        Ok(this)
     }
 
     pub fn shutdown_processes<T: RequestMessage>(
@@ -512,6 +1221,7 @@ impl SshRemoteClient {
         };
 
         let client = self.client.clone();
+        let post_disconnect_command = self.connection_options.post_disconnect_command.clone();
 
         Some(async move {
             if let Some(shutdown_request) = shutdown_request {
@@ -530,10 +1240,70 @@ impl SshRemoteClient {
             drop(ssh_connection);
             drop(delegate);
             drop(forwarder);
+
+            if let Some(command) = post_disconnect_command {
+                run_post_disconnect_command(command).await;
+            }
         })
     }
 
-    fn reconnect(&mut self, cx: &mut ModelContext<Self>) -> Result<()> {
+    /// Shuts down the remote server and closes the connection without
+    /// attempting to reconnect.
+    pub fn disconnect(&self, cx: &mut ModelContext<Self>) -> Task<()> {
+        let shutdown = self.shutdown_processes(Some(proto::ShutdownRemoteServer {}));
+        cx.notify();
+        cx.emit(SshRemoteEvent::Disconnected);
+        self.emit_connection_event(
+            SshConnectionEvent::Disconnected {
+                reason: DisconnectReason::Requested,
+            },
+            cx,
+        );
+
+        cx.background_executor().spawn(async move {
+            if let Some(shutdown) = shutdown {
+                shutdown.await;
+            }
+        })
+    }
+
+    fn handle_disconnected(&self, cx: &mut ModelContext<Self>) {
+        if let Some(command) = self.connection_options.post_disconnect_command.clone() {
+            cx.background_executor()
+                .spawn(run_post_disconnect_command(command))
+                .detach();
+        }
+        cx.emit(SshRemoteEvent::Disconnected);
+        self.emit_connection_event(
+            SshConnectionEvent::Disconnected {
+                reason: DisconnectReason::ServerNotRunning,
+            },
+            cx,
+        );
+    }
+
+    fn handle_offline(&self, cx: &mut ModelContext<Self>) {
+        if let Some(command) = self.connection_options.post_disconnect_command.clone() {
+            cx.background_executor()
+                .spawn(run_post_disconnect_command(command))
+                .detach();
+        }
+        cx.emit(SshRemoteEvent::Offline);
+        self.emit_connection_event(
+            SshConnectionEvent::Disconnected {
+                reason: DisconnectReason::ReconnectsExhausted,
+            },
+            cx,
+        );
+    }
+
+    fn emit_connection_event(&self, event: SshConnectionEvent, cx: &mut AppContext) {
+        SshConnectionEvents::emit(&self.connection_options, event, cx);
+    }
+
+    /// Re-establishes the connection to the server, resending any messages
+    /// that were queued while the connection was down.
+    pub fn reconnect(&mut self, cx: &mut ModelContext<Self>) -> Result<()> {
         let mut lock = self.state.lock();
 
         let can_reconnect = lock
@@ -578,10 +1348,12 @@ impl SshRemoteClient {
                 forwarder,
                 ..
             } => (attempts, ssh_connection, delegate, forwarder),
-            State::Connecting
-            | State::Reconnecting
-            | State::ReconnectExhausted
-            | State::ServerNotRunning => unreachable!(),
+            State::ReconnectExhausted {
+                ssh_connection,
+                delegate,
+                forwarder,
+            } => (0, ssh_connection, delegate, forwarder),
+            State::Connecting | State::Reconnecting | State::ServerNotRunning => unreachable!(),
         };
 
         let attempts = attempts + 1;
@@ -591,17 +1363,27 @@ impl SshRemoteClient {
                 MAX_RECONNECT_ATTEMPTS
             );
             drop(lock);
-            self.set_state(State::ReconnectExhausted, cx);
+            self.set_state(
+                State::ReconnectExhausted {
+                    ssh_connection,
+                    delegate,
+                    forwarder,
+                },
+                cx,
+            );
+            self.handle_offline(cx);
             return Ok(());
         }
         drop(lock);
 
         self.set_state(State::Reconnecting, cx);
+        self.emit_connection_event(SshConnectionEvent::Reconnecting { attempt: attempts }, cx);
 
         log::info!("Trying to reconnect to ssh server... Attempt {}", attempts);
 
         let identifier = self.unique_identifier.clone();
         let client = self.client.clone();
+        let restart_server = self.server_not_running;
         let reconnect_task = cx.spawn(|this, mut cx| async move {
             macro_rules! failed {
                 ($error:expr, $attempts:expr, $ssh_connection:expr, $delegate:expr, $forwarder:expr) => {
@@ -615,16 +1397,7 @@ impl SshRemoteClient {
                 };
             }
 
-            if let Err(error) = ssh_connection.master_process.kill() {
-                failed!(error, attempts, ssh_connection, delegate, forwarder);
-            };
-
-            if let Err(error) = ssh_connection
-                .master_process
-                .status()
-                .await
-                .context("Failed to kill ssh process")
-            {
+            if let Err(error) = ssh_connection.kill_master_process().await {
                 failed!(error, attempts, ssh_connection, delegate, forwarder);
             }
 
@@ -635,9 +1408,14 @@ impl SshRemoteClient {
                 ChannelForwarder::new(incoming_tx, outgoing_rx, &mut cx);
             let (connection_activity_tx, connection_activity_rx) = mpsc::channel::<()>(1);
 
+            if restart_server {
+                log::info!("starting a new server, since the previous one is gone");
+                client.reset();
+            }
+
             let (ssh_connection, ssh_process) = match Self::establish_connection(
                 identifier,
-                true,
+                !restart_server,
                 connection_options,
                 delegate.clone(),
                 &mut cx,
@@ -656,10 +1434,16 @@ impl SshRemoteClient {
                 proxy_incoming_tx,
                 proxy_outgoing_rx,
                 connection_activity_tx,
+                delegate.clone(),
                 &mut cx,
             );
 
-            if let Err(error) = client.ping(HEARTBEAT_TIMEOUT).await {
+            let result = if restart_server {
+                client.ping(HEARTBEAT_TIMEOUT).await
+            } else {
+                client.resync(HEARTBEAT_TIMEOUT).await
+            };
+            if let Err(error) = result {
                 failed!(error, attempts, ssh_connection, delegate, forwarder);
             };
 
@@ -694,7 +1478,7 @@ impl SshRemoteClient {
                                     error
                                 );
                             }
-                            State::ReconnectExhausted => {
+                            State::ReconnectExhausted { .. } => {
                                 log::error!("Reconnect attempt failed and all attempts exhausted");
                             }
                         }
@@ -707,7 +1491,16 @@ impl SshRemoteClient {
                 if this.state_is(State::is_reconnect_failed) {
                     this.reconnect(cx)
                 } else if this.state_is(State::is_reconnect_exhausted) {
-                    cx.emit(SshRemoteEvent::Disconnected);
+                    this.handle_offline(cx);
+                    Ok(())
+                } else if this.state_is(State::is_connected) {
+                    if restart_server {
+                        this.server_not_running = false;
+                        cx.emit(SshRemoteEvent::ServerRestarted);
+                    } else {
+                        cx.emit(SshRemoteEvent::Reconnected);
+                    }
+                    this.emit_connection_event(SshConnectionEvent::Connected, cx);
                     Ok(())
                 } else {
                     log::debug!("State has transition from Reconnecting into new state while attempting reconnect. Ignoring new state.");
@@ -725,7 +1518,13 @@ impl SshRemoteClient {
         mut connection_activity_rx: mpsc::Receiver<()>,
         cx: &mut AsyncAppContext,
     ) -> Task<Result<()>> {
-        let Ok(client) = this.update(cx, |this, _| this.client.clone()) else {
+        let Ok((client, heartbeat_interval, max_missed_heartbeats)) = this.update(cx, |this, _| {
+            (
+                this.client.clone(),
+                this.heartbeat_interval(),
+                this.max_missed_heartbeats(),
+            )
+        }) else {
             return Task::ready(Err(anyhow!("SshRemoteClient lost")));
         };
 
@@ -734,13 +1533,34 @@ impl SshRemoteClient {
             async move {
                 let mut missed_heartbeats = 0;
 
-                let keepalive_timer = cx.background_executor().timer(HEARTBEAT_INTERVAL).fuse();
+                let keepalive_timer = cx.background_executor().timer(heartbeat_interval).fuse();
                 futures::pin_mut!(keepalive_timer);
+                let latency_timer = cx.background_executor().timer(Duration::ZERO).fuse();
+                futures::pin_mut!(latency_timer);
 
                 loop {
                     select_biased! {
                         _ = connection_activity_rx.next().fuse() => {
-                            keepalive_timer.set(cx.background_executor().timer(HEARTBEAT_INTERVAL).fuse());
+                            keepalive_timer.set(cx.background_executor().timer(heartbeat_interval).fuse());
+                        }
+                        _ = latency_timer => {
+                            latency_timer.set(cx.background_executor().timer(LATENCY_INTERVAL).fuse());
+                            // Measured on the side, so that a slow ping doesn't hold up
+                            // the heartbeat. Failures are left to the heartbeat to detect.
+                            let client = client.clone();
+                            let this = this.clone();
+                            cx.spawn(|mut cx| async move {
+                                let start = Instant::now();
+                                if client.ping(HEARTBEAT_TIMEOUT).await.is_ok() {
+                                    let latency = start.elapsed();
+                                    this.update(&mut cx, |this, cx| {
+                                        this.latency = Some(latency);
+                                        cx.notify();
+                                    })
+                                    .ok();
+                                }
+                            })
+                            .detach();
                         }
                         _ = keepalive_timer => {
                             log::debug!("Sending heartbeat to server...");
@@ -759,7 +1579,7 @@ impl SshRemoteClient {
                                     "No heartbeat from server after {:?}. Missed heartbeat {} out of {}.",
                                     HEARTBEAT_TIMEOUT,
                                     missed_heartbeats,
-                                    MAX_MISSED_HEARTBEATS
+                                    max_missed_heartbeats
                                 );
                             } else if missed_heartbeats != 0 {
                                 missed_heartbeats = 0;
@@ -780,6 +1600,18 @@ impl SshRemoteClient {
         })
     }
 
+    fn heartbeat_interval(&self) -> Duration {
+        self.connection_options
+            .heartbeat_interval
+            .unwrap_or(HEARTBEAT_INTERVAL)
+    }
+
+    fn max_missed_heartbeats(&self) -> usize {
+        self.connection_options
+            .max_missed_heartbeats
+            .unwrap_or(MAX_MISSED_HEARTBEATS)
+    }
+
     fn handle_heartbeat_result(
         &mut self,
         missed_heartbeats: usize,
@@ -794,7 +1626,7 @@ impl SshRemoteClient {
 
         self.set_state(next_state, cx);
 
-        if missed_heartbeats >= MAX_MISSED_HEARTBEATS {
+        if missed_heartbeats >= self.max_missed_heartbeats() {
             log::error!(
                 "Missed last {} heartbeats. Reconnecting...",
                 missed_heartbeats
@@ -815,6 +1647,7 @@ impl SshRemoteClient {
         incoming_tx: UnboundedSender<Envelope>,
         mut outgoing_rx: UnboundedReceiver<Envelope>,
         mut connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn SshClientDelegate>,
         cx: &AsyncAppContext,
     ) -> Task<Result<()>> {
         let mut child_stderr = ssh_proxy_process.stderr.take().unwrap();
@@ -883,9 +1716,12 @@ impl SshRemoteClient {
                                     let content = &stderr_buffer[start_ix..line_ix];
                                     start_ix = line_ix + 1;
                                     if let Ok(record) = serde_json::from_slice::<LogRecord>(content) {
+                                        delegate.log_output(&record.message);
                                         record.log(log::logger())
                                     } else {
-                                        eprintln!("(remote) {}", String::from_utf8_lossy(content));
+                                        let content = String::from_utf8_lossy(content);
+                                        delegate.log_output(&content);
+                                        eprintln!("(remote) {}", content);
                                     }
                                 }
                                 stderr_buffer.drain(0..start_ix);
@@ -912,8 +1748,15 @@ impl SshRemoteClient {
                             ProxyLaunchError::ServerNotRunning => {
                                 log::error!("failed to reconnect because server is not running");
                                 this.update(&mut cx, |this, cx| {
-                                    this.set_state(State::ServerNotRunning, cx);
-                                    cx.emit(SshRemoteEvent::Disconnected);
+                                    // The host may have rebooted or the server may have been
+                                    // killed. The reconnect in progress will fail, and the
+                                    // next attempt starts a new server.
+                                    if this.state_is(State::is_reconnecting) {
+                                        this.server_not_running = true;
+                                    } else {
+                                        this.set_state(State::ServerNotRunning, cx);
+                                        this.handle_disconnected(cx);
+                                    }
                                 })?;
                             }
                         }
@@ -967,37 +1810,99 @@ impl SshRemoteClient {
         delegate: Arc<dyn SshClientDelegate>,
         cx: &mut AsyncAppContext,
     ) -> Result<(SshRemoteConnection, Child)> {
+        let invalid_env_names = connection_options.invalid_env_names();
+        if !invalid_env_names.is_empty() {
+            let error_message = format!(
+                "Invalid environment variables in the connection's env: {}",
+                invalid_env_names.join(", ")
+            );
+            delegate.set_error(error_message.clone(), cx);
+            Err(anyhow!(error_message))?;
+        }
+
+        if let Some(pre_connect_command) = &connection_options.pre_connect_command {
+            delegate.set_status(Some("Running pre-connect command"), cx);
+            if let Err(error) = run_cmd(&mut local_shell_command(pre_connect_command)).await {
+                let error_message = format!("Pre-connect command failed: {}", error);
+                delegate.set_error(error_message.clone(), cx);
+                Err(anyhow!(error_message))?;
+            }
+        }
+
         let ssh_connection =
             SshRemoteConnection::new(connection_options, delegate.clone(), cx).await?;
 
-        let platform = ssh_connection.query_platform().await?;
-        let (local_binary_path, version) = delegate.get_server_binary(platform, cx).await??;
-        let remote_binary_path = delegate.remote_server_binary_path(cx)?;
-        ssh_connection
-            .ensure_server_binary(
-                &delegate,
-                &local_binary_path,
-                &remote_binary_path,
-                version,
-                cx,
-            )
-            .await?;
+        if let Some(init_command) = &ssh_connection.socket.connection_options.init_command {
+            ssh_connection
+                .run_init_command(init_command, &delegate, cx)
+                .await?;
+        }
+
+        let platform = match ssh_connection.query_platform().await {
+            Ok(platform) => platform,
+            Err(error) => {
+                delegate.set_error(error.to_string(), cx);
+                Err(error)?
+            }
+        };
+        let app_version = cx.update(|cx| AppVersion::global(cx))?;
+        let remote_binary_path = if let Some(system_server) =
+            ssh_connection.find_system_server(app_version).await
+        {
+            log::info!(
+                "using system-installed remote server at {:?}",
+                system_server
+            );
+            system_server
+        } else if ssh_connection
+            .socket
+            .connection_options
+            .require_system_server
+        {
+            let error_message = format!(
+                "No zed-remote-server {} found on the host's PATH",
+                app_version
+            );
+            delegate.set_error(error_message.clone(), cx);
+            Err(anyhow!(error_message))?
+        } else {
+            let (local_binary_path, version) = delegate.get_server_binary(platform, cx).await??;
+            let remote_binary_path = delegate.remote_server_binary_path(cx)?;
+            ssh_connection
+                .ensure_server_binary(
+                    &delegate,
+                    &local_binary_path,
+                    &remote_binary_path,
+                    version,
+                    cx,
+                )
+                .await?;
+            remote_binary_path
+        };
 
         let socket = ssh_connection.socket.clone();
         run_cmd(socket.ssh_command(&remote_binary_path).arg("version")).await?;
 
         delegate.set_status(Some("Starting proxy"), cx);
 
-        let mut start_proxy_command = format!(
+        let mut start_proxy_command = String::new();
+        // Names were checked before connecting, so only the values need quoting.
+        for (name, value) in &socket.connection_options.env {
+            start_proxy_command.push_str(&format!("{}={} ", name, shlex::try_quote(value)?));
+        }
+        start_proxy_command.push_str(&format!(
             "RUST_LOG={} RUST_BACKTRACE={} {:?} proxy --identifier {}",
             std::env::var("RUST_LOG").unwrap_or_default(),
             std::env::var("RUST_BACKTRACE").unwrap_or_default(),
             remote_binary_path,
             unique_identifier,
-        );
+        ));
         if reconnect {
             start_proxy_command.push_str(" --reconnect");
         }
+        let start_proxy_command = socket
+            .connection_options
+            .wrap_in_shell(&start_proxy_command)?;
 
         let ssh_proxy_process = socket
             .ssh_command(start_proxy_command)
@@ -1021,6 +1926,14 @@ impl SshRemoteClient {
             .map(|ssh_connection| ssh_connection.socket.ssh_args())
     }
 
+    pub fn ssh_binary(&self) -> Option<PathBuf> {
+        self.state
+            .lock()
+            .as_ref()
+            .and_then(|state| state.ssh_connection())
+            .map(|ssh_connection| ssh_connection.socket.ssh_binary.clone())
+    }
+
     pub fn to_proto_client(&self) -> AnyProtoClient {
         self.client.clone().into()
     }
@@ -1051,6 +1964,21 @@ impl SshRemoteClient {
         self.connection_state() == ConnectionState::Disconnected
     }
 
+    /// Whether the connection is down but the server may still be running, so
+    /// that edits can be sent once it's restored.
+    pub fn is_offline(&self) -> bool {
+        self.connection_state() == ConnectionState::Offline
+    }
+
+    /// Round-trip time to the server, measured periodically while connected.
+    pub fn latency(&self) -> Option<Duration> {
+        if self.connection_state() == ConnectionState::Connected {
+            self.latency
+        } else {
+            None
+        }
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn fake(
         client_cx: &mut gpui::TestAppContext,
@@ -1069,6 +1997,8 @@ impl SshRemoteClient {
                     unique_identifier: "fake".to_string(),
                     connection_options: SshConnectionOptions::default(),
                     state: Arc::new(Mutex::new(None)),
+                    latency: None,
+                    server_not_running: false,
                 })
             }),
             server_cx.update(|cx| ChannelClient::new(client_to_server_rx, server_to_client_tx, cx)),
@@ -1084,14 +2014,19 @@ impl From<SshRemoteClient> for AnyProtoClient {
 
 struct SshRemoteConnection {
     socket: SshSocket,
-    master_process: process::Child,
+    /// The process owning the control socket. This is `None` for multiplexed
+    /// connections, whose master runs in the background until no connection
+    /// has used it for `SHARED_MASTER_PERSIST`, so it's never killed here.
+    master_process: Option<process::Child>,
     _temp_dir: TempDir,
 }
 
 impl Drop for SshRemoteConnection {
     fn drop(&mut self) {
-        if let Err(error) = self.master_process.kill() {
-            log::error!("failed to kill SSH master process: {}", error);
+        if let Some(master_process) = self.master_process.as_mut() {
+            if let Err(error) = master_process.kill() {
+                log::error!("failed to kill SSH master process: {}", error);
+            }
         }
     }
 }
@@ -1099,11 +2034,104 @@ impl Drop for SshRemoteConnection {
 impl SshRemoteConnection {
     #[cfg(not(unix))]
     async fn new(
-        _connection_options: SshConnectionOptions,
-        _delegate: Arc<dyn SshClientDelegate>,
-        _cx: &mut AsyncAppContext,
+        connection_options: SshConnectionOptions,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
     ) -> Result<Self> {
-        Err(anyhow!("ssh is not supported on this platform"))
+        // OpenSSH's connection sharing doesn't work here, so plink is the default.
+        let ssh_binary = delegate
+            .ssh_binary(cx)
+            .unwrap_or_else(|| PathBuf::from("plink"));
+        if !is_plink(&ssh_binary) {
+            Err(anyhow!(
+                "only PuTTY's plink is supported as an ssh client on this platform"
+            ))?;
+        }
+        Self::new_plink(connection_options, ssh_binary, delegate, cx).await
+    }
+
+    /// Connects using PuTTY's plink, relying on Pageant or a password for
+    /// authentication. Plink has no control socket, so this only checks that
+    /// commands can be run and remembers the credentials needed to run them.
+    async fn new_plink(
+        connection_options: SshConnectionOptions,
+        ssh_binary: PathBuf,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Self> {
+        delegate.set_status(Some("connecting"), cx);
+
+        if !connection_options.port_forwards.is_empty()
+            || connection_options.dynamic_forward_port.is_some()
+        {
+            log::warn!("port forwarding is not supported when connecting with plink");
+        }
+
+        let temp_dir = tempfile::Builder::new()
+            .prefix("zed-ssh-session")
+            .tempdir()?;
+        let mut auth = PlinkAuth::default();
+        let socket = loop {
+            let socket = SshSocket {
+                connection_options: connection_options.clone(),
+                socket_path: temp_dir.path().join("ssh.sock"),
+                ssh_binary: ssh_binary.clone(),
+                plink: Some(auth.clone()),
+            };
+            let output = socket.ssh_command("true").output().await?;
+            if output.status.success() {
+                break socket;
+            }
+
+            // In batch mode plink refuses to prompt, so translate the reason it
+            // stopped into the prompts the delegate already knows how to show.
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            delegate.log_output(&stderr);
+            if stderr.contains("does not match") {
+                let error_message = format!("Host key verification failed: {}", stderr.trim());
+                delegate.set_error(error_message.clone(), cx);
+                Err(anyhow!(error_message))?;
+            }
+            if auth.host_key.is_none() {
+                if let Some((key_type, fingerprint)) = parse_plink_host_key(&stderr) {
+                    let prompt = format!(
+                        "The authenticity of host '{}' can't be established.\n\
+                         {} key fingerprint is {}.\n\
+                         Are you sure you want to continue connecting (yes/no)?",
+                        connection_options.host, key_type, fingerprint
+                    );
+                    let answer = prompt_user(&delegate, &connection_options, prompt, cx).await??;
+                    if answer.trim() != "yes" {
+                        let error_message = "Host key verification failed.".to_string();
+                        delegate.set_error(error_message.clone(), cx);
+                        Err(anyhow!(error_message))?;
+                    }
+                    auth.host_key = Some(fingerprint);
+                    continue;
+                }
+            }
+            if auth.password_file.is_none()
+                && (stderr.contains("Cannot answer interactive prompts in batch mode")
+                    || stderr.contains("No supported authentication methods"))
+            {
+                let prompt = format!("{}'s password: ", connection_options.scp_url());
+                let password = prompt_user(&delegate, &connection_options, prompt, cx).await??;
+                let password_file = temp_dir.path().join("password");
+                fs::write(&password_file, password).await?;
+                auth.password_file = Some(password_file);
+                continue;
+            }
+
+            let error_message = format!("failed to connect: {}", stderr.trim());
+            delegate.set_error(error_message.clone(), cx);
+            Err(anyhow!(error_message))?;
+        };
+
+        Ok(Self {
+            socket,
+            master_process: None,
+            _temp_dir: temp_dir,
+        })
     }
 
     #[cfg(unix)]
@@ -1112,9 +2140,14 @@ impl SshRemoteConnection {
         delegate: Arc<dyn SshClientDelegate>,
         cx: &mut AsyncAppContext,
     ) -> Result<Self> {
-        use futures::{io::BufReader, AsyncBufReadExt as _};
         use smol::{fs::unix::PermissionsExt as _, net::unix::UnixListener};
-        use util::ResultExt as _;
+
+        let ssh_binary = delegate
+            .ssh_binary(cx)
+            .unwrap_or_else(|| PathBuf::from("ssh"));
+        if is_plink(&ssh_binary) {
+            return Self::new_plink(connection_options, ssh_binary, delegate, cx).await;
+        }
 
         delegate.set_status(Some("connecting"), cx);
 
@@ -1123,6 +2156,31 @@ impl SshRemoteConnection {
             .prefix("zed-ssh-session")
             .tempdir()?;
 
+        let socket_path = if connection_options.multiplex {
+            connection_options.shared_control_path().await?
+        } else {
+            temp_dir.path().join("ssh.sock")
+        };
+
+        if connection_options.multiplex && socket_path.exists() {
+            let socket = SshSocket {
+                connection_options: connection_options.clone(),
+                socket_path: socket_path.clone(),
+                ssh_binary: ssh_binary.clone(),
+                plink: None,
+            };
+            if socket.is_master_alive().await {
+                log::info!("reusing existing ssh connection to {}", url);
+                return Ok(Self {
+                    socket,
+                    master_process: None,
+                    _temp_dir: temp_dir,
+                });
+            }
+            // The socket was left behind by a master process that is gone.
+            fs::remove_file(&socket_path).await.log_err();
+        }
+
         // Create a domain socket listener to handle requests from the askpass program.
         let askpass_socket = temp_dir.path().join("askpass.sock");
         let (askpass_opened_tx, askpass_opened_rx) = oneshot::channel::<()>();
@@ -1131,6 +2189,7 @@ impl SshRemoteConnection {
 
         let askpass_task = cx.spawn({
             let delegate = delegate.clone();
+            let connection_options = connection_options.clone();
             |mut cx| async move {
                 let mut askpass_opened_tx = Some(askpass_opened_tx);
 
@@ -1144,12 +2203,16 @@ impl SshRemoteConnection {
                         buffer.clear();
                     }
                     let password_prompt = String::from_utf8_lossy(&buffer);
-                    if let Some(password) = delegate
-                        .ask_password(password_prompt.to_string(), &mut cx)
-                        .await
-                        .context("failed to get ssh password")
-                        .and_then(|p| p)
-                        .log_err()
+                    if let Some(password) = prompt_user(
+                        &delegate,
+                        &connection_options,
+                        password_prompt.to_string(),
+                        &mut cx,
+                    )
+                    .await
+                    .context("failed to get ssh password")
+                    .and_then(|p| p)
+                    .log_err()
                     {
                         stream.write_all(password.as_bytes()).await.log_err();
                     }
@@ -1171,16 +2234,28 @@ impl SshRemoteConnection {
         // Start the master SSH process, which does not do anything except for establish
         // the connection and keep it open, allowing other ssh commands to reuse it
         // via a control socket.
-        let socket_path = temp_dir.path().join("ssh.sock");
-        let mut master_process = process::Command::new("ssh")
+        // A shared master is started with `ControlPersist`, so it moves to the
+        // background once authenticated and stays alive while any connection
+        // uses it, instead of belonging to the connection that started it.
+        let mut master_command = process::Command::new(&ssh_binary);
+        master_command
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .env("SSH_ASKPASS_REQUIRE", "force")
             .env("SSH_ASKPASS", &askpass_script_path)
             .args(["-N", "-o", "ControlMaster=yes", "-o"])
-            .arg(format!("ControlPath={}", socket_path.display()))
+            .arg(format!("ControlPath={}", socket_path.display()));
+        if connection_options.multiplex {
+            master_command.arg("-o").arg(format!(
+                "ControlPersist={}",
+                SHARED_MASTER_PERSIST.as_secs()
+            ));
+        }
+        let mut master_process = master_command
+            .args(connection_options.additional_args())
             .arg(&url)
+            .kill_on_drop(true)
             .spawn()?;
 
         // Wait for this ssh process to close its stdout, indicating that authentication
@@ -1214,10 +2289,18 @@ impl SshRemoteConnection {
 
         drop(askpass_task);
 
-        if master_process.try_status()?.is_some() {
+        let multiplex = connection_options.multiplex;
+        let master_exited = if multiplex {
+            // The foreground process exits once the master is in the background.
+            !master_process.status().await?.success() || !socket_path.exists()
+        } else {
+            master_process.try_status()?.is_some()
+        };
+        if master_exited {
             output.clear();
             let mut stderr = master_process.stderr.take().unwrap();
             stderr.read_to_end(&mut output).await?;
+            delegate.log_output(&String::from_utf8_lossy(&output));
 
             let error_message = format!("failed to connect: {}", String::from_utf8_lossy(&output));
             delegate.set_error(error_message.clone(), cx);
@@ -1228,12 +2311,25 @@ impl SshRemoteConnection {
             socket: SshSocket {
                 connection_options,
                 socket_path,
+                ssh_binary,
+                plink: None,
             },
-            master_process,
+            master_process: (!multiplex).then_some(master_process),
             _temp_dir: temp_dir,
         })
     }
 
+    async fn kill_master_process(&mut self) -> Result<()> {
+        if let Some(master_process) = self.master_process.as_mut() {
+            master_process.kill()?;
+            master_process
+                .status()
+                .await
+                .context("Failed to kill ssh process")?;
+        }
+        Ok(())
+    }
+
     async fn ensure_server_binary(
         &self,
         delegate: &Arc<dyn SshClientDelegate>,
@@ -1254,7 +2350,7 @@ impl SshRemoteConnection {
             if let Ok(installed_version) =
                 run_cmd(self.socket.ssh_command(dst_path).arg("version")).await
             {
-                if installed_version.trim() == version.to_string() {
+                if is_server_version(&installed_version, version) {
                     server_binary_exists = true;
                 }
             }
@@ -1298,38 +2394,225 @@ impl SshRemoteConnection {
         Ok(())
     }
 
+    /// Runs the user's init command, streaming its output into the connection status.
+    async fn run_init_command(
+        &self,
+        init_command: &str,
+        delegate: &Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        delegate.set_status(Some("Running init command"), cx);
+
+        let init_command = self.socket.connection_options.wrap_in_shell(init_command)?;
+        let mut child = self
+            .socket
+            .ssh_command(init_command)
+            .spawn()
+            .context("failed to spawn init command")?;
+        let stdout = child.stdout.take().context("init command has no stdout")?;
+        let mut stderr = child.stderr.take().context("init command has no stderr")?;
+        let read_stdout = async {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Some(line) = lines.next().await {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    delegate.set_status(Some(line.trim()), cx);
+                }
+            }
+            anyhow::Ok(())
+        };
+        // Drained alongside stdout, so that the command can't block on a full stderr pipe.
+        let read_stderr = async move {
+            let mut output = Vec::new();
+            stderr.read_to_end(&mut output).await.map(|_| output)
+        };
+        let (stdout_result, stderr_result) = futures::join!(read_stdout, read_stderr);
+        stdout_result?;
+        let stderr_output = stderr_result?;
+
+        let status = child.status().await?;
+        if !status.success() {
+            Err(anyhow!(
+                "init command failed with {}: {}",
+                status,
+                String::from_utf8_lossy(&stderr_output)
+            ))?;
+        }
+        Ok(())
+    }
+
     async fn query_platform(&self) -> Result<SshPlatform> {
-        let os = run_cmd(self.socket.ssh_command("uname").arg("-s")).await?;
+        let os = match run_cmd(self.socket.ssh_command("uname").arg("-s")).await {
+            Ok(os) => os,
+            // Windows has no `uname`.
+            Err(error) => return Err(self.unsupported_platform_error().await.unwrap_or(error)),
+        };
         let arch = run_cmd(self.socket.ssh_command("uname").arg("-m")).await?;
 
         let os = match os.trim() {
             "Darwin" => "macos",
             "Linux" => "linux",
+            "FreeBSD" => "freebsd",
             _ => Err(anyhow!("unknown uname os {os:?}"))?,
         };
-        let arch = if arch.starts_with("arm") || arch.starts_with("aarch64") {
+        // armv8l is a 64-bit CPU running a 32-bit userland, which needs 32-bit binaries.
+        let arch = if arch.starts_with("armv7") || arch.starts_with("armv8l") {
+            "armv7"
+        } else if arch.starts_with("arm") || arch.starts_with("aarch64") {
             "aarch64"
-        } else if arch.starts_with("x86") || arch.starts_with("i686") {
+        } else if arch.starts_with("x86") || arch.starts_with("i686") || arch.starts_with("amd64") {
             "x86_64"
         } else {
             Err(anyhow!("unknown uname architecture {arch:?}"))?
         };
+        let musl = os == "linux" && !self.has_glibc().await;
 
-        Ok(SshPlatform { os, arch })
+        Ok(SshPlatform { os, arch, musl })
     }
 
-    async fn upload_file(&self, src_path: &Path, dest_path: &Path) -> Result<()> {
-        let mut command = process::Command::new("scp");
-        let output = self
+    /// Looks for a `zed-remote-server` on the host's PATH, e.g. installed by an
+    /// administrator for all users, that matches this version of Zed.
+    async fn find_system_server(&self, version: SemanticVersion) -> Option<PathBuf> {
+        let path = run_cmd(
+            self.socket
+                .ssh_command("command")
+                .args(["-v", "zed-remote-server"]),
+        )
+        .await
+        .ok()?;
+        let path = PathBuf::from(path.trim());
+        if path.as_os_str().is_empty() {
+            return None;
+        }
+
+        let installed_version = run_cmd(self.socket.ssh_command(&path).arg("version"))
+            .await
+            .ok()?;
+        if is_server_version(&installed_version, version) {
+            Some(path)
+        } else {
+            log::info!(
+                "ignoring system-installed remote server {:?} with version {}",
+                path,
+                installed_version.trim()
+            );
+            None
+        }
+    }
+
+    /// Recognizes hosts the remote server doesn't run on, so the user sees why
+    /// the connection failed instead of a missing `uname`.
+    async fn unsupported_platform_error(&self) -> Option<anyhow::Error> {
+        // Going through cmd expands the variable whether sshd starts cmd or PowerShell.
+        let os = run_cmd(self.socket.ssh_command("cmd").args(["/c", "echo", "%OS%"]))
+            .await
+            .ok()?;
+        (os.trim() == "Windows_NT").then(|| anyhow!("Windows hosts are not supported yet"))
+    }
+
+    /// glibc's `ldd` identifies itself as GNU. musl's prints its own name, and
+    /// minimal containers may have no `ldd` at all; the static musl build
+    /// runs on all of them.
+    async fn has_glibc(&self) -> bool {
+        let Ok(output) = self
             .socket
+            .ssh_command("ldd")
+            .arg("--version")
+            .output()
+            .await
+        else {
+            return false;
+        };
+        let version = String::from_utf8_lossy(&output.stdout);
+        output.status.success() && (version.contains("GNU") || version.contains("GLIBC"))
+    }
+
+    async fn upload_file(&self, src_path: &Path, dest_path: &Path) -> Result<()> {
+        match self.socket.connection_options.upload_method {
+            Some(SshUploadMethod::Scp) => self.upload_file_scp(src_path, dest_path).await,
+            Some(SshUploadMethod::Sftp) => self.upload_file_sftp(src_path, dest_path).await,
+            None => match self.upload_file_scp(src_path, dest_path).await {
+                // Some hosts restrict exec channels, which scp needs but sftp doesn't.
+                Err(error) if self.socket.plink.is_none() => {
+                    log::warn!("{:?}; retrying upload over sftp", error);
+                    self.upload_file_sftp(src_path, dest_path).await
+                }
+                result => result,
+            },
+        }
+    }
+
+    async fn upload_file_sftp(&self, src_path: &Path, dest_path: &Path) -> Result<()> {
+        if self.socket.plink.is_some() {
+            Err(anyhow!("sftp uploads are not supported with plink"))?;
+        }
+
+        let mut command = process::Command::new("sftp");
+        self.socket
             .ssh_options(&mut command)
-            .args(
-                self.socket
-                    .connection_options
-                    .port
-                    .map(|port| vec!["-P".to_string(), port.to_string()])
-                    .unwrap_or_default(),
+            .arg("-S")
+            .arg(&self.socket.ssh_binary)
+            .args(["-b", "-"]);
+        if let Some(port) = self.socket.connection_options.port {
+            command.arg("-P").arg(port.to_string());
+        }
+        let mut child = command
+            .arg(self.socket.connection_options.scp_url())
+            .spawn()
+            .context("failed to spawn sftp")?;
+
+        let mut stdin = child.stdin.take().context("sftp has no stdin")?;
+        stdin
+            .write_all(
+                format!(
+                    "put \"{}\" \"{}\"\n",
+                    src_path.display(),
+                    dest_path.display()
+                )
+                .as_bytes(),
             )
+            .await?;
+        drop(stdin);
+
+        let output = child.output().await?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "failed to upload file over sftp {} -> {}: {}",
+                src_path.display(),
+                dest_path.display(),
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    }
+
+    async fn upload_file_scp(&self, src_path: &Path, dest_path: &Path) -> Result<()> {
+        let mut command = if self.socket.plink.is_some() {
+            // pscp takes the same connection arguments as plink, including the port.
+            let pscp = self
+                .socket
+                .ssh_binary
+                .with_file_name(format!("pscp{}", std::env::consts::EXE_SUFFIX));
+            let mut command = process::Command::new(pscp);
+            self.socket.ssh_options(&mut command);
+            command
+        } else {
+            let mut command = process::Command::new("scp");
+            self.socket
+                .ssh_options(&mut command)
+                .arg("-S")
+                .arg(&self.socket.ssh_binary)
+                .args(
+                    self.socket
+                        .connection_options
+                        .port
+                        .map(|port| vec!["-P".to_string(), port.to_string()])
+                        .unwrap_or_default(),
+                );
+            command
+        };
+        let output = command
             .arg(src_path)
             .arg(format!(
                 "{}:{}",
@@ -1356,7 +2639,16 @@ type ResponseChannels = Mutex<HashMap<MessageId, oneshot::Sender<(Envelope, ones
 
 pub struct ChannelClient {
     next_message_id: AtomicU32,
+    /// The id of the next message we expect from the other side. Sent along
+    /// with every outgoing message to acknowledge everything before it.
+    next_incoming_id: AtomicU32,
     outgoing_tx: mpsc::UnboundedSender<Envelope>,
+    /// Messages that have been sent but not yet acknowledged, kept around so
+    /// they can be sent again after the transport has been re-established.
+    buffer: Mutex<VecDeque<Envelope>>, // Lock
+    /// The incoming id at which we last asked the other side to resend its
+    /// messages, so that a missing message is only asked for once.
+    resend_requested_at: Mutex<Option<u32>>, // Lock
     response_channels: ResponseChannels,             // Lock
     message_handlers: Mutex<ProtoMessageHandlerSet>, // Lock
 }
@@ -1370,6 +2662,9 @@ impl ChannelClient {
         let this = Arc::new(Self {
             outgoing_tx,
             next_message_id: AtomicU32::new(0),
+            next_incoming_id: AtomicU32::new(0),
+            buffer: Default::default(),
+            resend_requested_at: Default::default(),
             response_channels: ResponseChannels::default(),
             message_handlers: Default::default(),
         });
@@ -1393,6 +2688,43 @@ impl ChannelClient {
                         return anyhow::Ok(());
                     };
 
+                    if let Some(ack_id) = incoming.ack_id {
+                        let mut buffer = this.buffer.lock();
+                        while buffer.front().is_some_and(|message| message.id < ack_id) {
+                            buffer.pop_front();
+                        }
+                    }
+
+                    // Requests to resend are outside of the message sequence, since
+                    // they're sent precisely when messages may have been lost.
+                    if let Some(proto::envelope::Payload::FlushBufferedMessages(_)) =
+                        &incoming.payload
+                    {
+                        this.resend_buffered_messages();
+                        continue;
+                    }
+
+                    // Messages that were in flight when the connection dropped are sent
+                    // again after reconnecting, so skip anything we've already handled,
+                    // as well as anything that arrives ahead of a message we missed.
+                    let expected_id = this.next_incoming_id.load(SeqCst);
+                    if incoming.id != expected_id {
+                        log::debug!(
+                            "ignoring ssh message. id:{}, expected:{}",
+                            incoming.id,
+                            expected_id
+                        );
+                        if incoming.id > expected_id {
+                            let mut resend_requested_at = this.resend_requested_at.lock();
+                            if *resend_requested_at != Some(expected_id) {
+                                *resend_requested_at = Some(expected_id);
+                                this.request_resend();
+                            }
+                        }
+                        continue;
+                    }
+                    this.next_incoming_id.store(expected_id + 1, SeqCst);
+
                     if let Some(request_id) = incoming.responding_to {
                         let request_id = MessageId(request_id);
                         let sender = this.response_channels.lock().remove(&request_id);
@@ -1485,6 +2817,51 @@ impl ChannelClient {
         .await
     }
 
+    /// Sends every unacknowledged message again and asks the other side to do
+    /// the same. Used after the transport has been re-established, so that no
+    /// messages are lost to the old connection.
+    pub async fn resync(&self, timeout: Duration) -> Result<()> {
+        self.resend_buffered_messages();
+        self.request_resend();
+        self.ping(timeout).await
+    }
+
+    /// Asks the other side to send every message we haven't acknowledged again.
+    /// This isn't buffered, nor given an id, so it can't itself go missing from
+    /// the sequence of messages.
+    fn request_resend(&self) {
+        let mut envelope = proto::FlushBufferedMessages {}.into_envelope(0, None, None);
+        envelope.ack_id = Some(self.next_incoming_id.load(SeqCst));
+        self.outgoing_tx.unbounded_send(envelope).log_err();
+    }
+
+    /// Forgets everything exchanged with the other side, for when it has been
+    /// replaced by a new process. Requests still waiting for a response fail,
+    /// since the new process never received them.
+    fn reset(&self) {
+        let mut buffer = self.buffer.lock();
+        buffer.clear();
+        *self.resend_requested_at.lock() = None;
+        self.next_message_id.store(0, SeqCst);
+        self.next_incoming_id.store(0, SeqCst);
+        self.response_channels.lock().clear();
+    }
+
+    fn resend_buffered_messages(&self) {
+        let buffer = self.buffer.lock();
+        if buffer.is_empty() {
+            return;
+        }
+        log::info!("resending {} buffered ssh messages", buffer.len());
+
+        let ack_id = self.next_incoming_id.load(SeqCst);
+        for envelope in buffer.iter() {
+            let mut envelope = envelope.clone();
+            envelope.ack_id = Some(ack_id);
+            self.outgoing_tx.unbounded_send(envelope).log_err();
+        }
+    }
+
     pub fn send<T: EnvelopedMessage>(&self, payload: T) -> Result<()> {
         log::debug!("ssh send name:{}", T::NAME);
         self.send_dynamic(payload.into_envelope(0, None, None))
@@ -1492,15 +2869,13 @@ impl ChannelClient {
 
     pub fn request_dynamic(
         &self,
-        mut envelope: proto::Envelope,
+        envelope: proto::Envelope,
         type_name: &'static str,
     ) -> impl 'static + Future<Output = Result<proto::Envelope>> {
-        envelope.id = self.next_message_id.fetch_add(1, SeqCst);
         let (tx, rx) = oneshot::channel();
-        let mut response_channels_lock = self.response_channels.lock();
-        response_channels_lock.insert(MessageId(envelope.id), tx);
-        drop(response_channels_lock);
-        let result = self.outgoing_tx.unbounded_send(envelope);
+        let result = self.send_buffered(envelope, |id| {
+            self.response_channels.lock().insert(MessageId(id), tx);
+        });
         async move {
             if let Err(error) = &result {
                 log::error!("failed to send message: {}", error);
@@ -1515,8 +2890,31 @@ impl ChannelClient {
         }
     }
 
-    pub fn send_dynamic(&self, mut envelope: proto::Envelope) -> Result<()> {
+    pub fn send_dynamic(&self, envelope: proto::Envelope) -> Result<()> {
+        self.send_buffered(envelope, |_| {})
+    }
+
+    fn send_buffered(
+        &self,
+        mut envelope: proto::Envelope,
+        on_id_assigned: impl FnOnce(u32),
+    ) -> Result<()> {
+        // Hold the buffer lock while assigning the id, so that messages are
+        // buffered and sent in id order.
+        let mut buffer = self.buffer.lock();
+        if buffer.len() >= MAX_BUFFERED_MESSAGES {
+            // The other side hasn't acknowledged anything in a long time. Stop
+            // queueing messages for it and fail the requests waiting on it.
+            self.response_channels.lock().clear();
+            Err(anyhow!(
+                "too many unacknowledged ssh messages ({})",
+                buffer.len()
+            ))?;
+        }
         envelope.id = self.next_message_id.fetch_add(1, SeqCst);
+        envelope.ack_id = Some(self.next_incoming_id.load(SeqCst));
+        on_id_assigned(envelope.id);
+        buffer.push_back(envelope.clone());
         self.outgoing_tx.unbounded_send(envelope)?;
         Ok(())
     }
@@ -1547,3 +2945,51 @@ impl ProtoClient for ChannelClient {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resolved_ssh_config() {
+        let config = ResolvedSshConfig::parse(
+            "user root\nhostname 10.0.0.5\nport 2222\nproxycommand none\n\
+             userknownhostsfile /etc/zed/known_hosts /tmp/known_hosts2\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            ResolvedSshConfig {
+                hostname: "10.0.0.5".into(),
+                port: 2222,
+                host_key_alias: None,
+                proxy_command: None,
+                proxy_jump: None,
+                user_known_hosts_files: vec![
+                    PathBuf::from("/etc/zed/known_hosts"),
+                    PathBuf::from("/tmp/known_hosts2")
+                ],
+            }
+        );
+        assert!(ResolvedSshConfig::parse("port 22\n").is_err());
+    }
+
+    #[test]
+    fn test_is_server_version() {
+        let version = SemanticVersion::new(0, 160, 2);
+        assert!(is_server_version("0.160.2\n", version));
+        assert!(!is_server_version("0.160.1\n", version));
+        assert!(!is_server_version("", version));
+    }
+
+    #[test]
+    fn test_is_env_var_name() {
+        assert!(is_env_var_name("HTTP_PROXY"));
+        assert!(is_env_var_name("_private1"));
+        assert!(!is_env_var_name(""));
+        assert!(!is_env_var_name("1PATH"));
+        assert!(!is_env_var_name("MY-VAR"));
+        assert!(!is_env_var_name("A=B"));
+        assert!(!is_env_var_name("$(reboot)"));
+    }
+}
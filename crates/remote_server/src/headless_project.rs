@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context as _, Result};
 use fs::Fs;
 use gpui::{AppContext, AsyncAppContext, Context, Model, ModelContext};
 use language::{proto::serialize_operation, Buffer, BufferEvent, LanguageRegistry};
@@ -131,6 +131,8 @@ impl HeadlessProject {
 
         client.add_request_handler(cx.weak_model(), Self::handle_list_remote_directory);
         client.add_request_handler(cx.weak_model(), Self::handle_check_file_exists);
+        client.add_request_handler(cx.weak_model(), Self::handle_create_remote_directory);
+        client.add_request_handler(cx.weak_model(), Self::handle_get_path_metadata);
         client.add_request_handler(cx.weak_model(), Self::handle_shutdown_remote_server);
         client.add_request_handler(cx.weak_model(), Self::handle_ping);
 
@@ -141,6 +143,7 @@ impl HeadlessProject {
         client.add_model_request_handler(Self::handle_find_search_candidates);
 
         client.add_model_request_handler(BufferStore::handle_update_buffer);
+        client.add_model_request_handler(BufferStore::handle_restore_buffer);
         client.add_model_message_handler(BufferStore::handle_close_buffer);
 
         BufferStore::init(&client);
@@ -223,9 +226,8 @@ impl HeadlessProject {
         mut cx: AsyncAppContext,
     ) -> Result<proto::AddWorktreeResponse> {
         use client::ErrorCodeExt;
-        let path = shellexpand::tilde(&message.payload.path).to_string();
-
         let fs = this.read_with(&mut cx, |this, _| this.fs.clone())?;
+        let path = expand_path(&message.payload.path, fs.as_ref()).await?;
         let path = PathBuf::from(path);
 
         let canonicalized = match fs.canonicalize(&path).await {
@@ -246,17 +248,44 @@ impl HeadlessProject {
             }
         };
 
-        let worktree = this
-            .update(&mut cx.clone(), |this, _| {
-                Worktree::local(
-                    Arc::from(canonicalized),
-                    message.payload.visible,
-                    this.fs.clone(),
-                    this.next_entry_id.clone(),
-                    &mut cx,
-                )
-            })?
-            .await?;
+        let worktree = match message.payload.worktree_id.map(WorktreeId::from_proto) {
+            // The client is re-adding a worktree it knew before this server started.
+            Some(worktree_id) => {
+                let existing = this.update(&mut cx, |this, cx| {
+                    this.worktree_store
+                        .read(cx)
+                        .worktree_for_id(worktree_id, cx)
+                })?;
+                if existing.is_some() {
+                    return Ok(proto::AddWorktreeResponse {
+                        worktree_id: worktree_id.to_proto(),
+                    });
+                }
+                this.update(&mut cx.clone(), |this, _| {
+                    Worktree::local_with_id(
+                        Arc::from(canonicalized),
+                        worktree_id,
+                        message.payload.visible,
+                        this.fs.clone(),
+                        this.next_entry_id.clone(),
+                        &mut cx,
+                    )
+                })?
+                .await?
+            }
+            None => {
+                this.update(&mut cx.clone(), |this, _| {
+                    Worktree::local(
+                        Arc::from(canonicalized),
+                        message.payload.visible,
+                        this.fs.clone(),
+                        this.next_entry_id.clone(),
+                        &mut cx,
+                    )
+                })?
+                .await?
+            }
+        };
 
         let response = this.update(&mut cx, |_, cx| {
             worktree.update(cx, |worktree, _| proto::AddWorktreeResponse {
@@ -377,17 +406,23 @@ impl HeadlessProject {
         envelope: TypedEnvelope<proto::ListRemoteDirectory>,
         cx: AsyncAppContext,
     ) -> Result<proto::ListRemoteDirectoryResponse> {
-        let expanded = shellexpand::tilde(&envelope.payload.path).to_string();
         let fs = cx.read_model(&this, |this, _| this.fs.clone())?;
+        let expanded = expand_path(&envelope.payload.path, fs.as_ref()).await?;
 
         let mut entries = Vec::new();
+        let mut entry_is_dir = Vec::new();
         let mut response = fs.read_dir(Path::new(&expanded)).await?;
         while let Some(path) = response.next().await {
-            if let Some(file_name) = path?.file_name() {
+            let path = path?;
+            if let Some(file_name) = path.file_name() {
                 entries.push(file_name.to_string_lossy().to_string());
+                entry_is_dir.push(fs.is_dir(&path).await);
             }
         }
-        Ok(proto::ListRemoteDirectoryResponse { entries })
+        Ok(proto::ListRemoteDirectoryResponse {
+            entries,
+            entry_is_dir,
+        })
     }
 
     pub async fn handle_check_file_exists(
@@ -396,7 +431,7 @@ impl HeadlessProject {
         cx: AsyncAppContext,
     ) -> Result<proto::CheckFileExistsResponse> {
         let fs = cx.read_model(&this, |this, _| this.fs.clone())?;
-        let expanded = shellexpand::tilde(&envelope.payload.path).to_string();
+        let expanded = expand_path(&envelope.payload.path, fs.as_ref()).await?;
 
         let exists = fs.is_file(&PathBuf::from(expanded.clone())).await;
 
@@ -406,6 +441,31 @@ impl HeadlessProject {
         })
     }
 
+    pub async fn handle_create_remote_directory(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::CreateRemoteDirectory>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let fs = cx.read_model(&this, |this, _| this.fs.clone())?;
+        let expanded = expand_path(&envelope.payload.path, fs.as_ref()).await?;
+        fs.create_dir(Path::new(&expanded)).await?;
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_get_path_metadata(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetPathMetadata>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GetPathMetadataResponse> {
+        let fs = cx.read_model(&this, |this, _| this.fs.clone())?;
+        let expanded = expand_path(&envelope.payload.path, fs.as_ref()).await?;
+        let metadata = fs.metadata(Path::new(&expanded)).await?;
+        Ok(proto::GetPathMetadataResponse {
+            exists: metadata.is_some(),
+            is_dir: metadata.map_or(false, |metadata| metadata.is_dir),
+        })
+    }
+
     pub async fn handle_shutdown_remote_server(
         _this: Model<Self>,
         _envelope: TypedEnvelope<proto::ShutdownRemoteServer>,
@@ -433,3 +493,90 @@ impl HeadlessProject {
         Ok(proto::Ack {})
     }
 }
+
+/// Expands `~`, `~user` and `$VAR`s in a path sent by the client, the way a shell on the
+/// server would, so that the same configured paths work for any user on any host.
+///
+/// A path that exists exactly as written is used as is, so that directories whose
+/// names contain a `$` stay reachable.
+async fn expand_path(path: &str, fs: &dyn Fs) -> Result<String> {
+    if path.contains('$') && fs.metadata(Path::new(path)).await.ok().flatten().is_some() {
+        return Ok(path.to_string());
+    }
+    expand_path_with_env(path, |name| std::env::var(name).ok())
+}
+
+/// Like [`expand_path`], but looks up variables, including `HOME`, through `env`.
+pub(crate) fn expand_path_with_env(
+    path: &str,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<String> {
+    let path = match path.strip_prefix('~') {
+        Some(rest) if !rest.is_empty() && !rest.starts_with('/') => {
+            let (user, rest) = match rest.split_once('/') {
+                Some((user, rest)) => (user, Some(rest)),
+                None => (rest, None),
+            };
+            let home = home_dir_for_user(user)
+                .with_context(|| format!("no home directory found for user {user}"))?;
+            match rest {
+                Some(rest) => home.join(rest).to_string_lossy().into_owned(),
+                None => home.to_string_lossy().into_owned(),
+            }
+        }
+        _ => path.to_string(),
+    };
+    let home_dir =
+        || env("HOME").or_else(|| Some(util::paths::home_dir().to_string_lossy().into_owned()));
+    Ok(shellexpand::full_with_context(&path, home_dir, |name| {
+        env(name).map(Some).ok_or(std::env::VarError::NotPresent)
+    })
+    .with_context(|| format!("expanding {path}"))?
+    .into_owned())
+}
+
+#[cfg(unix)]
+fn home_dir_for_user(user: &str) -> Option<PathBuf> {
+    const MAX_BUFFER_LEN: usize = 1 << 20;
+
+    let user = std::ffi::CString::new(user).ok()?;
+    // SAFETY: `sysconf` has no preconditions.
+    let mut buflen = match unsafe { libc::sysconf(libc::_SC_GETPW_R_SIZE_MAX) } {
+        n if n < 0 => 1024,
+        n => n as usize,
+    };
+    loop {
+        let mut buffer = vec![0 as libc::c_char; buflen];
+        let mut pwd: std::mem::MaybeUninit<libc::passwd> = std::mem::MaybeUninit::uninit();
+        let mut result: *mut libc::passwd = std::ptr::null_mut();
+        // SAFETY: `user` is a valid NUL-terminated string, and `pwd`, `buffer` and
+        // `result` are valid for writes, with `buffer` being `buflen` long.
+        let status = unsafe {
+            libc::getpwnam_r(
+                user.as_ptr(),
+                pwd.as_mut_ptr(),
+                buffer.as_mut_ptr(),
+                buflen,
+                &mut result,
+            )
+        };
+        if status == libc::ERANGE && buflen < MAX_BUFFER_LEN {
+            buflen *= 2;
+            continue;
+        }
+        if status != 0 || result.is_null() {
+            return None;
+        }
+        // SAFETY: `getpwnam_r` succeeded and set `result`, so it initialized `pwd`.
+        let entry = unsafe { pwd.assume_init() };
+        // SAFETY: `pw_dir` points to a NUL-terminated string in `buffer`, which is
+        // still alive.
+        let home = unsafe { std::ffi::CStr::from_ptr(entry.pw_dir) };
+        return Some(PathBuf::from(home.to_string_lossy().into_owned()));
+    }
+}
+
+#[cfg(not(unix))]
+fn home_dir_for_user(_user: &str) -> Option<PathBuf> {
+    None
+}
@@ -73,7 +73,7 @@ fn main() -> Result<()> {
             }
         },
         Some(Commands::Version) => {
-            eprintln!("{}", env!("ZED_PKG_VERSION"));
+            println!("{}", env!("ZED_PKG_VERSION"));
             Ok(())
         }
         None => {
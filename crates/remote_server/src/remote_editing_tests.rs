@@ -1,4 +1,4 @@
-use crate::headless_project::HeadlessProject;
+use crate::headless_project::{expand_path_with_env, HeadlessProject};
 use client::{Client, UserStore};
 use clock::FakeSystemClock;
 use fs::{FakeFs, Fs};
@@ -13,7 +13,7 @@ use lsp::{CompletionContext, CompletionResponse, CompletionTriggerKind};
 use node_runtime::NodeRuntime;
 use project::{
     search::{SearchQuery, SearchResult},
-    Project, ProjectPath,
+    PathKind, Project, ProjectPath,
 };
 use remote::SshRemoteClient;
 use serde_json::json;
@@ -536,6 +536,57 @@ async fn test_remote_resolve_file_path(cx: &mut TestAppContext, server_cx: &mut
     );
 }
 
+#[gpui::test]
+async fn test_remote_path_kind(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let (project, _headless, _fs) = init_test(cx, server_cx).await;
+
+    let path_kind = |path: &str, cx: &mut TestAppContext| {
+        project.update(cx, |project, cx| project.path_kind(path.to_string(), cx))
+    };
+    assert_eq!(
+        path_kind("/code/project1", cx).await.unwrap(),
+        Some(PathKind::Directory)
+    );
+    assert_eq!(
+        path_kind("/code/project1/README.md", cx).await.unwrap(),
+        Some(PathKind::File)
+    );
+    assert_eq!(path_kind("/code/project3/src", cx).await.unwrap(), None);
+
+    project
+        .update(cx, |project, cx| {
+            project.create_directory("/code/project3/src".to_string(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        path_kind("/code/project3/src", cx).await.unwrap(),
+        Some(PathKind::Directory)
+    );
+}
+
+#[test]
+fn test_remote_path_expansion() {
+    let env = |name: &str| match name {
+        "HOME" => Some("/home/me".to_string()),
+        "ZED_TEST_REMOTE_CODE_DIR" => Some("/code".to_string()),
+        _ => None,
+    };
+    assert_eq!(
+        expand_path_with_env("$ZED_TEST_REMOTE_CODE_DIR/project1", env).unwrap(),
+        "/code/project1"
+    );
+    assert_eq!(
+        expand_path_with_env("~/project1", env).unwrap(),
+        "/home/me/project1"
+    );
+    assert_eq!(
+        expand_path_with_env("/code/project1", env).unwrap(),
+        "/code/project1"
+    );
+    assert!(expand_path_with_env("$ZED_TEST_UNSET_DIR/project1", env).is_err());
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_canceling_buffer_opening(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let (project, _headless, _fs) = init_test(cx, server_cx).await;
@@ -9,13 +9,14 @@ use serde::{de::DeserializeOwned, Deserialize as _, Serialize};
 use smallvec::SmallVec;
 use std::{
     any::{type_name, Any, TypeId},
+    cmp::Reverse,
     fmt::Debug,
     ops::Range,
     path::{Path, PathBuf},
     str,
     sync::{Arc, LazyLock},
 };
-use tree_sitter::Query;
+use tree_sitter::{Node, Query};
 use util::{merge_non_null_json_value_into, RangeExt, ResultExt as _};
 
 use crate::{SettingsJsonSchemaParams, WorktreeId};
@@ -1004,6 +1005,20 @@ fn update_value_in_json_text<'a>(
         .map_or(false, |key| preserved_keys.contains(key))
         || old_value != new_value
     {
+        // Arrays are edited element by element, keeping the comments and formatting
+        // of the elements that didn't change.
+        if old_value.is_array() && new_value.is_array() {
+            if let Some(array_edits) =
+                edits_for_array_in_json_text(text, key_path, tab_size, old_value, new_value)
+            {
+                for (range, replacement) in array_edits {
+                    text.replace_range(range.clone(), &replacement);
+                    edits.push((range, replacement));
+                }
+                return;
+            }
+        }
+
         let mut new_value = new_value.clone();
         if let Some(new_object) = new_value.as_object_mut() {
             new_object.retain(|_, v| !v.is_null());
@@ -1136,6 +1151,400 @@ fn replace_value_in_json_text(
     }
 }
 
+/// Returns the edits turning the array at `key_path` into `new_value`, or `None` when
+/// there is no array at that path in the text. The edits are ordered from the end of
+/// the text to its start, so that each one can be applied after the previous ones.
+fn edits_for_array_in_json_text(
+    text: &str,
+    key_path: &[&str],
+    tab_size: usize,
+    old_value: &serde_json::Value,
+    new_value: &serde_json::Value,
+) -> Option<Vec<(Range<usize>, String)>> {
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(&tree_sitter_json::LANGUAGE.into())
+        .unwrap();
+    let syntax_tree = parser.parse(text, None)?;
+
+    let mut node = json_children(syntax_tree.root_node()).next()?;
+    for key in key_path {
+        node = json_object_value(text, node, key)?;
+    }
+    if node.kind() != "array" {
+        return None;
+    }
+
+    let mut edits = Vec::new();
+    json_value_edits(text, node, tab_size, old_value, new_value, &mut edits);
+    edits.sort_by_key(|(range, _)| Reverse(range.start));
+    Some(edits)
+}
+
+/// Collects the edits turning the JSON value at `node` from `old_value` into
+/// `new_value`, descending into objects and arrays so that only what changed is
+/// replaced.
+fn json_value_edits(
+    text: &str,
+    node: Node,
+    tab_size: usize,
+    old_value: &serde_json::Value,
+    new_value: &serde_json::Value,
+    edits: &mut Vec<(Range<usize>, String)>,
+) {
+    if old_value == new_value {
+        return;
+    }
+
+    let indent = line_indent(text, node.start_byte());
+    let replace_node = |edits: &mut Vec<(Range<usize>, String)>| {
+        edits.push((
+            node.byte_range(),
+            to_pretty_json(new_value, tab_size, indent),
+        ));
+    };
+
+    match (old_value, new_value) {
+        (serde_json::Value::Object(old_object), serde_json::Value::Object(new_object))
+            if node.kind() == "object" =>
+        {
+            let pairs = json_children(node)
+                .filter(|child| child.kind() == "pair")
+                .collect::<Vec<_>>();
+            let pair_key = |pair: &Node| {
+                let key = pair.child_by_field_name("key")?;
+                let key = text.get(key.byte_range())?;
+                Some(key.trim_matches('"').to_string())
+            };
+
+            let removed_pairs = pairs
+                .iter()
+                .enumerate()
+                .filter(|(_, pair)| {
+                    pair_key(*pair).map_or(false, |key| {
+                        old_object.contains_key(&key) && !new_object.contains_key(&key)
+                    })
+                })
+                .map(|(ix, _)| ix)
+                .collect::<Vec<_>>();
+            let Some(removals) = removal_ranges(text, &pairs, &removed_pairs) else {
+                replace_node(edits);
+                return;
+            };
+
+            let mut pair_edits = Vec::new();
+            let mut insertions = Vec::new();
+            for (key, new_sub_value) in new_object {
+                let old_sub_value = old_object.get(key).unwrap_or(&serde_json::Value::Null);
+                let pair = pairs
+                    .iter()
+                    .find(|pair| pair_key(*pair).as_deref() == Some(key.as_str()));
+                match pair.and_then(|pair| pair.child_by_field_name("value")) {
+                    Some(value) => json_value_edits(
+                        text,
+                        value,
+                        tab_size,
+                        old_sub_value,
+                        new_sub_value,
+                        &mut pair_edits,
+                    ),
+                    None if new_sub_value.is_null() => {}
+                    None => insertions.push((key, new_sub_value)),
+                }
+            }
+            if !insertions.is_empty() {
+                // New keys go first, as when they are added to objects outside of arrays.
+                let first_pair = pairs.first().filter(|_| !removed_pairs.contains(&0));
+                let Some(first_pair) = first_pair else {
+                    replace_node(edits);
+                    return;
+                };
+                let start = first_pair.start_byte();
+                let mut content = String::new();
+                for (key, value) in insertions {
+                    if starts_line(text, start) {
+                        let pair_indent = line_indent(text, start);
+                        content.push_str(&format!(
+                            "\"{key}\": {},\n{:pair_indent$}",
+                            to_pretty_json(value, tab_size, pair_indent),
+                            ""
+                        ));
+                    } else {
+                        content.push_str(&format!(
+                            "\"{key}\": {}, ",
+                            serde_json::to_string(value).unwrap()
+                        ));
+                    }
+                }
+                pair_edits.push((start..start, content));
+            }
+            edits.extend(pair_edits);
+            edits.extend(removals);
+        }
+        (serde_json::Value::Array(old_array), serde_json::Value::Array(new_array))
+            if node.kind() == "array" =>
+        {
+            let elements = json_children(node).collect::<Vec<_>>();
+            if elements.len() != old_array.len() {
+                replace_node(edits);
+            } else if old_array.len() == new_array.len() {
+                let moved_from = array_moves(old_array, new_array);
+                let moved = moved_from
+                    .iter()
+                    .enumerate()
+                    .any(|(ix, from)| from.map_or(false, |from| from != ix));
+                if !moved {
+                    for ((element, old_element), new_element) in
+                        elements.into_iter().zip(old_array).zip(new_array)
+                    {
+                        json_value_edits(text, element, tab_size, old_element, new_element, edits);
+                    }
+                    return;
+                }
+                // Elements that were only reordered are moved along with their comments.
+                // When they were changed as well, there's no telling which comments
+                // belong where anymore.
+                let moved_from = moved_from.into_iter().collect::<Option<Vec<_>>>();
+                match moved_from
+                    .and_then(|moved_from| array_move_edits(text, node, &elements, &moved_from))
+                {
+                    Some(move_edits) => edits.extend(move_edits),
+                    None => replace_node(edits),
+                }
+            } else if new_array.len() > old_array.len() && new_array.starts_with(old_array) {
+                let Some(last_element) = elements.last() else {
+                    replace_node(edits);
+                    return;
+                };
+                let end = last_element.end_byte();
+                let element_indent = line_indent(text, last_element.start_byte());
+                let mut content = String::new();
+                for element in &new_array[old_array.len()..] {
+                    if starts_line(text, last_element.start_byte()) {
+                        content.push_str(&format!(
+                            ",\n{:element_indent$}{}",
+                            "",
+                            to_pretty_json(element, tab_size, element_indent)
+                        ));
+                    } else {
+                        content.push_str(&format!(", {}", serde_json::to_string(element).unwrap()));
+                    }
+                }
+                edits.push((end..end, content));
+            } else if let Some(removed_ix) = (new_array.len() + 1 == old_array.len())
+                .then(|| {
+                    (0..old_array.len()).find(|&ix| {
+                        old_array[..ix] == new_array[..ix] && old_array[ix + 1..] == new_array[ix..]
+                    })
+                })
+                .flatten()
+            {
+                match removal_ranges(text, &elements, &[removed_ix]) {
+                    Some(removals) => edits.extend(removals),
+                    None => replace_node(edits),
+                }
+            } else {
+                replace_node(edits);
+            }
+        }
+        _ => replace_node(edits),
+    }
+}
+
+/// For each element of `new_array`, the index of an equal element of `old_array`,
+/// preferring the one at the same position.
+fn array_moves(
+    old_array: &[serde_json::Value],
+    new_array: &[serde_json::Value],
+) -> Vec<Option<usize>> {
+    let mut used = old_array
+        .iter()
+        .zip(new_array)
+        .map(|(old, new)| old == new)
+        .collect::<Vec<_>>();
+    new_array
+        .iter()
+        .enumerate()
+        .map(|(ix, new)| {
+            if old_array.get(ix) == Some(new) {
+                return Some(ix);
+            }
+            let from = (0..old_array.len()).find(|&from| !used[from] && old_array[from] == *new)?;
+            used[from] = true;
+            Some(from)
+        })
+        .collect()
+}
+
+/// The edits moving the elements of an array to the positions given by `moved_from`,
+/// along with the comments on the lines above them and at the end of their line.
+/// Returns `None` when the array has comments elsewhere, or when it has comments and
+/// elements that don't start their line.
+fn array_move_edits(
+    text: &str,
+    array: Node,
+    elements: &[Node],
+    moved_from: &[usize],
+) -> Option<Vec<(Range<usize>, String)>> {
+    let comments = (0..array.named_child_count())
+        .filter_map(|ix| array.named_child(ix))
+        .filter(|child| child.kind() == "comment")
+        .collect::<Vec<_>>();
+    if !comments.is_empty()
+        && !elements
+            .iter()
+            .all(|element| starts_line(text, element.start_byte()))
+    {
+        return None;
+    }
+
+    let mut starts = elements
+        .iter()
+        .map(|element| element.start_byte())
+        .collect::<Vec<_>>();
+    let mut trailing_comments = vec![None; elements.len()];
+    for comment in comments {
+        let start = comment.start_byte();
+        let next = elements
+            .iter()
+            .position(|element| element.start_byte() > start)
+            .unwrap_or(elements.len());
+        let previous = next.checked_sub(1);
+        if let Some(previous) =
+            previous.filter(|&previous| !text[elements[previous].end_byte()..start].contains('\n'))
+        {
+            if trailing_comments[previous].is_some() {
+                return None;
+            }
+            let whitespace_start = text[..start].trim_end_matches([' ', '\t']).len();
+            trailing_comments[previous] = Some(whitespace_start..comment.end_byte());
+        } else if next == elements.len() || (previous.is_none() && !starts_line(text, start)) {
+            // Comments at the end of the array, or after its opening bracket, stay.
+        } else if starts_line(text, start) {
+            starts[next] = starts[next].min(start);
+        } else {
+            return None;
+        }
+    }
+
+    let mut edits = Vec::new();
+    for (ix, &from) in moved_from.iter().enumerate() {
+        if from == ix {
+            continue;
+        }
+        let element_end = elements[ix].end_byte();
+        edits.push((
+            starts[ix]..element_end,
+            text[starts[from]..elements[from].end_byte()].to_string(),
+        ));
+
+        let comment = trailing_comments[from]
+            .clone()
+            .map_or("", |range| &text[range]);
+        let comment_range = match trailing_comments[ix].clone() {
+            Some(range) => range,
+            None if ix + 1 < elements.len() => {
+                let comma = element_end + text[element_end..].find(',')?;
+                comma + 1..comma + 1
+            }
+            None => element_end..element_end,
+        };
+        if text[comment_range.clone()] != *comment {
+            edits.push((comment_range, comment.to_string()));
+        }
+    }
+    Some(edits)
+}
+
+/// The ranges to delete to remove the given children of an object or array, along
+/// with their commas. Children on lines of their own are removed with their line,
+/// keeping the comments on other lines. Returns `None` when all children are removed
+/// or the ranges would overlap.
+fn removal_ranges(
+    text: &str,
+    children: &[Node],
+    removed: &[usize],
+) -> Option<Vec<(Range<usize>, String)>> {
+    if removed.len() == children.len() && !removed.is_empty() {
+        return None;
+    }
+    let line_or_child_start = |child: &Node| {
+        let start = child.start_byte();
+        if starts_line(text, start) {
+            start - line_indent(text, start)
+        } else {
+            start
+        }
+    };
+
+    let mut ranges = Vec::new();
+    for &ix in removed {
+        let child = &children[ix];
+        if let Some(next_child) = children.get(ix + 1) {
+            ranges.push(line_or_child_start(child)..line_or_child_start(next_child));
+        } else {
+            // The last child has no comma of its own, so the one before it goes.
+            let previous_end = children[ix - 1].end_byte();
+            let comma = previous_end + text[previous_end..child.start_byte()].find(',')?;
+            if starts_line(text, child.start_byte()) {
+                ranges.push(comma..comma + 1);
+                ranges.push(line_or_child_start(child) - 1..child.end_byte());
+            } else {
+                ranges.push(comma..child.end_byte());
+            }
+        }
+    }
+    ranges.sort_by_key(|range| range.start);
+    if ranges
+        .windows(2)
+        .any(|ranges| ranges[0].end > ranges[1].start)
+    {
+        return None;
+    }
+    Some(
+        ranges
+            .into_iter()
+            .map(|range| (range, String::new()))
+            .collect(),
+    )
+}
+
+/// The children of a JSON object or array, without comments.
+fn json_children<'a>(node: Node<'a>) -> impl Iterator<Item = Node<'a>> {
+    (0..node.named_child_count())
+        .filter_map(move |ix| node.named_child(ix))
+        .filter(|child| child.kind() != "comment")
+}
+
+fn json_object_value<'a>(text: &str, object: Node<'a>, key: &str) -> Option<Node<'a>> {
+    if object.kind() != "object" {
+        return None;
+    }
+    json_children(object)
+        .filter(|child| child.kind() == "pair")
+        .find(|pair| {
+            pair.child_by_field_name("key")
+                .and_then(|key_node| text.get(key_node.byte_range()))
+                .map_or(false, |key_text| key_text == format!("\"{key}\""))
+        })?
+        .child_by_field_name("value")
+}
+
+/// The number of whitespace characters at the start of the line containing `offset`.
+fn line_indent(text: &str, offset: usize) -> usize {
+    let line_start = text[..offset].rfind('\n').map_or(0, |ix| ix + 1);
+    text[line_start..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .count()
+}
+
+/// Whether only whitespace precedes `offset` on its line.
+fn starts_line(text: &str, offset: usize) -> bool {
+    let line_start = text[..offset].rfind('\n').map_or(0, |ix| ix + 1);
+    text[line_start..offset].trim().is_empty()
+}
+
 fn to_pretty_json(value: &impl Serialize, indent_size: usize, indent_prefix_len: usize) -> String {
     const SPACES: [u8; 32] = [b' '; 32];
 
@@ -1468,6 +1877,201 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_setting_store_update_array(cx: &mut AppContext) {
+        let mut store = SettingsStore::new(cx);
+        store.register_setting::<ServerSettings>(cx);
+
+        // changed elements are edited in place
+        check_settings_update::<ServerSettings>(
+            &mut store,
+            r#"{
+                "servers": [
+                    // the build box
+                    { "host": "build", "port": 22 },
+                    {
+                        "host": "dev", // rarely used
+                        "projects": ["/code"]
+                    }
+                ]
+            }"#
+            .unindent(),
+            |settings| {
+                settings.servers[0].port = Some(2222);
+                settings.servers[1].nickname = Some("Dev".into());
+                settings.servers[1].projects.push("/srv".into());
+            },
+            r#"{
+                "servers": [
+                    // the build box
+                    { "host": "build", "port": 2222 },
+                    {
+                        "nickname": "Dev",
+                        "host": "dev", // rarely used
+                        "projects": ["/code", "/srv"]
+                    }
+                ]
+            }"#
+            .unindent(),
+            cx,
+        );
+
+        // elements appended and removed
+        check_settings_update::<ServerSettings>(
+            &mut store,
+            r#"{
+                "servers": [
+                    { "host": "a" }, // first
+                    { "host": "b" }
+                ]
+            }"#
+            .unindent(),
+            |settings| {
+                settings.servers.push(ServerEntry {
+                    host: "c".into(),
+                    ..Default::default()
+                })
+            },
+            r#"{
+                "servers": [
+                    { "host": "a" }, // first
+                    { "host": "b" },
+                    {
+                      "host": "c"
+                    }
+                ]
+            }"#
+            .unindent(),
+            cx,
+        );
+        check_settings_update::<ServerSettings>(
+            &mut store,
+            r#"{
+                "servers": [
+                    { "host": "a" }, // first
+                    { "host": "b", "nickname": "B" }, // second
+                    { "host": "c" }
+                ]
+            }"#
+            .unindent(),
+            |settings| {
+                settings.servers.remove(1);
+            },
+            r#"{
+                "servers": [
+                    { "host": "a" }, // first
+                    { "host": "c" }
+                ]
+            }"#
+            .unindent(),
+            cx,
+        );
+        check_settings_update::<ServerSettings>(
+            &mut store,
+            r#"{
+                "servers": [
+                    { "host": "a" }, // first
+                    { "host": "b", "nickname": "B" }
+                ]
+            }"#
+            .unindent(),
+            |settings| settings.servers[1].nickname = None,
+            r#"{
+                "servers": [
+                    { "host": "a" }, // first
+                    { "host": "b" }
+                ]
+            }"#
+            .unindent(),
+            cx,
+        );
+
+        // reordered elements keep their comments
+        check_settings_update::<ServerSettings>(
+            &mut store,
+            r#"{
+                "servers": [
+                    // the build box
+                    { "host": "build" },
+                    { "host": "dev" }, // rarely used
+                    { "host": "ci" }
+                ]
+            }"#
+            .unindent(),
+            |settings| settings.servers.swap(0, 1),
+            r#"{
+                "servers": [
+                    { "host": "dev" }, // rarely used
+                    // the build box
+                    { "host": "build" },
+                    { "host": "ci" }
+                ]
+            }"#
+            .unindent(),
+            cx,
+        );
+
+        // elements reordered within a nested array keep their comments
+        check_settings_update::<ServerSettings>(
+            &mut store,
+            r#"{
+                "servers": [
+                    {
+                        "host": "dev",
+                        "projects": [
+                            "/code", // main checkout
+                            "/srv"
+                        ]
+                    }
+                ]
+            }"#
+            .unindent(),
+            |settings| settings.servers[0].projects.swap(0, 1),
+            r#"{
+                "servers": [
+                    {
+                        "host": "dev",
+                        "projects": [
+                            "/srv",
+                            "/code" // main checkout
+                        ]
+                    }
+                ]
+            }"#
+            .unindent(),
+            cx,
+        );
+
+        // elements that were moved and changed replace the array
+        check_settings_update::<ServerSettings>(
+            &mut store,
+            r#"{
+                "servers": [
+                    { "host": "a" }, // first
+                    { "host": "b" }
+                ]
+            }"#
+            .unindent(),
+            |settings| {
+                settings.servers.swap(0, 1);
+                settings.servers[1].port = Some(2222);
+            },
+            r#"{
+                "servers": [
+                  {
+                    "host": "b"
+                  },
+                  {
+                    "host": "a",
+                    "port": 2222
+                  }
+                ]
+            }"#
+            .unindent(),
+            cx,
+        );
+    }
+
     fn check_settings_update<T: Settings>(
         store: &mut SettingsStore,
         old_json: String,
@@ -1593,4 +2197,31 @@ mod tests {
             sources.json_merge()
         }
     }
+
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+    struct ServerSettings {
+        #[serde(default)]
+        servers: Vec<ServerEntry>,
+    }
+
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+    struct ServerEntry {
+        host: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        nickname: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        port: Option<u16>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        projects: Vec<String>,
+    }
+
+    impl Settings for ServerSettings {
+        const KEY: Option<&'static str> = None;
+
+        type FileContent = Self;
+
+        fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+            sources.json_merge()
+        }
+    }
 }
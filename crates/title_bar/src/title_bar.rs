@@ -18,7 +18,7 @@ use gpui::{
     StatefulInteractiveElement, Styled, Subscription, View, ViewContext, VisualContext, WeakView,
 };
 use project::{Project, RepositoryEntry};
-use recent_projects::{OpenRemote, RecentProjects};
+use recent_projects::{latency_color, OpenRemote, RecentProjects};
 use rpc::proto::{self, DevServerStatus};
 use smallvec::SmallVec;
 use std::sync::Arc;
@@ -265,9 +265,17 @@ impl TitleBar {
     fn render_ssh_project_host(&self, cx: &mut ViewContext<Self>) -> Option<AnyElement> {
         let host = self.project.read(cx).ssh_connection_string(cx)?;
 
+        let latency = self.project.read(cx).ssh_latency(cx);
+
         let (indicator_color, meta) = match self.project.read(cx).ssh_connection_state(cx)? {
             remote::ConnectionState::Connecting => (Color::Info, format!("Connecting to: {host}")),
-            remote::ConnectionState::Connected => (Color::Success, format!("Connected to: {host}")),
+            remote::ConnectionState::Connected => match latency {
+                Some(latency) => (
+                    Color::Success,
+                    format!("Connected to: {host} ({} ms)", latency.as_millis()),
+                ),
+                None => (Color::Success, format!("Connected to: {host}")),
+            },
             remote::ConnectionState::HeartbeatMissed => (
                 Color::Warning,
                 format!("Connection attempt to {host} missed. Retrying..."),
@@ -276,6 +284,10 @@ impl TitleBar {
                 Color::Warning,
                 format!("Lost connection to {host}. Reconnecting..."),
             ),
+            remote::ConnectionState::Offline => (
+                Color::Warning,
+                format!("Offline from {host}. Changes will be sent when you reconnect."),
+            ),
             remote::ConnectionState::Disconnected => {
                 (Color::Error, format!("Disconnected from {host}"))
             }
@@ -288,6 +300,7 @@ impl TitleBar {
             remote::ConnectionState::Connected => Color::Default,
             remote::ConnectionState::HeartbeatMissed => Color::Warning,
             remote::ConnectionState::Reconnecting => Color::Warning,
+            remote::ConnectionState::Offline => Color::Warning,
             remote::ConnectionState::Disconnected => Color::Error,
         };
 
@@ -309,26 +322,35 @@ impl TitleBar {
             .child(Indicator::dot().color(indicator_color));
 
         Some(
-            div()
-                .relative()
+            h_flex()
+                .gap_0p5()
                 .child(
-                    IconButton::new("ssh-server-icon", IconName::Server)
-                        .icon_size(IconSize::Small)
-                        .shape(IconButtonShape::Square)
-                        .icon_color(icon_color)
-                        .tooltip(move |cx| {
-                            Tooltip::with_meta(
-                                "Remote Project",
-                                Some(&OpenRemote),
-                                meta.clone(),
-                                cx,
-                            )
-                        })
-                        .on_click(|_, cx| {
-                            cx.dispatch_action(OpenRemote.boxed_clone());
-                        }),
+                    div()
+                        .relative()
+                        .child(
+                            IconButton::new("ssh-server-icon", IconName::Server)
+                                .icon_size(IconSize::Small)
+                                .shape(IconButtonShape::Square)
+                                .icon_color(icon_color)
+                                .tooltip(move |cx| {
+                                    Tooltip::with_meta(
+                                        "Remote Project",
+                                        Some(&OpenRemote),
+                                        meta.clone(),
+                                        cx,
+                                    )
+                                })
+                                .on_click(|_, cx| {
+                                    cx.dispatch_action(OpenRemote.boxed_clone());
+                                }),
+                        )
+                        .child(indicator),
                 )
-                .child(indicator)
+                .children(latency.map(|latency| {
+                    Label::new(format!("{} ms", latency.as_millis()))
+                        .size(LabelSize::XSmall)
+                        .color(latency_color(latency))
+                }))
                 .into_any_element(),
         )
     }
@@ -369,6 +369,18 @@ define_connection! {
     sql!(
         ALTER TABLE ssh_projects RENAME COLUMN path TO paths;
     ),
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN pinned INTEGER DEFAULT 0;
+    ),
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN open_count INTEGER DEFAULT 1;
+    ),
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN branch TEXT DEFAULT NULL;
+    ),
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN display_name TEXT DEFAULT NULL;
+    ),
     ];
 }
 
@@ -545,6 +557,7 @@ impl WorkspaceDb {
         &self,
         ssh_project: &SerializedSshProject,
     ) -> Option<SerializedWorkspace> {
+        let ssh_project_id = ssh_project.id?;
         let (workspace_id, window_bounds, display, centered_layout, docks, window_id): (
             WorkspaceId,
             Option<SerializedWindowBounds>,
@@ -576,7 +589,7 @@ impl WorkspaceDb {
                 FROM workspaces
                 WHERE ssh_project_id = ?
             })
-            .and_then(|mut prepared_statement| (prepared_statement)(ssh_project.id.0))
+            .and_then(|mut prepared_statement| (prepared_statement)(ssh_project_id.0))
             .context("No workspaces found")
             .warn_on_err()
             .flatten()?;
@@ -713,9 +726,12 @@ impl WorkspaceDb {
                         .context("Updating workspace")?;
                     },
                     SerializedWorkspaceLocation::Ssh(ssh_project) => {
+                        let ssh_project_id = ssh_project
+                            .id
+                            .context("ssh project isn't saved in the database")?;
                         conn.exec_bound(sql!(
                             DELETE FROM workspaces WHERE ssh_project_id = ? AND workspace_id != ?
-                        ))?((ssh_project.id.0, workspace.id))
+                        ))?((ssh_project_id.0, workspace.id))
                         .context("clearing out old locations")?;
 
                         // Upsert
@@ -754,7 +770,7 @@ impl WorkspaceDb {
                                 timestamp = CURRENT_TIMESTAMP
                         ))?((
                             workspace.id,
-                            ssh_project.id.0,
+                            ssh_project_id.0,
                             workspace.docks,
                             workspace.session_id,
                             workspace.window_id
@@ -794,6 +810,23 @@ impl WorkspaceDb {
         }
     }
 
+    /// The display and bounds of the window an ssh project was last shown in, if it was opened before.
+    pub(crate) async fn ssh_project_window(
+        &self,
+        host: String,
+        port: Option<u16>,
+        paths: Vec<String>,
+        user: Option<String>,
+    ) -> Result<Option<(Uuid, SerializedWindowBounds)>> {
+        let paths = serde_json::to_string(&paths)?;
+        let Some(ssh_project) = self.get_ssh_project(host, port, paths, user).await? else {
+            return Ok(None);
+        };
+        Ok(self
+            .workspace_for_ssh_project(&ssh_project)
+            .and_then(|workspace| Some((workspace.display?, workspace.window_bounds?))))
+    }
+
     query! {
         async fn get_ssh_project(host: String, port: Option<u16>, paths: String, user: Option<String>) -> Result<Option<SerializedSshProject>> {
             SELECT id, host, port, paths, user
@@ -888,6 +921,78 @@ impl WorkspaceDb {
         }
     }
 
+    query! {
+        pub async fn set_workspace_pinned(workspace_id: WorkspaceId, pinned: bool) -> Result<()> {
+            UPDATE workspaces
+            SET pinned = ?2
+            WHERE workspace_id = ?1
+        }
+    }
+
+    query! {
+        pub async fn pinned_workspaces() -> Result<Vec<WorkspaceId>> {
+            SELECT workspace_id
+            FROM workspaces
+            WHERE pinned = 1
+        }
+    }
+
+    query! {
+        pub async fn workspace_timestamps() -> Result<Vec<(WorkspaceId, i64)>> {
+            // Seconds since the Unix epoch.
+            SELECT workspace_id, CAST((julianday(timestamp) - 2440587.5) * 86400 AS INTEGER)
+            FROM workspaces
+            WHERE timestamp IS NOT NULL
+        }
+    }
+
+    query! {
+        pub async fn increment_open_count(workspace_id: WorkspaceId) -> Result<()> {
+            UPDATE workspaces
+            SET open_count = open_count + 1
+            WHERE workspace_id = ?
+        }
+    }
+
+    query! {
+        pub async fn workspace_open_counts() -> Result<Vec<(WorkspaceId, u32)>> {
+            SELECT workspace_id, open_count
+            FROM workspaces
+        }
+    }
+
+    query! {
+        pub async fn set_workspace_branch(workspace_id: WorkspaceId, branch: Option<String>) -> Result<()> {
+            UPDATE workspaces
+            SET branch = ?2
+            WHERE workspace_id = ?1
+        }
+    }
+
+    query! {
+        pub async fn workspace_branches() -> Result<Vec<(WorkspaceId, String)>> {
+            SELECT workspace_id, branch
+            FROM workspaces
+            WHERE branch IS NOT NULL
+        }
+    }
+
+    query! {
+        pub async fn set_workspace_display_name(workspace_id: WorkspaceId, display_name: Option<String>) -> Result<()> {
+            UPDATE workspaces
+            SET display_name = ?2
+            WHERE workspace_id = ?1
+        }
+    }
+
+    query! {
+        pub async fn workspace_display_names() -> Result<Vec<(WorkspaceId, String)>> {
+            SELECT workspace_id, display_name
+            FROM workspaces
+            WHERE display_name IS NOT NULL
+        }
+    }
+
     pub async fn delete_workspace_by_dev_server_project_id(
         &self,
         id: DevServerProjectId,
@@ -904,10 +1009,72 @@ impl WorkspaceDb {
         .await
     }
 
-    // Returns the recent locations which are still valid on disk and deletes ones which no longer
-    // exist.
+    /// Records a workspace for the location without any open items, as if it had been opened
+    /// and closed, unless there already is one. Returns the id of the workspace.
+    ///
+    /// The id of an ssh project's location is ignored, as it may come from another database.
+    pub async fn import_workspace(
+        &self,
+        location: SerializedWorkspaceLocation,
+    ) -> Result<WorkspaceId> {
+        let location = match location {
+            SerializedWorkspaceLocation::Ssh(ssh_project) => SerializedWorkspaceLocation::Ssh(
+                self.get_or_create_ssh_project(
+                    ssh_project.host,
+                    ssh_project.port,
+                    ssh_project.paths,
+                    ssh_project.user,
+                )
+                .await?,
+            ),
+            location => location,
+        };
+        let existing_workspace = match &location {
+            SerializedWorkspaceLocation::Local(paths, _) => self.workspace_for_roots(paths.paths()),
+            SerializedWorkspaceLocation::Ssh(ssh_project) => {
+                self.workspace_for_ssh_project(ssh_project)
+            }
+            SerializedWorkspaceLocation::DevServer(dev_server_project) => {
+                self.workspace_for_dev_server_project(dev_server_project.id)
+            }
+        };
+        if let Some(workspace) = existing_workspace {
+            return Ok(workspace.id);
+        }
+
+        let id = self.next_id().await?;
+        self.save_workspace(SerializedWorkspace {
+            id,
+            location,
+            center_group: Default::default(),
+            window_bounds: None,
+            display: None,
+            docks: Default::default(),
+            centered_layout: false,
+            session_id: None,
+            window_id: None,
+        })
+        .await;
+        Ok(id)
+    }
+
+    // Returns the recent locations which are still valid on disk. Local workspaces whose folders
+    // no longer exist are kept in the database, so they can be shown as missing.
     pub async fn recent_workspaces_on_disk(
         &self,
+    ) -> Result<Vec<(WorkspaceId, SerializedWorkspaceLocation)>> {
+        let mut result = self.recent_workspaces_including_missing().await?;
+        result.retain(|(_, location)| match location {
+            SerializedWorkspaceLocation::Local(paths, _) => paths.exist_on_disk(),
+            _ => true,
+        });
+        Ok(result)
+    }
+
+    /// Like [`Self::recent_workspaces_on_disk`], but also lists local workspaces whose
+    /// folders were deleted or moved.
+    pub async fn recent_workspaces_including_missing(
+        &self,
     ) -> Result<Vec<(WorkspaceId, SerializedWorkspaceLocation)>> {
         let mut result = Vec::new();
         let mut delete_tasks = Vec::new();
@@ -930,7 +1097,9 @@ impl WorkspaceDb {
             }
 
             if let Some(ssh_project_id) = ssh_project_id.map(SshProjectId) {
-                if let Some(ssh_project) = ssh_projects.iter().find(|rp| rp.id == ssh_project_id) {
+                if let Some(ssh_project) =
+                    ssh_projects.iter().find(|rp| rp.id == Some(ssh_project_id))
+                {
                     result.push((id, SerializedWorkspaceLocation::Ssh(ssh_project.clone())));
                 } else {
                     delete_tasks.push(self.delete_workspace_by_id(id));
@@ -938,13 +1107,7 @@ impl WorkspaceDb {
                 continue;
             }
 
-            if location.paths().iter().all(|path| path.exists())
-                && location.paths().iter().any(|path| path.is_dir())
-            {
-                result.push((id, SerializedWorkspaceLocation::Local(location, order)));
-            } else {
-                delete_tasks.push(self.delete_workspace_by_id(id));
-            }
+            result.push((id, SerializedWorkspaceLocation::Local(location, order)));
         }
 
         futures::future::join_all(delete_tasks).await;
@@ -1623,7 +1786,7 @@ mod tests {
         let empty_paths: Vec<&str> = Vec::new();
         assert_eq!(locations[1].0, LocalPaths::new(empty_paths.iter()));
         assert_eq!(locations[1].1, Some(50));
-        assert_eq!(locations[1].2, Some(ssh_project.id.0));
+        assert_eq!(locations[1].2, ssh_project.id.map(|id| id.0));
     }
 
     fn default_workspace<P: AsRef<Path>>(
@@ -1897,6 +2060,106 @@ mod tests {
         assert_eq!(stored_projects.len(), projects.len());
     }
 
+    fn local_workspace<P: AsRef<Path>>(id: i64, paths: &[P]) -> SerializedWorkspace {
+        SerializedWorkspace {
+            id: WorkspaceId(id),
+            location: SerializedWorkspaceLocation::from_local_paths(paths),
+            center_group: Default::default(),
+            window_bounds: Default::default(),
+            display: Default::default(),
+            docks: Default::default(),
+            centered_layout: false,
+            session_id: None,
+            window_id: None,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_pinned_workspaces() {
+        let db = WorkspaceDb(open_test_db("test_pinned_workspaces").await);
+
+        db.save_workspace(local_workspace(1, &["/tmp1"])).await;
+        db.save_workspace(local_workspace(2, &["/tmp2"])).await;
+        assert_eq!(db.pinned_workspaces().await.unwrap(), Vec::new());
+
+        db.set_workspace_pinned(WorkspaceId(2), true).await.unwrap();
+        assert_eq!(db.pinned_workspaces().await.unwrap(), vec![WorkspaceId(2)]);
+
+        // Saving the workspace again keeps it pinned.
+        db.save_workspace(local_workspace(2, &["/tmp2"])).await;
+        assert_eq!(db.pinned_workspaces().await.unwrap(), vec![WorkspaceId(2)]);
+
+        db.set_workspace_pinned(WorkspaceId(2), false)
+            .await
+            .unwrap();
+        assert_eq!(db.pinned_workspaces().await.unwrap(), Vec::new());
+    }
+
+    #[gpui::test]
+    async fn test_import_workspace() {
+        let db = WorkspaceDb(open_test_db("test_import_workspace").await);
+
+        let local_id = db
+            .import_workspace(SerializedWorkspaceLocation::from_local_paths(["/tmp1"]))
+            .await
+            .unwrap();
+        let ssh_location = SerializedWorkspaceLocation::Ssh(SerializedSshProject {
+            id: None,
+            host: "example.com".to_string(),
+            port: Some(22),
+            paths: vec!["/home/user/code".to_string()],
+            user: None,
+        });
+        let ssh_id = db.import_workspace(ssh_location.clone()).await.unwrap();
+        assert_ne!(local_id, ssh_id);
+
+        // Importing a location again finds the workspace recorded the first time.
+        assert_eq!(
+            db.import_workspace(SerializedWorkspaceLocation::from_local_paths(["/tmp1"]))
+                .await
+                .unwrap(),
+            local_id
+        );
+        assert_eq!(db.import_workspace(ssh_location).await.unwrap(), ssh_id);
+    }
+
+    #[gpui::test]
+    async fn test_workspace_open_counts() {
+        let db = WorkspaceDb(open_test_db("test_workspace_open_counts").await);
+
+        db.save_workspace(local_workspace(1, &["/tmp1"])).await;
+        db.save_workspace(local_workspace(2, &["/tmp2"])).await;
+        db.increment_open_count(WorkspaceId(2)).await.unwrap();
+        db.increment_open_count(WorkspaceId(2)).await.unwrap();
+
+        let mut open_counts = db.workspace_open_counts().await.unwrap();
+        open_counts.sort();
+        assert_eq!(open_counts, vec![(WorkspaceId(1), 1), (WorkspaceId(2), 3)]);
+    }
+
+    #[gpui::test]
+    async fn test_workspace_display_names() {
+        let db = WorkspaceDb(open_test_db("test_workspace_display_names").await);
+
+        let workspace = local_workspace(1, &["/tmp"]);
+        db.save_workspace(workspace.clone()).await;
+        db.set_workspace_display_name(WorkspaceId(1), Some("API – staging".to_string()))
+            .await
+            .unwrap();
+
+        // Saving the workspace again keeps its name.
+        db.save_workspace(workspace).await;
+        assert_eq!(
+            db.workspace_display_names().await.unwrap(),
+            vec![(WorkspaceId(1), "API – staging".to_string())]
+        );
+
+        db.set_workspace_display_name(WorkspaceId(1), None)
+            .await
+            .unwrap();
+        assert_eq!(db.workspace_display_names().await.unwrap(), vec![]);
+    }
+
     #[gpui::test]
     async fn test_simple_split() {
         env_logger::try_init().ok();
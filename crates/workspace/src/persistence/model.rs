@@ -23,7 +23,8 @@ use uuid::Uuid;
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct SerializedSshProject {
-    pub id: SshProjectId,
+    /// The project's id in the database, if it was saved there.
+    pub id: Option<SshProjectId>,
     pub host: String,
     pub port: Option<u16>,
     pub paths: Vec<String>,
@@ -56,7 +57,7 @@ impl SerializedSshProject {
             host: self.host.clone(),
             username: self.user.clone(),
             port: self.port,
-            password: None,
+            ..Default::default()
         }
     }
 }
@@ -69,7 +70,7 @@ impl StaticColumnCount for SerializedSshProject {
 
 impl Bind for &SerializedSshProject {
     fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
-        let next_index = statement.bind(&self.id.0, start_index)?;
+        let next_index = statement.bind(&self.id.map(|id| id.0), start_index)?;
         let next_index = statement.bind(&self.host, next_index)?;
         let next_index = statement.bind(&self.port, next_index)?;
         let raw_paths = serde_json::to_string(&self.paths)?;
@@ -90,7 +91,7 @@ impl Column for SerializedSshProject {
 
         Ok((
             Self {
-                id: SshProjectId(id as u64),
+                id: Some(SshProjectId(id as u64)),
                 host,
                 port,
                 paths,
@@ -126,6 +127,11 @@ impl LocalPaths {
     pub fn paths(&self) -> &Arc<Vec<PathBuf>> {
         &self.0
     }
+
+    /// Whether all of the paths still exist, and at least one of them is a folder.
+    pub fn exist_on_disk(&self) -> bool {
+        self.0.iter().all(|path| path.exists()) && self.0.iter().any(|path| path.is_dir())
+    }
 }
 
 impl StaticColumnCount for LocalPaths {}
@@ -49,17 +49,18 @@ use node_runtime::NodeRuntime;
 use notifications::{simple_message_notification::MessageNotification, NotificationHandle};
 pub use pane::*;
 pub use pane_group::*;
+use persistence::{model::SerializedWorkspace, SerializedWindowBounds, DB};
 pub use persistence::{
-    model::{ItemId, LocalPaths, SerializedDevServerProject, SerializedWorkspaceLocation},
+    model::{
+        ItemId, LocalPaths, SerializedDevServerProject, SerializedSshProject,
+        SerializedWorkspaceLocation,
+    },
     WorkspaceDb, DB as WORKSPACE_DB,
 };
-use persistence::{
-    model::{SerializedSshProject, SerializedWorkspace},
-    SerializedWindowBounds, DB,
-};
 use postage::stream::Stream;
 use project::{
-    DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
+    DirectoryLister, PathKind, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree,
+    WorktreeId,
 };
 use release_channel::ReleaseChannel;
 use remote::{SshClientDelegate, SshConnectionOptions};
@@ -98,7 +99,8 @@ use ui::{
 use util::{maybe, ResultExt, TryFutureExt};
 use uuid::Uuid;
 pub use workspace_settings::{
-    AutosaveSetting, RestoreOnStartupBehavior, TabBarSettings, WorkspaceSettings,
+    AutosaveSetting, RecentProjectsSettings, RecentProjectsSortOrder, RestoreOnStartupBehavior,
+    TabBarSettings, WorkspaceSettings, WorkspaceTemplate,
 };
 
 use crate::notifications::NotificationId;
@@ -316,6 +318,7 @@ pub fn init_settings(cx: &mut AppContext) {
     ItemSettings::register(cx);
     PreviewTabsSettings::register(cx);
     TabBarSettings::register(cx);
+    RecentProjectsSettings::register(cx);
 }
 
 pub fn init(app_state: Arc<AppState>, cx: &mut AppContext) {
@@ -758,6 +761,7 @@ pub struct Workspace {
     bounds: Bounds<Pixels>,
     centered_layout: bool,
     bounds_save_task_queued: Option<Task<()>>,
+    saved_window_open_status: Option<(SerializedWindowBounds, Uuid)>,
     on_prompt_for_new_path: Option<PromptForNewPath>,
     on_prompt_for_open_path: Option<PromptForOpenPath>,
     render_disconnected_overlay:
@@ -991,18 +995,17 @@ impl Workspace {
                         .timer(Duration::from_millis(100))
                         .await;
                     this.update(&mut cx, |this, cx| {
-                        if let Some(display) = cx.display() {
-                            if let Ok(display_uuid) = display.uuid() {
-                                let window_bounds = cx.window_bounds();
-                                if let Some(database_id) = workspace_id {
-                                    cx.background_executor()
-                                        .spawn(DB.set_window_open_status(
-                                            database_id,
-                                            SerializedWindowBounds(window_bounds),
-                                            display_uuid,
-                                        ))
-                                        .detach_and_log_err(cx);
-                                }
+                        if let Some(database_id) = workspace_id {
+                            if let Some((window_bounds, display_uuid)) =
+                                this.changed_window_open_status(cx)
+                            {
+                                cx.background_executor()
+                                    .spawn(DB.set_window_open_status(
+                                        database_id,
+                                        window_bounds,
+                                        display_uuid,
+                                    ))
+                                    .detach_and_log_err(cx);
                             }
                         }
                         this.bounds_save_task_queued.take();
@@ -1041,6 +1044,11 @@ impl Workspace {
         cx.defer(|this, cx| {
             this.update_window_title(cx);
         });
+        if let Some(workspace_id) = workspace_id {
+            cx.background_executor()
+                .spawn(persistence::DB.increment_open_count(workspace_id))
+                .detach_and_log_err(cx);
+        }
         Workspace {
             weak_self: weak_handle.clone(),
             zoomed: None,
@@ -1077,6 +1085,7 @@ impl Workspace {
             bounds: Default::default(),
             centered_layout: false,
             bounds_save_task_queued: None,
+            saved_window_open_status: None,
             on_prompt_for_new_path: None,
             on_prompt_for_open_path: None,
             render_disconnected_overlay: None,
@@ -1794,7 +1803,7 @@ impl Workspace {
             .detach_and_log_err(cx);
     }
 
-    fn save_all_internal(
+    pub fn save_all_internal(
         &mut self,
         mut save_intent: SaveIntent,
         cx: &mut ViewContext<Self>,
@@ -1802,6 +1811,15 @@ impl Workspace {
         if self.project.read(cx).is_disconnected(cx) {
             return Task::ready(Ok(true));
         }
+        if save_intent == SaveIntent::Close && self.project.read(cx).has_offline_edits() {
+            let answer = cx.prompt(
+                PromptLevel::Warning,
+                "Discard the edits made while offline?",
+                Some("They can't be sent to the host until the connection is restored."),
+                &["Discard", "Cancel"],
+            );
+            return cx.spawn(|_, _| async move { Ok(answer.await? == 0) });
+        }
         let dirty_items = self
             .panes
             .iter()
@@ -4035,10 +4053,30 @@ impl Workspace {
         }
     }
 
-    fn serialize_workspace_internal(&self, cx: &mut WindowContext) -> Task<()> {
+    /// The window's bounds and display, if they differ from the ones saved last. They are
+    /// then considered saved.
+    fn changed_window_open_status(
+        &mut self,
+        cx: &WindowContext,
+    ) -> Option<(SerializedWindowBounds, Uuid)> {
+        let display = cx.display()?.uuid().ok()?;
+        let status = (SerializedWindowBounds(cx.window_bounds()), display);
+        if self.saved_window_open_status == Some(status) {
+            return None;
+        }
+        self.saved_window_open_status = Some(status);
+        Some(status)
+    }
+
+    fn serialize_workspace_internal(&mut self, cx: &mut WindowContext) -> Task<()> {
         let Some(database_id) = self.database_id() else {
             return Task::ready(());
         };
+        // While disconnected, the remote worktrees are gone and items can't be resolved to
+        // paths. Keep the last state saved while connected, so that reconnecting restores it.
+        if self.project.read(cx).is_via_ssh() && self.project.read(cx).is_disconnected(cx) {
+            return Task::ready(());
+        }
 
         fn serialize_pane_handle(pane_handle: &View<Pane>, cx: &WindowContext) -> SerializedPane {
             let (items, active, pinned_count) = {
@@ -4166,21 +4204,44 @@ impl Workspace {
         };
 
         if let Some(location) = location {
+            // Kept outside of the serialized workspace, only to be shown in recent projects.
+            let branch = self
+                .project()
+                .read(cx)
+                .visible_worktrees(cx)
+                .next()
+                .and_then(|worktree| worktree.read(cx).root_git_entry())
+                .and_then(|entry| entry.branch())
+                .map(|branch| branch.to_string());
             let center_group = build_serialized_pane_group(&self.center.root, cx);
             let docks = build_serialized_docks(self, cx);
-            let window_bounds = Some(SerializedWindowBounds(cx.window_bounds()));
+            let window_bounds = SerializedWindowBounds(cx.window_bounds());
+            let window_open_status = self.changed_window_open_status(cx);
             let serialized_workspace = SerializedWorkspace {
                 id: database_id,
                 location,
                 center_group,
-                window_bounds,
+                window_bounds: Some(window_bounds),
                 display: Default::default(),
                 docks,
                 centered_layout: self.centered_layout,
                 session_id: self.session_id.clone(),
                 window_id: Some(cx.window_handle().window_id().as_u64()),
             };
-            return cx.spawn(|_| persistence::DB.save_workspace(serialized_workspace));
+            return cx.spawn(|_| async move {
+                persistence::DB.save_workspace(serialized_workspace).await;
+                persistence::DB
+                    .set_workspace_branch(database_id, branch)
+                    .await
+                    .log_err();
+                // Windows that were never moved or resized have no bounds recorded yet.
+                if let Some((window_bounds, display)) = window_open_status {
+                    persistence::DB
+                        .set_window_open_status(database_id, window_bounds, display)
+                        .await
+                        .log_err();
+                }
+            });
         }
         Task::ready(())
     }
@@ -5520,58 +5581,174 @@ pub fn join_hosted_project(
     })
 }
 
+/// Options for a new window showing the given ssh project, placed where that project's window
+/// was last closed.
+pub async fn ssh_project_window_options(
+    connection_options: &SshConnectionOptions,
+    paths: &[PathBuf],
+    app_state: &AppState,
+    cx: &mut AsyncAppContext,
+) -> Result<WindowOptions> {
+    let (window_bounds, display) = if let Some(bounds) = window_bounds_env_override() {
+        (Some(WindowBounds::Windowed(bounds)), None)
+    } else {
+        let restorable_bounds = persistence::DB
+            .ssh_project_window(
+                connection_options.host.clone(),
+                connection_options.port,
+                paths
+                    .iter()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect(),
+                connection_options.username.clone(),
+            )
+            .await
+            .log_err()
+            .flatten()
+            .or_else(|| {
+                let (display, window_bounds) = DB.last_window().log_err()?;
+                Some((display?, window_bounds?))
+            });
+
+        if let Some((serialized_display, serialized_status)) = restorable_bounds {
+            (Some(serialized_status.0), Some(serialized_display))
+        } else {
+            (None, None)
+        }
+    };
+
+    let mut options = cx.update(|cx| (app_state.build_window_options)(display, cx))?;
+    options.window_bounds = window_bounds;
+    Ok(options)
+}
+
+/// Returned by [`open_ssh_project`] when paths to open don't exist on the server.
+#[derive(Debug)]
+pub struct RemotePathsNotFound {
+    pub host: String,
+    pub paths: Vec<PathBuf>,
+    /// The project connected to the server, to pass back to [`open_ssh_project`]
+    /// so that it doesn't connect again.
+    pub project: Model<Project>,
+}
+
+impl std::fmt::Display for RemotePathsNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let paths = self
+            .paths
+            .iter()
+            .map(|path| path.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let verb = if self.paths.len() == 1 { "does" } else { "do" };
+        write!(f, "{paths} {verb} not exist on {}", self.host)
+    }
+}
+
+impl std::error::Error for RemotePathsNotFound {}
+
+/// Connects to the server, unless `project` is already connected to it, and opens
+/// `paths` on it in `window`. Directories in `paths_to_create` are created on the
+/// server first; any other path that doesn't exist fails with [`RemotePathsNotFound`]
+/// before the workspace is built.
+#[allow(clippy::too_many_arguments)]
 pub fn open_ssh_project(
     window: WindowHandle<Workspace>,
     connection_options: SshConnectionOptions,
     delegate: Arc<dyn SshClientDelegate>,
     app_state: Arc<AppState>,
+    project: Option<Model<Project>>,
     paths: Vec<PathBuf>,
+    paths_to_create: Vec<PathBuf>,
     cx: &mut AppContext,
 ) -> Task<Result<()>> {
     let release_channel = ReleaseChannel::global(cx);
+    let host = connection_options.host.clone();
 
     cx.spawn(|mut cx| async move {
         let (serialized_ssh_project, workspace_id, serialized_workspace) =
             serialize_ssh_project(connection_options.clone(), paths.clone(), &cx).await?;
 
-        let identifier_prefix = match release_channel {
-            ReleaseChannel::Stable => None,
-            _ => Some(format!("{}-", release_channel.dev_name())),
+        let project = match project {
+            Some(project) => project,
+            None => {
+                let identifier_prefix = match release_channel {
+                    ReleaseChannel::Stable => None,
+                    _ => Some(format!("{}-", release_channel.dev_name())),
+                };
+                let unique_identifier = format!(
+                    "{}workspace-{}",
+                    identifier_prefix.unwrap_or_default(),
+                    workspace_id.0
+                );
+
+                let session = cx
+                    .update(|cx| {
+                        remote::SshRemoteClient::new(
+                            unique_identifier,
+                            connection_options,
+                            delegate,
+                            cx,
+                        )
+                    })?
+                    .await?;
+
+                cx.update(|cx| {
+                    project::Project::ssh(
+                        session,
+                        app_state.client.clone(),
+                        app_state.node_runtime.clone(),
+                        app_state.user_store.clone(),
+                        app_state.languages.clone(),
+                        app_state.fs.clone(),
+                        cx,
+                    )
+                })?
+            }
         };
-        let unique_identifier = format!(
-            "{}workspace-{}",
-            identifier_prefix.unwrap_or_default(),
-            workspace_id.0
-        );
 
-        let session = cx
-            .update(|cx| {
-                remote::SshRemoteClient::new(unique_identifier, connection_options, delegate, cx)
-            })?
-            .await?;
+        for path in paths_to_create {
+            let path = path.to_string_lossy().into_owned();
+            project
+                .update(&mut cx, |project, cx| project.create_directory(path, cx))?
+                .await?;
+        }
 
-        let project = cx.update(|cx| {
-            project::Project::ssh(
-                session,
-                app_state.client.clone(),
-                app_state.node_runtime.clone(),
-                app_state.user_store.clone(),
-                app_state.languages.clone(),
-                app_state.fs.clone(),
-                cx,
-            )
-        })?;
+        let mut missing_paths = Vec::new();
+        let mut path_kinds = Vec::new();
+        for path in &paths {
+            let path_kind = project
+                .update(&mut cx, |project, cx| {
+                    project.path_kind(path.to_string_lossy().into_owned(), cx)
+                })?
+                .await?;
+            match path_kind {
+                Some(path_kind) => path_kinds.push(path_kind),
+                None => missing_paths.push(path.clone()),
+            }
+        }
+        if !missing_paths.is_empty() {
+            return Err(RemotePathsNotFound {
+                host,
+                paths: missing_paths,
+                project,
+            }
+            .into());
+        }
 
         let mut project_paths_to_open = vec![];
         let mut project_path_errors = vec![];
 
-        for path in paths {
+        for (path, path_kind) in paths.into_iter().zip(path_kinds) {
             let result = cx
                 .update(|cx| Workspace::project_path_for_path(project.clone(), &path, true, cx))?
                 .await;
             match result {
+                // Files open in an editor, directories only as worktrees. The kind has to
+                // come from the server, as the path doesn't exist on this machine.
                 Ok((_, project_path)) => {
-                    project_paths_to_open.push((path.clone(), Some(project_path)));
+                    let project_path = (path_kind == PathKind::File).then_some(project_path);
+                    project_paths_to_open.push((path.clone(), project_path));
                 }
                 Err(error) => {
                     project_path_errors.push(error);
@@ -1,9 +1,13 @@
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use collections::HashMap;
 use gpui::AppContext;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
+use std::path::PathBuf;
+use util::paths::PathMatcher;
+
+use crate::{SerializedSshProject, SerializedWorkspaceLocation};
 
 #[derive(Deserialize)]
 pub struct WorkspaceSettings {
@@ -189,3 +193,140 @@ impl Settings for TabBarSettings {
         sources.json_merge()
     }
 }
+
+#[derive(Clone)]
+pub struct RecentProjectsSettings {
+    pub sort_order: RecentProjectsSortOrder,
+    pub exclude: PathMatcher,
+    pub max_history: Option<usize>,
+    pub prune_missing: bool,
+    pub templates: Vec<WorkspaceTemplate>,
+}
+
+impl RecentProjectsSettings {
+    /// Whether any of the workspace's paths matches one of the `exclude` globs.
+    pub fn is_excluded(&self, location: &SerializedWorkspaceLocation) -> bool {
+        match location {
+            SerializedWorkspaceLocation::Local(paths, _) => {
+                paths.paths().iter().any(|path| self.exclude.is_match(path))
+            }
+            SerializedWorkspaceLocation::DevServer(dev_server_project) => dev_server_project
+                .paths
+                .iter()
+                .any(|path| self.exclude.is_match(path.to_string())),
+            SerializedWorkspaceLocation::Ssh(ssh_project) => ssh_project
+                .paths
+                .iter()
+                .any(|path| self.exclude.is_match(path)),
+        }
+    }
+}
+
+/// A named set of folders that are opened together in one window.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct WorkspaceTemplate {
+    /// The name shown in the recent projects picker.
+    pub name: String,
+    /// The folders to open. These are on the server given by `host`, or on this
+    /// machine when no host is set.
+    pub paths: Vec<String>,
+    /// The ssh server the folders are on.
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+impl WorkspaceTemplate {
+    /// Where the template's folders are, as they would be saved once opened.
+    pub fn location(&self) -> SerializedWorkspaceLocation {
+        match &self.host {
+            Some(host) => SerializedWorkspaceLocation::Ssh(SerializedSshProject {
+                id: None,
+                host: host.clone(),
+                port: self.port,
+                paths: self.paths.clone(),
+                user: self.username.clone(),
+            }),
+            None => SerializedWorkspaceLocation::from_local_paths(self.paths.iter().map(|path| {
+                match path.strip_prefix("~/") {
+                    Some(relative_path) => util::paths::home_dir().join(relative_path),
+                    None if path == "~" => util::paths::home_dir().clone(),
+                    None => PathBuf::from(path),
+                }
+            })),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecentProjectsSortOrder {
+    /// The most recently opened projects first.
+    #[default]
+    Recency,
+    /// By the name of the project's first folder.
+    Alphabetical,
+    /// The most often opened projects first.
+    Frequency,
+}
+
+impl RecentProjectsSortOrder {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Recency => Self::Alphabetical,
+            Self::Alphabetical => Self::Frequency,
+            Self::Frequency => Self::Recency,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct RecentProjectsSettingsContent {
+    /// How to order the projects in the recent projects picker.
+    ///
+    /// Default: recency
+    pub sort_order: Option<RecentProjectsSortOrder>,
+    /// Globs matching folders whose workspaces aren't listed as recent
+    /// projects. They are still saved and restored.
+    ///
+    /// Default: []
+    pub exclude: Option<Vec<String>>,
+    /// How many recent projects to keep, not counting pinned ones. Older
+    /// projects are forgotten at startup and whenever this setting changes.
+    /// When unset, all of them are kept.
+    ///
+    /// Default: null
+    pub max_history: Option<usize>,
+    /// Whether to forget local projects whose folders no longer exist, rather
+    /// than listing them grayed out.
+    ///
+    /// Default: false
+    pub prune_missing: Option<bool>,
+    /// Named sets of folders, listed at the top of the recent projects picker
+    /// and opened together in one window. A template's folders are either all
+    /// on this machine or all on the same ssh server.
+    ///
+    /// Default: []
+    pub templates: Option<Vec<WorkspaceTemplate>>,
+}
+
+impl Settings for RecentProjectsSettings {
+    const KEY: Option<&'static str> = Some("recent_projects");
+
+    type FileContent = RecentProjectsSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        let content: RecentProjectsSettingsContent = sources.json_merge()?;
+        Ok(Self {
+            sort_order: content.sort_order.unwrap_or_default(),
+            exclude: PathMatcher::new(&content.exclude.unwrap_or_default())
+                .context("Failed to parse globs from recent_projects.exclude")?,
+            max_history: content.max_history,
+            prune_missing: content.prune_missing.unwrap_or_default(),
+            templates: content.templates.unwrap_or_default(),
+        })
+    }
+}
@@ -363,7 +363,30 @@ impl Worktree {
         next_entry_id: Arc<AtomicUsize>,
         cx: &mut AsyncAppContext,
     ) -> Result<Model<Self>> {
-        let abs_path = path.into();
+        Self::new_local(path.into(), None, visible, fs, next_entry_id, cx).await
+    }
+
+    /// Like [`Worktree::local`], but with the given id instead of one derived
+    /// from the model, e.g. to recreate a worktree that a client already knows.
+    pub async fn local_with_id(
+        path: impl Into<Arc<Path>>,
+        id: WorktreeId,
+        visible: bool,
+        fs: Arc<dyn Fs>,
+        next_entry_id: Arc<AtomicUsize>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Model<Self>> {
+        Self::new_local(path.into(), Some(id), visible, fs, next_entry_id, cx).await
+    }
+
+    async fn new_local(
+        abs_path: Arc<Path>,
+        id: Option<WorktreeId>,
+        visible: bool,
+        fs: Arc<dyn Fs>,
+        next_entry_id: Arc<AtomicUsize>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Model<Self>> {
         let metadata = fs
             .metadata(&abs_path)
             .await
@@ -381,7 +404,7 @@ impl Worktree {
                 ignores_by_parent_abs_path: Default::default(),
                 git_repositories: Default::default(),
                 snapshot: Snapshot::new(
-                    cx.entity_id().as_u64(),
+                    id.map_or(cx.entity_id().as_u64(), |id| id.to_proto()),
                     abs_path
                         .file_name()
                         .map_or(String::new(), |f| f.to_string_lossy().to_string()),
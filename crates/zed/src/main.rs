@@ -26,14 +26,13 @@ use gpui::{
 use http_client::{read_proxy_from_env, Uri};
 use language::LanguageRegistry;
 use log::LevelFilter;
-use remote::SshConnectionOptions;
 use reqwest_client::ReqwestClient;
 
 use assets::Assets;
 use node_runtime::{NodeBinaryOptions, NodeRuntime};
 use parking_lot::Mutex;
 use project::project_settings::ProjectSettings;
-use recent_projects::open_ssh_project;
+use recent_projects::{open_ssh_project, SshSettings};
 use release_channel::{AppCommitSha, AppVersion};
 use session::{AppSession, Session};
 use settings::{
@@ -882,12 +881,13 @@ async fn restore_or_create_workspace(
                     task.await?;
                 }
                 SerializedWorkspaceLocation::Ssh(ssh_project) => {
-                    let connection_options = SshConnectionOptions {
-                        host: ssh_project.host.clone(),
-                        username: ssh_project.user.clone(),
-                        port: ssh_project.port,
-                        password: None,
-                    };
+                    let connection_options = cx.update(|cx| {
+                        SshSettings::get_global(cx).connection_options_for(
+                            ssh_project.host.clone(),
+                            ssh_project.port,
+                            ssh_project.user.clone(),
+                        )
+                    })?;
                     let app_state = app_state.clone();
                     cx.spawn(move |mut cx| async move {
                         recent_projects::open_ssh_project(
@@ -243,6 +243,11 @@ pub fn initialize_workspace(
                 workspace_handle.clone(),
                 cx.clone(),
             );
+            let recent_projects_panel =
+                recent_projects::recent_projects_panel::RecentProjectsPanel::load(
+                    workspace_handle.clone(),
+                    cx.clone(),
+                );
 
             let (
                 project_panel,
@@ -252,6 +257,7 @@ pub fn initialize_workspace(
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                recent_projects_panel,
             ) = futures::try_join!(
                 project_panel,
                 outline_panel,
@@ -260,6 +266,7 @@ pub fn initialize_workspace(
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                recent_projects_panel,
             )?;
 
             workspace_handle.update(&mut cx, |workspace, cx| {
@@ -270,6 +277,7 @@ pub fn initialize_workspace(
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
+                workspace.add_panel(recent_projects_panel, cx);
                 cx.focus_self();
             })
         })
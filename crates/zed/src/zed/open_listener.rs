@@ -18,6 +18,7 @@ use gpui::{AppContext, AsyncAppContext, Global, WindowHandle};
 use language::{Bias, Point};
 use recent_projects::open_ssh_project;
 use remote::SshConnectionOptions;
+use settings::Settings;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
@@ -26,7 +27,10 @@ use util::paths::PathWithPosition;
 use util::ResultExt;
 use welcome::{show_welcome_view, FIRST_OPEN};
 use workspace::item::ItemHandle;
-use workspace::{AppState, OpenOptions, SerializedWorkspaceLocation, Workspace};
+use workspace::{
+    AppState, OpenOptions, RecentProjectsSettings, SerializedWorkspaceLocation, Workspace,
+    WORKSPACE_DB,
+};
 
 #[derive(Default, Debug)]
 pub struct OpenRequest {
@@ -66,30 +70,21 @@ impl OpenRequest {
     }
 
     fn parse_ssh_file_path(&mut self, file: &str) -> Result<()> {
-        let url = url::Url::parse(file)?;
-        let host = url
-            .host()
-            .ok_or_else(|| anyhow!("missing host in ssh url: {}", file))?
-            .to_string();
-        let username = Some(url.username().to_string()).filter(|s| !s.is_empty());
-        let password = url.password().map(|s| s.to_string());
-        let port = url.port();
+        let (connection, paths) = SshConnectionOptions::parse_ssh_url(file)?;
         if !self.open_paths.is_empty() {
             return Err(anyhow!("cannot open both local and ssh paths"));
         }
-        let connection = SshConnectionOptions {
-            username,
-            password,
-            host,
-            port,
-        };
         if let Some(ssh_connection) = &self.ssh_connection {
             if *ssh_connection != connection {
                 return Err(anyhow!("cannot open multiple ssh connections"));
             }
         }
         self.ssh_connection = Some(connection);
-        self.parse_file_path(url.path());
+        self.open_paths.extend(
+            paths
+                .into_iter()
+                .map(|path| path.to_string_lossy().into_owned()),
+        );
         Ok(())
     }
 
@@ -344,10 +339,62 @@ pub async fn handle_cli_connection(
                 let status = if open_workspace_result.is_err() { 1 } else { 0 };
                 responses.send(CliResponse::Exit { status }).log_err();
             }
+            CliRequest::ListRecent => {
+                let status = match list_recent_projects(&responses, &mut cx).await {
+                    Ok(()) => 0,
+                    Err(error) => {
+                        responses
+                            .send(CliResponse::Stderr {
+                                message: format!("{error}"),
+                            })
+                            .log_err();
+                        1
+                    }
+                };
+                responses.send(CliResponse::Exit { status }).log_err();
+            }
         }
     }
 }
 
+/// Prints a line per recent local or ssh project, with its paths or ssh:// URLs
+/// separated by tabs.
+async fn list_recent_projects(
+    responses: &IpcSender<CliResponse>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let workspaces = WORKSPACE_DB.recent_workspaces_on_disk().await?;
+    let workspaces = cx.update(|cx| {
+        // Zed may have been launched only to answer this, without setting up its UI.
+        RecentProjectsSettings::register(cx);
+        let settings = RecentProjectsSettings::get_global(cx);
+        workspaces
+            .into_iter()
+            .filter(|(_, location)| !settings.is_excluded(location))
+            .collect::<Vec<_>>()
+    })?;
+    for (_, location) in workspaces {
+        let paths = match location {
+            SerializedWorkspaceLocation::Local(paths, order) => order
+                .order()
+                .iter()
+                .filter_map(|i| paths.paths().get(*i))
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>(),
+            SerializedWorkspaceLocation::Ssh(ssh_project) => ssh_project
+                .ssh_urls()
+                .iter()
+                .map(|url| format!("ssh://{}", url.to_string_lossy()))
+                .collect(),
+            SerializedWorkspaceLocation::DevServer(_) => continue,
+        };
+        responses.send(CliResponse::Stdout {
+            message: paths.join("\t"),
+        })?;
+    }
+    Ok(())
+}
+
 async fn open_workspaces(
     paths: Vec<String>,
     open_new_workspace: Option<bool>,
@@ -580,7 +627,10 @@ mod tests {
     use serde_json::json;
     use workspace::{AppState, Workspace};
 
-    use crate::zed::{open_listener::open_local_workspace, tests::init_test};
+    use crate::zed::{
+        open_listener::{open_local_workspace, OpenRequest},
+        tests::init_test,
+    };
 
     #[gpui::test]
     async fn test_open_workspace_with_directory(cx: &mut TestAppContext) {
@@ -683,6 +733,19 @@ mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    fn test_parse_ssh_url(cx: &mut TestAppContext) {
+        let request = cx.update(|cx| {
+            OpenRequest::parse(vec!["ssh://me@example.com:2222/~/project".into()], cx).unwrap()
+        });
+
+        let connection = request.ssh_connection.unwrap();
+        assert_eq!(connection.host, "example.com");
+        assert_eq!(connection.username.as_deref(), Some("me"));
+        assert_eq!(connection.port, Some(2222));
+        assert_eq!(request.open_paths, vec!["~/project".to_string()]);
+    }
+
     async fn open_workspace_file(
         path: &str,
         open_new_workspace: Option<bool>,